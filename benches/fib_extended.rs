@@ -0,0 +1,51 @@
+//! Instructions per second for `programs/fib_extended.nca`, with and without
+//! fast mode. Run with `cargo bench`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use nanocore::{assembler::Assembler, nanocore::NanoCore};
+
+const RUNS: u32 = 20_000;
+
+/// Runs the program `RUNS` times on a fresh core, as a batch grader would,
+/// returning the instructions executed and the time taken.
+fn bench(program: &[u8], fast: bool) -> (u64, Duration) {
+    let mut instructions = 0;
+    let start = Instant::now();
+
+    for _ in 0..RUNS {
+        let mut nano = NanoCore::new().with_max_cycles(u64::MAX);
+        if fast {
+            nano = nano.with_fast_mode();
+        }
+        nano.load_program(black_box(program), 0).unwrap();
+        nano.run().unwrap();
+        instructions += nano.cycle;
+        black_box(&nano.output);
+    }
+
+    (instructions, start.elapsed())
+}
+
+fn main() {
+    let source = include_str!("../programs/fib_extended.nca");
+    let mut assembler = Assembler::default();
+    assembler.assemble(source).unwrap();
+
+    // Warm up.
+    bench(&assembler.program, false);
+
+    println!("fib_extended.nca, {RUNS} runs");
+    for (name, fast) in [("normal", false), ("fast", true)] {
+        let (instructions, elapsed) = bench(&assembler.program, fast);
+        let ips = instructions as f64 / elapsed.as_secs_f64();
+        println!(
+            "{name:>8}: {instructions} instructions in {:>8.2?} ({:>6.2} M instructions/s)",
+            elapsed,
+            ips / 1e6
+        );
+    }
+}
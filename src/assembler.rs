@@ -14,9 +14,9 @@
 //! language programming.
 //!
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::Op;
+use crate::{Op, banking::BankWindow};
 
 #[derive(Default)]
 pub struct Assembler {
@@ -24,6 +24,23 @@ pub struct Assembler {
     pub labels: HashMap<String, u8>,
     pub constants: HashMap<String, u8>,
     pub program: Vec<u8>,
+    /// Source line (1-based) of the instruction or data at each address of
+    /// the main program. Banked code is not mapped.
+    pub line_map: BTreeMap<u8, usize>,
+    /// Contents of each bank declared with `.BANK n`, addressed from
+    /// [`BankWindow::BASE`].
+    pub banks: Vec<Vec<u8>>,
+    /// Labels exported with `.GLOBAL` for other modules to link against.
+    pub globals: Vec<String>,
+    /// Symbols imported with `.EXTERN`, with the line declaring each. Jumps
+    /// and calls to them are assembled with a `0x00` target for the linker
+    /// to fill in.
+    pub externs: BTreeMap<String, usize>,
+    /// Label or external symbol named by the jump or call target at each
+    /// address of the main program. These are the bytes the linker
+    /// relocates.
+    pub references: BTreeMap<u8, String>,
+    current_bank: Option<(usize, usize)>,
 }
 
 impl Assembler {
@@ -33,7 +50,11 @@ impl Assembler {
         self.map_constants()?;
         self.map_labels()?;
 
-        let lines = self.asm.lines();
+        self.current_bank = None;
+
+        // Cloned so that emitting bytes can borrow `self` mutably.
+        let asm = self.asm.clone();
+        let lines = asm.lines();
 
         for (line_idx, line) in lines.enumerate() {
             let line_num = line_idx + 1;
@@ -49,14 +70,41 @@ impl Assembler {
                 continue;
             }
 
-            if Self::is_label(line) || Self::is_constant(line) {
+            if Self::is_label(line)
+                || Self::is_constant(line)
+                || Self::is_global(line)
+                || Self::is_extern(line)
+            {
                 continue;
             }
 
+            if Self::is_bank(line) {
+                self.check_bank_size()?;
+
+                let bank = self.bank_number(line, line_num)?;
+                if self.banks.get(bank).is_some_and(|b| !b.is_empty()) {
+                    return Err(crate::AssemblerError::SyntaxError {
+                        line: line_num,
+                        message: format!("Bank {} is already defined", bank),
+                    });
+                }
+                if self.banks.len() <= bank {
+                    self.banks.resize(bank + 1, Vec::new());
+                }
+
+                self.current_bank = Some((bank, line_num));
+                continue;
+            }
+
+            if self.current_bank.is_none() {
+                // A line that emits nothing is replaced by the next one.
+                self.line_map.insert(self.program.len() as u8, line_num);
+            }
+
             if line.starts_with(".DB") {
                 let parts = line.split_whitespace().collect::<Vec<&str>>();
                 for part in parts.iter().skip(1) {
-                    self.program.push(self.resolve_number(part, line_num)?);
+                    self.emit(self.resolve_number(part, line_num)?);
                 }
                 continue;
             }
@@ -72,7 +120,7 @@ impl Assembler {
                 })?;
                 let content = &line[start..end];
                 for byte in content.bytes() {
-                    self.program.push(byte);
+                    self.emit(byte);
                 }
                 continue;
             }
@@ -96,29 +144,36 @@ impl Assembler {
             let opcode: u8 = op.into();
 
             match op {
-                Op::HLT | Op::RET => self.program.push(opcode),
+                Op::HLT | Op::RET | Op::EI | Op::DI | Op::RETI => self.emit(opcode),
                 Op::NOP => {}
-                Op::LDI | Op::ADDI | Op::SUBI | Op::MULI | Op::DIVI | Op::MODI => {
+                Op::LDI
+                | Op::ADDI
+                | Op::SUBI
+                | Op::MULI
+                | Op::DIVI
+                | Op::MODI
+                | Op::ADCI
+                | Op::SBCI => {
                     if parts.len() < 3 {
                         return Err(crate::AssemblerError::SyntaxError {
                             line: line_num,
                             message: format!("{} requires 2 arguments", op),
                         });
                     }
-                    self.program.push(opcode);
-                    self.program.push(Self::register(parts[1], line_num)?);
-                    self.program.push(self.resolve_number(parts[2], line_num)?);
+                    self.emit(opcode);
+                    self.emit(Self::register(parts[1], line_num)?);
+                    self.emit(self.resolve_number(parts[2], line_num)?);
                 }
-                Op::LDA | Op::STORE => {
+                Op::LDA | Op::STORE | Op::TAS => {
                     if parts.len() < 3 {
                         return Err(crate::AssemblerError::SyntaxError {
                             line: line_num,
                             message: format!("{} requires 2 arguments", op),
                         });
                     }
-                    self.program.push(opcode);
-                    self.program.push(Self::register(parts[1], line_num)?);
-                    self.program.push(self.resolve_number(parts[2], line_num)?);
+                    self.emit(opcode);
+                    self.emit(Self::register(parts[1], line_num)?);
+                    self.emit(self.resolve_number(parts[2], line_num)?);
                 }
                 Op::LDR
                 | Op::MOV
@@ -131,6 +186,10 @@ impl Assembler {
                 | Op::MUL
                 | Op::DIV
                 | Op::MOD
+                | Op::ADC
+                | Op::SBC
+                | Op::IDIV
+                | Op::IMOD
                 | Op::STR => {
                     if parts.len() < 3 {
                         return Err(crate::AssemblerError::SyntaxError {
@@ -138,8 +197,8 @@ impl Assembler {
                             message: format!("{} requires 2 arguments", op),
                         });
                     }
-                    self.program.push(opcode);
-                    self.program.push(
+                    self.emit(opcode);
+                    self.emit(
                         Self::register(parts[1], line_num)? << 4
                             | Self::register(parts[2], line_num)?,
                     );
@@ -153,6 +212,8 @@ impl Assembler {
                 | Op::SHR
                 | Op::ROL
                 | Op::ROR
+                | Op::RCL
+                | Op::RCR
                 | Op::IN
                 | Op::JMPR
                 | Op::CALLR
@@ -163,34 +224,60 @@ impl Assembler {
                             message: format!("{} requires 1 argument", op),
                         });
                     }
-                    self.program.push(opcode);
-                    self.program.push(Self::register(parts[1], line_num)?);
+                    self.emit(opcode);
+                    self.emit(Self::register(parts[1], line_num)?);
                 }
-                Op::JMP | Op::JZ | Op::JNZ | Op::CALL => {
+                Op::JMP
+                | Op::CALL
+                | Op::JZ
+                | Op::JNZ
+                | Op::JC
+                | Op::JNC
+                | Op::JN
+                | Op::JNN
+                | Op::JLT
+                | Op::JGE
+                | Op::JGT
+                | Op::JLE
+                | Op::JLTS
+                | Op::JGES
+                | Op::JGTS
+                | Op::JLES
+                | Op::JV
+                | Op::JNV => {
                     if parts.len() < 2 {
                         return Err(crate::AssemblerError::SyntaxError {
                             line: line_num,
                             message: format!("{} requires 1 argument", op),
                         });
                     }
-                    let addr = if self.labels.contains_key(parts[1]) {
-                        *self.labels.get(parts[1]).unwrap()
+                    let (addr, symbol) = if let Some(&addr) = self.labels.get(parts[1]) {
+                        (addr, true)
+                    } else if self.externs.contains_key(parts[1]) {
+                        (0, true)
                     } else {
-                        self.resolve_number(parts[1], line_num)?
+                        (self.resolve_number(parts[1], line_num)?, false)
                     };
 
-                    self.program.push(opcode);
-                    self.program.push(addr);
+                    self.emit(opcode);
+                    if symbol && self.current_bank.is_none() {
+                        self.references
+                            .insert(self.program.len() as u8, parts[1].to_owned());
+                    }
+                    self.emit(addr);
                 }
             }
         }
-        Ok(())
+
+        self.check_bank_size()?;
+        self.check_main_size()
     }
 
     pub fn map_labels(&mut self) -> crate::AssemblerResult<()> {
         let lines = self.asm.lines();
 
         let mut addr: u8 = 0;
+        let mut globals = Vec::new();
 
         for (line_idx, line) in lines.enumerate() {
             let line_num = line_idx + 1;
@@ -207,6 +294,23 @@ impl Assembler {
                 continue;
             }
 
+            if Self::is_bank(line) {
+                addr = BankWindow::BASE;
+                continue;
+            }
+
+            if Self::is_global(line) {
+                globals.extend(line.split_whitespace().skip(1).map(|name| (name, line_num)));
+                continue;
+            }
+
+            if Self::is_extern(line) {
+                for name in line.split_whitespace().skip(1) {
+                    self.externs.insert(name.to_owned(), line_num);
+                }
+                continue;
+            }
+
             if line.starts_with(".DB") {
                 let parts = line.split_whitespace().collect::<Vec<&str>>();
                 addr = addr.wrapping_add((parts.len() - 1) as u8);
@@ -250,9 +354,43 @@ impl Assembler {
 
             addr = addr.wrapping_add(op.instruction_len());
         }
+
+        for (name, line) in globals {
+            if !self.labels.contains_key(name) {
+                return Err(crate::AssemblerError::UndefinedLabel {
+                    label: name.to_owned(),
+                    line,
+                });
+            }
+            if !self.globals.iter().any(|g| g == name) {
+                self.globals.push(name.to_owned());
+            }
+        }
+        if let Some((name, &line)) = self
+            .externs
+            .iter()
+            .find(|(e, _)| self.labels.contains_key(*e))
+        {
+            return Err(crate::AssemblerError::SyntaxError {
+                line,
+                message: format!("'{}' is declared .EXTERN but defined here", name),
+            });
+        }
         Ok(())
     }
 
+    /// Fails if the program imports `.EXTERN` symbols, which only the linker
+    /// can resolve.
+    pub fn check_linked(&self) -> crate::AssemblerResult<()> {
+        match self.externs.iter().next() {
+            Some((name, &line)) => Err(crate::AssemblerError::UnresolvedExtern {
+                name: name.clone(),
+                line,
+            }),
+            None => Ok(()),
+        }
+    }
+
     pub fn map_constants(&mut self) -> crate::AssemblerResult<()> {
         let lines = self.asm.lines();
 
@@ -291,6 +429,66 @@ impl Assembler {
         Ok(())
     }
 
+    /// Appends a byte to the current bank, or to the main program if no
+    /// `.BANK` directive has been seen.
+    fn emit(&mut self, byte: u8) {
+        match self.current_bank {
+            Some((bank, _)) => self.banks[bank].push(byte),
+            None => self.program.push(byte),
+        }
+    }
+
+    /// Fails if the current bank has outgrown the bank window.
+    fn check_bank_size(&self) -> crate::AssemblerResult<()> {
+        if let Some((bank, line)) = self.current_bank
+            && self.banks[bank].len() > BankWindow::SIZE
+        {
+            return Err(crate::AssemblerError::SyntaxError {
+                line,
+                message: format!(
+                    "Bank {} is {} bytes, larger than the {}-byte bank window",
+                    bank,
+                    self.banks[bank].len(),
+                    BankWindow::SIZE
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Fails if the program uses banks and the main program reaches into the
+    /// bank window, where the selected bank would hide it.
+    fn check_main_size(&self) -> crate::AssemblerResult<()> {
+        if !self.banks.is_empty() && self.program.len() > BankWindow::BASE as usize {
+            let line = self
+                .line_map
+                .range(..=BankWindow::BASE)
+                .next_back()
+                .map_or(0, |(_, &line)| line);
+            return Err(crate::AssemblerError::SyntaxError {
+                line,
+                message: format!(
+                    "Main program is {} bytes and runs into the bank window at {:#04X}",
+                    self.program.len(),
+                    BankWindow::BASE
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    fn bank_number(&self, line: &str, line_num: usize) -> crate::AssemblerResult<usize> {
+        let parts = line.split_whitespace().collect::<Vec<&str>>();
+        if parts.len() < 2 {
+            return Err(crate::AssemblerError::SyntaxError {
+                line: line_num,
+                message: ".BANK requires a bank number".to_string(),
+            });
+        }
+
+        Ok(self.resolve_number(parts[1], line_num)? as usize)
+    }
+
     pub fn resolve_number(&self, v: &str, line: usize) -> crate::AssemblerResult<u8> {
         if let Some(value) = self.constants.get(v) {
             return Ok(*value);
@@ -360,6 +558,18 @@ impl Assembler {
         l.starts_with(".CONST")
     }
 
+    pub fn is_bank(l: &str) -> bool {
+        l.starts_with(".BANK")
+    }
+
+    pub fn is_global(l: &str) -> bool {
+        l.starts_with(".GLOBAL")
+    }
+
+    pub fn is_extern(l: &str) -> bool {
+        l.starts_with(".EXTERN")
+    }
+
     pub fn is_comment(l: &str) -> bool {
         l.starts_with(";")
     }
@@ -610,6 +820,19 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_assemble_interrupts() {
+        let mut c = Assembler::default();
+        c.assemble(
+            "EI
+             DI
+             RETI",
+        )
+        .unwrap();
+
+        assert_eq!(&c.program, &[Op::EI.into(), Op::DI.into(), Op::RETI.into()])
+    }
+
     #[test]
     fn test_assemble_str() {
         let mut c = Assembler::default();
@@ -617,4 +840,78 @@ mod tests {
 
         assert_eq!(&c.program, &[Op::STR.into(), 0x01])
     }
+
+    #[test]
+    fn test_assemble_banks() {
+        let mut c = Assembler::default();
+        c.assemble(
+            "CALL second
+             HLT
+             .BANK 1
+             first:
+             RET
+             .BANK 0
+             .DB 0xAA
+             second:
+             RET",
+        )
+        .unwrap();
+
+        assert_eq!(&c.program, &[Op::CALL.into(), 0x61, Op::HLT.into()]);
+        assert_eq!(
+            c.banks,
+            vec![vec![0xAA, Op::RET.into()], vec![Op::RET.into()]]
+        );
+        assert_eq!(c.labels["first"], BankWindow::BASE);
+    }
+
+    #[test]
+    fn test_assemble_bank_overflow() {
+        let mut c = Assembler::default();
+        let data = vec!["0x00"; BankWindow::SIZE + 1].join(" ");
+        let err = c.assemble(&format!(".BANK 0\n.DB {data}")).unwrap_err();
+
+        assert!(matches!(
+            err,
+            crate::AssemblerError::SyntaxError { line: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_assemble_main_overlapping_bank_window() {
+        let data = vec!["0x00"; BankWindow::BASE as usize - 1].join(" ");
+
+        // Ends exactly where the window starts.
+        let mut c = Assembler::default();
+        c.assemble(&format!(
+            ".DB {data}
+HLT
+.BANK 0
+RET"
+        ))
+        .unwrap();
+
+        // Straddles the window start.
+        let mut c = Assembler::default();
+        let err = c
+            .assemble(&format!(
+                ".DB {data}
+LDI R0 1
+.BANK 0
+RET"
+            ))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            crate::AssemblerError::SyntaxError { line: 2, .. }
+        ));
+
+        // Without banks the whole address space is main memory.
+        let mut c = Assembler::default();
+        c.assemble(&format!(
+            ".DB {data}
+LDI R0 1"
+        ))
+        .unwrap();
+    }
 }
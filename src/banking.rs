@@ -0,0 +1,336 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    FormatError, FormatResult,
+    bus::{Bus, Device},
+    codec::Reader,
+    nanocore::NanoCore,
+};
+
+/// The pages behind the bank window and the currently selected one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Banks {
+    /// One page per bank, each as large as the window.
+    pub pages: Vec<Vec<u8>>,
+    /// Index of the page visible in the window.
+    pub current: u8,
+}
+
+impl Banks {
+    /// Creates `count` zeroed banks for a window of `size` bytes.
+    pub fn new(count: usize, size: usize) -> Self {
+        Self {
+            pages: vec![vec![0; size]; count],
+            current: 0,
+        }
+    }
+
+    /// Creates banks holding `contents`, each padded to `size` bytes.
+    pub fn with_contents(contents: &[Vec<u8>], size: usize) -> Self {
+        let pages = contents
+            .iter()
+            .map(|bank| {
+                let mut page = bank.clone();
+                page.resize(size, 0);
+                page
+            })
+            .collect();
+
+        Self { pages, current: 0 }
+    }
+
+    pub fn page(&self) -> Option<&Vec<u8>> {
+        self.pages.get(self.current as usize)
+    }
+
+    pub fn page_mut(&mut self) -> Option<&mut Vec<u8>> {
+        self.pages.get_mut(self.current as usize)
+    }
+}
+
+/// The banked part of the address space. Shows the selected page of the
+/// shared [`Banks`].
+///
+/// While a bank that does not exist is selected, reads return `0` and
+/// writes are ignored.
+#[derive(Debug, Clone)]
+pub struct BankWindow {
+    pub banks: Rc<RefCell<Banks>>,
+}
+
+impl BankWindow {
+    pub const BASE: u8 = 0x60;
+    pub const END: u8 = 0x9F;
+    pub const SIZE: usize = (Self::END - Self::BASE) as usize + 1;
+}
+
+impl Device for BankWindow {
+    fn name(&self) -> &str {
+        "Bank Window"
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        self.peek(offset)
+    }
+
+    fn write(&mut self, offset: u8, value: u8) {
+        if let Some(byte) = self
+            .banks
+            .borrow_mut()
+            .page_mut()
+            .and_then(|page| page.get_mut(offset as usize))
+        {
+            *byte = value;
+        }
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        self.banks
+            .borrow()
+            .page()
+            .and_then(|page| page.get(offset as usize).copied())
+            .unwrap_or(0)
+    }
+
+    /// The selected bank, followed by every page.
+    fn save_state(&self) -> Vec<u8> {
+        let banks = self.banks.borrow();
+        let mut state = vec![banks.current];
+        for page in &banks.pages {
+            state.extend(page);
+        }
+        state
+    }
+
+    fn load_state(&mut self, state: &[u8]) {
+        let mut banks = self.banks.borrow_mut();
+        let Some((&current, pages)) = state.split_first() else {
+            return;
+        };
+
+        banks.current = current;
+        let size = banks.pages.first().map_or(0, Vec::len);
+        if size == 0 {
+            return;
+        }
+
+        for (page, saved) in banks.pages.iter_mut().zip(pages.chunks_exact(size)) {
+            page.copy_from_slice(saved);
+        }
+    }
+}
+
+/// Bank-select register.
+///
+/// | Offset | Read                  | Write         |
+/// | -----: | :-------------------- | :------------ |
+/// |    `0` | Selected bank number  | Select a bank |
+#[derive(Debug, Clone)]
+pub struct BankSelect {
+    pub banks: Rc<RefCell<Banks>>,
+}
+
+impl BankSelect {
+    pub const BASE: u8 = 0xA8;
+    pub const END: u8 = 0xA8;
+}
+
+impl Device for BankSelect {
+    fn name(&self) -> &str {
+        "Bank Select"
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        self.peek(offset)
+    }
+
+    fn write(&mut self, _offset: u8, value: u8) {
+        self.banks.borrow_mut().current = value;
+    }
+
+    fn peek(&self, _offset: u8) -> u8 {
+        self.banks.borrow().current
+    }
+}
+
+/// Maps a [`BankWindow`] and its [`BankSelect`] register over `banks`,
+/// returning the shared bank storage.
+///
+/// # Errors
+///
+/// Returns [`EmulatorError::BusConflict`](crate::EmulatorError::BusConflict)
+/// if the window or the register address is already claimed.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{
+///     banking::{self, BankSelect, BankWindow, Banks},
+///     bus::Bus,
+/// };
+///
+/// let mut bus = Bus::new();
+/// let banks = banking::map_banks(&mut bus, Banks::new(4, BankWindow::SIZE)).unwrap();
+///
+/// bus.write(BankWindow::BASE, 1);
+/// bus.write(BankSelect::BASE, 2);
+/// bus.write(BankWindow::BASE, 3);
+///
+/// assert_eq!(bus.read(BankWindow::BASE), 3);
+/// bus.write(BankSelect::BASE, 0);
+/// assert_eq!(bus.read(BankWindow::BASE), 1);
+/// assert_eq!(banks.borrow().pages[2][0], 3);
+/// ```
+pub fn map_banks(bus: &mut Bus, banks: Banks) -> crate::EmulatorResult<Rc<RefCell<Banks>>> {
+    let banks = Rc::new(RefCell::new(banks));
+    bus.map(
+        BankWindow::BASE,
+        BankWindow::END,
+        BankWindow {
+            banks: Rc::clone(&banks),
+        },
+    )?;
+    bus.map(
+        BankSelect::BASE,
+        BankSelect::END,
+        BankSelect {
+            banks: Rc::clone(&banks),
+        },
+    )?;
+    Ok(banks)
+}
+
+/// A program split into a fixed part loaded at `0x00` and any number of
+/// banks shown through the [`BankWindow`].
+///
+/// # File format
+///
+/// | Field | Size |
+/// | :--- | :--- |
+/// | Magic `NCBK` | 4 |
+/// | Version | 1 |
+/// | Window start, window size - 1 | 2 |
+/// | Main program length (`u16`, little-endian) and bytes | 2 + n |
+/// | Bank count (`u16`, little-endian) | 2 |
+/// | Per bank: length (`u16`, little-endian) and bytes | 2 + n |
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BankedImage {
+    pub main: Vec<u8>,
+    pub banks: Vec<Vec<u8>>,
+}
+
+impl BankedImage {
+    pub const MAGIC: &'static [u8; 4] = b"NCBK";
+    pub const VERSION: u8 = 1;
+
+    /// Returns `true` if `bytes` starts with the banked image magic.
+    pub fn is_banked(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::MAGIC)
+    }
+
+    /// Loads the main program at `0x00` and, if there are any banks, maps
+    /// them with [`map_banks`].
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`NanoCore::load_program`] or [`map_banks`].
+    pub fn load(&self, nano: &mut NanoCore) -> crate::EmulatorResult<()> {
+        nano.load_program(&self.main, 0x00)?;
+
+        if !self.banks.is_empty() {
+            map_banks(
+                &mut nano.cpu.bus,
+                Banks::with_contents(&self.banks, BankWindow::SIZE),
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend(Self::MAGIC);
+        out.push(Self::VERSION);
+        out.push(BankWindow::BASE);
+        out.push((BankWindow::SIZE - 1) as u8);
+
+        out.extend((self.main.len() as u16).to_le_bytes());
+        out.extend(&self.main);
+
+        out.extend((self.banks.len() as u16).to_le_bytes());
+        for bank in &self.banks {
+            out.extend((bank.len() as u16).to_le_bytes());
+            out.extend(bank);
+        }
+
+        out
+    }
+
+    /// Decodes an image written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FormatError`] if the magic or version does not match, the
+    /// data is truncated, or the image was built for a different bank window.
+    pub fn from_bytes(bytes: &[u8]) -> FormatResult<Self> {
+        let mut r = Reader::new(bytes);
+        r.magic(Self::MAGIC)?;
+
+        let version = r.u8()?;
+        if version != Self::VERSION {
+            return Err(FormatError::UnsupportedVersion { version });
+        }
+
+        let [start, last] = r.array()?;
+        if start != BankWindow::BASE || last as usize + 1 != BankWindow::SIZE {
+            return Err(FormatError::InvalidData(format!(
+                "bank window {:#04X}+{} does not match {:#04X}+{}",
+                start,
+                last as usize + 1,
+                BankWindow::BASE,
+                BankWindow::SIZE
+            )));
+        }
+
+        let len = r.u16()? as usize;
+        let main = r.take(len)?.to_vec();
+
+        let count = r.u16()?;
+        if count > 256 {
+            return Err(FormatError::InvalidData(format!(
+                "{} banks, but the bank select register addresses 256",
+                count
+            )));
+        }
+        let mut banks = Vec::with_capacity(count as usize);
+        for i in 0..count {
+            let len = r.u16()? as usize;
+            if len > BankWindow::SIZE {
+                return Err(FormatError::InvalidData(format!(
+                    "bank {} is {} bytes, larger than the {}-byte window",
+                    i,
+                    len,
+                    BankWindow::SIZE
+                )));
+            }
+            banks.push(r.take(len)?.to_vec());
+        }
+
+        Ok(Self { main, banks })
+    }
+}
@@ -16,8 +16,10 @@
 
 use std::fs;
 
-use clap::Parser;
-use nanocore::{assembler::Assembler, end_color, start_color};
+use clap::{Parser, ValueEnum};
+use nanocore::{
+    assembler::Assembler, end_color, hexfile, loader::Executable, object::Object, start_color,
+};
 
 #[derive(Parser, Debug)]
 #[command(name = "assembler")]
@@ -30,6 +32,22 @@ struct Args {
     /// Path to the output binary file
     #[arg(short, long, default_value = "out.ncb")]
     output: String,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Bin)]
+    format: Format,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    /// `.ncb` executable
+    Bin,
+    /// Intel HEX
+    Ihex,
+    /// Motorola S-records
+    Srec,
+    /// `.nco` object file for `ncld`
+    Obj,
 }
 
 fn main() -> std::io::Result<()> {
@@ -48,14 +66,36 @@ fn main() -> std::io::Result<()> {
 
     let mut c = Assembler::default();
 
-    if let Err(e) = c.assemble(&asm) {
+    // Only object files may leave externs for the linker.
+    let assembled = c.assemble(&asm).and_then(|_| match args.format {
+        Format::Obj => Ok(()),
+        _ => c.check_linked(),
+    });
+    if let Err(e) = assembled {
         eprintln!("Error assembling '{}': {}", args.input, e);
         std::process::exit(1);
     }
 
-    print!("Assembled. Writing to bin.");
+    let executable = Executable::from_assembler(&c);
+    let (name, output) = match args.format {
+        Format::Bin => ("executable", executable.to_bytes()),
+        Format::Ihex => ("Intel HEX", hexfile::to_ihex(&executable).into_bytes()),
+        Format::Srec => ("S-records", hexfile::to_srec(&executable).into_bytes()),
+        Format::Obj => match Object::from_assembler(&c) {
+            Ok(object) => ("object file", object.to_bytes()),
+            Err(e) => {
+                eprintln!("Error assembling '{}': {}", args.input, e);
+                std::process::exit(1);
+            }
+        },
+    };
 
-    fs::write(&args.output, &c.program)?;
+    if c.banks.is_empty() {
+        print!("Assembled. Writing {}.", name);
+    } else {
+        print!("Assembled {} banks. Writing {}.", c.banks.len(), name);
+    }
+    fs::write(&args.output, output)?;
 
     println!("\nDone.");
 
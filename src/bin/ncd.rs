@@ -0,0 +1,67 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::fs;
+
+use clap::Parser;
+use nanocore::{disassembler, end_color, loader::Executable, start_color};
+
+#[derive(Parser, Debug)]
+#[command(name = "disassembler")]
+#[command(version, about = "Disassembles a NanoCore binary (.ncb, Intel HEX or S-records) into ASM (.nca)", long_about = None)]
+struct Args {
+    /// Path to the binary file
+    #[arg(short, long)]
+    input: String,
+
+    /// Path to the output assembly file. Prints to stdout if not given.
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+
+    let executable = match Executable::read(&args.input) {
+        Ok(executable) => executable,
+        Err(e) => {
+            eprintln!("Error reading '{}': {}", args.input, e);
+            std::process::exit(1);
+        }
+    };
+    let asm = disassembler::disassemble(&executable);
+
+    let Some(output) = args.output else {
+        print!("{asm}");
+        return Ok(());
+    };
+
+    print!("\nDisassembling:\n  Input: ");
+    start_color();
+    print!("{}", args.input);
+    end_color();
+    print!("\n Output: ");
+    start_color();
+    print!("{}", output);
+    end_color();
+    println!();
+
+    fs::write(&output, asm)?;
+
+    println!("Done.");
+
+    Ok(())
+}
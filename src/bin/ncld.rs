@@ -0,0 +1,102 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{fs, path::Path};
+
+use clap::Parser;
+use nanocore::{end_color, linker::Linker, object::Object, start_color};
+
+#[derive(Parser, Debug)]
+#[command(name = "linker")]
+#[command(version, about = "Links NanoCore object files (.nco) into a binary (.ncb)", long_about = None)]
+struct Args {
+    /// Object files, placed in the order given
+    #[arg(required = true)]
+    inputs: Vec<String>,
+
+    /// Path to the output binary file
+    #[arg(short, long, default_value = "out.ncb")]
+    output: String,
+
+    /// Path to write the link map to
+    #[arg(short, long)]
+    map: Option<String>,
+
+    /// Address of the first module (decimal or 0x hex)
+    #[arg(short, long, default_value = "0x00", value_parser = parse_address)]
+    base: u8,
+
+    /// Global symbol to start at [default: the base address]
+    #[arg(short, long)]
+    entry: Option<String>,
+}
+
+fn parse_address(s: &str) -> Result<u8, String> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u8::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+    .map_err(|_| format!("invalid address '{s}'"))
+}
+
+fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+
+    print!("\nLinking:\n  Input: ");
+    start_color();
+    print!("{}", args.inputs.join(", "));
+    end_color();
+    print!("\n Output: ");
+    start_color();
+    print!("{}", args.output);
+    end_color();
+    println!();
+
+    let mut linker = Linker::new().with_base(args.base);
+    if let Some(entry) = args.entry {
+        linker = linker.with_entry(entry);
+    }
+
+    for input in &args.inputs {
+        let object = Object::read(input).unwrap_or_else(|e| {
+            eprintln!("Error reading '{}': {}", input, e);
+            std::process::exit(1);
+        });
+        let module = Path::new(input)
+            .file_stem()
+            .map_or(input.clone(), |stem| stem.to_string_lossy().into_owned());
+        linker.add(module, object);
+    }
+
+    let linked = linker.link().unwrap_or_else(|e| {
+        eprintln!("Error linking: {}", e);
+        std::process::exit(1);
+    });
+
+    print!(
+        "Linked {} modules, {} bytes. Writing executable.",
+        linked.sections.len(),
+        linked.executable.size()
+    );
+    fs::write(&args.output, linked.executable.to_bytes())?;
+    if let Some(map) = &args.map {
+        fs::write(map, linked.map())?;
+    }
+
+    println!("\nDone.");
+
+    Ok(())
+}
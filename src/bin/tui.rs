@@ -16,30 +16,43 @@
 
 use std::{fs, io};
 
-use nanocore::{assembler::Assembler, tui::app::App};
+use nanocore::{assembler::Assembler, loader::Executable, tui::app::App};
 
 fn main() -> io::Result<()> {
+    let files: Vec<String> = std::env::args().skip(1).collect();
+    let bin = files.first().expect("Missing filename.").clone();
+
+    let mut app = if files.len() > 1 {
+        let programs = files
+            .iter()
+            .map(|file| read_executable(file))
+            .collect::<io::Result<_>>()?;
+        App::new_system(files.join(" + "), programs)
+    } else {
+        let executable = read_executable(&bin)?;
+        App::new(bin, executable)
+    };
+
     let mut terminal = ratatui::init();
+    app.run(&mut terminal)?;
 
-    let bin = std::env::args().nth(1).expect("Missing filename.");
+    ratatui::restore();
+
+    Ok(())
+}
 
-    let bytes = if bin.ends_with(".nca") {
-        let asm = fs::read_to_string(&bin).unwrap();
+fn read_executable(bin: &str) -> io::Result<Executable> {
+    if bin.ends_with(".nca") {
+        let asm = fs::read_to_string(bin)?;
 
         let mut assembler = Assembler::default();
         assembler
             .assemble(&asm)
-            .map_err(|e| format!("Failed to assemble '{}': {}", bin, e))
-            .unwrap();
-        assembler.program
+            .and_then(|_| assembler.check_linked())
+            .map_err(|e| io::Error::other(format!("Failed to assemble '{}': {}", bin, e)))?;
+        Ok(Executable::from_assembler(&assembler))
     } else {
-        fs::read(&bin).unwrap()
-    };
-
-    let mut app = App::new(bin, bytes);
-    app.run(&mut terminal, &app.program.clone())?;
-
-    ratatui::restore();
-
-    Ok(())
+        Executable::read(bin)
+            .map_err(|e| io::Error::other(format!("Failed to read '{}': {}", bin, e)))
+    }
 }
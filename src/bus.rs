@@ -0,0 +1,221 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::any::Any;
+
+/// A peripheral that claims a range of the address space.
+///
+/// Offsets passed to a device are relative to the start of its mapping.
+pub trait Device: Any + std::fmt::Debug {
+    /// Short name shown by debuggers.
+    fn name(&self) -> &str;
+
+    /// Reads a byte. May have side effects (e.g. popping a key press).
+    fn read(&mut self, offset: u8) -> u8;
+
+    /// Writes a byte.
+    fn write(&mut self, offset: u8, value: u8);
+
+    /// Reads a byte without side effects, for debuggers and the TUI.
+    fn peek(&self, offset: u8) -> u8;
+
+    /// Advances the device by one CPU cycle.
+    ///
+    /// Returns `true` to request an interrupt.
+    fn tick(&mut self) -> bool {
+        false
+    }
+
+    /// Serializes the device's internal state for a
+    /// [`Snapshot`](crate::snapshot::Snapshot). Stateless devices keep the
+    /// default, which saves nothing.
+    fn save_state(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    /// Restores state produced by [`save_state`](Self::save_state).
+    fn load_state(&mut self, _state: &[u8]) {}
+}
+
+/// Plain read-write memory. Every address not claimed by another device is RAM.
+#[derive(Debug, Clone)]
+pub struct Ram {
+    pub bytes: [u8; 256],
+}
+
+impl Default for Ram {
+    fn default() -> Self {
+        Self { bytes: [0; 256] }
+    }
+}
+
+impl Device for Ram {
+    fn name(&self) -> &str {
+        "RAM"
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        self.bytes[offset as usize]
+    }
+
+    fn write(&mut self, offset: u8, value: u8) {
+        self.bytes[offset as usize] = value;
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        self.bytes[offset as usize]
+    }
+}
+
+/// An address range claimed by a device.
+#[derive(Debug)]
+pub struct Mapping {
+    pub start: u8,
+    pub end: u8,
+    pub device: Box<dyn Device>,
+}
+
+impl Mapping {
+    pub fn contains(&self, addr: u8) -> bool {
+        (self.start..=self.end).contains(&addr)
+    }
+}
+
+/// The memory bus: 256 bytes of RAM plus any number of mapped devices.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{bus::Bus, devices::Screen};
+///
+/// let mut bus = Bus::new();
+/// bus.map(Screen::BASE, Screen::END, Screen::default()).unwrap();
+///
+/// bus.write(Screen::BASE, b'A');
+/// assert_eq!(bus.device::<Screen>().unwrap().char_at(0, 0), b'A');
+/// // The RAM underneath is untouched.
+/// assert_eq!(bus.ram()[Screen::BASE as usize], 0);
+/// ```
+#[derive(Debug, Default)]
+pub struct Bus {
+    ram: Ram,
+    mappings: Vec<Mapping>,
+}
+
+impl Bus {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps a device over `start..=end`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EmulatorError::BusConflict`](crate::EmulatorError::BusConflict)
+    /// if the range is empty or overlaps an existing mapping.
+    pub fn map(&mut self, start: u8, end: u8, device: impl Device) -> crate::EmulatorResult<()> {
+        if start > end
+            || self
+                .mappings
+                .iter()
+                .any(|m| start <= m.end && m.start <= end)
+        {
+            return Err(crate::EmulatorError::BusConflict { start, end });
+        }
+
+        self.mappings.push(Mapping {
+            start,
+            end,
+            device: Box::new(device),
+        });
+        Ok(())
+    }
+
+    /// Removes the device mapped at `start`, returning it.
+    pub fn unmap(&mut self, start: u8) -> Option<Box<dyn Device>> {
+        let idx = self.mappings.iter().position(|m| m.start == start)?;
+        Some(self.mappings.remove(idx).device)
+    }
+
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    pub fn mappings_mut(&mut self) -> &mut [Mapping] {
+        &mut self.mappings
+    }
+
+    /// Returns the first mapped device of type `T`.
+    pub fn device<T: Device>(&self) -> Option<&T> {
+        self.mappings
+            .iter()
+            .find_map(|m| (m.device.as_ref() as &dyn Any).downcast_ref::<T>())
+    }
+
+    /// Returns the first mapped device of type `T` mutably.
+    pub fn device_mut<T: Device>(&mut self) -> Option<&mut T> {
+        self.mappings
+            .iter_mut()
+            .find_map(|m| (m.device.as_mut() as &mut dyn Any).downcast_mut::<T>())
+    }
+
+    /// The RAM behind the bus, ignoring any mapped devices.
+    pub fn ram(&self) -> &[u8; 256] {
+        &self.ram.bytes
+    }
+
+    pub fn ram_mut(&mut self) -> &mut [u8; 256] {
+        &mut self.ram.bytes
+    }
+
+    /// Returns `true` if `addr` is backed by plain RAM.
+    pub fn is_ram(&self, addr: u8) -> bool {
+        !self.mappings.iter().any(|m| m.contains(addr))
+    }
+
+    pub fn read(&mut self, addr: u8) -> u8 {
+        match self.mappings.iter_mut().find(|m| m.contains(addr)) {
+            Some(m) => m.device.read(addr - m.start),
+            None => self.ram.read(addr),
+        }
+    }
+
+    pub fn write(&mut self, addr: u8, value: u8) {
+        match self.mappings.iter_mut().find(|m| m.contains(addr)) {
+            Some(m) => m.device.write(addr - m.start, value),
+            None => self.ram.write(addr, value),
+        }
+    }
+
+    pub fn peek(&self, addr: u8) -> u8 {
+        match self.mappings.iter().find(|m| m.contains(addr)) {
+            Some(m) => m.device.peek(addr - m.start),
+            None => self.ram.peek(addr),
+        }
+    }
+
+    /// Advances every mapped device by one cycle.
+    ///
+    /// Returns `true` if any device requested an interrupt.
+    pub fn tick(&mut self) -> bool {
+        let mut irq = false;
+        for m in &mut self.mappings {
+            irq |= m.device.tick();
+        }
+        irq
+    }
+}
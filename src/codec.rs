@@ -0,0 +1,116 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use crate::{FormatError, FormatResult};
+
+/// Appends `bytes` prefixed with their length as a `u32`.
+pub(crate) fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend((bytes.len() as u32).to_le_bytes());
+    out.extend(bytes);
+}
+
+/// Cursor over an encoded file.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> FormatResult<&'a [u8]> {
+        let remaining = self.bytes.len() - self.offset;
+        if remaining < n {
+            return Err(FormatError::Truncated {
+                offset: self.offset,
+                needed: n - remaining,
+            });
+        }
+
+        let slice = &self.bytes[self.offset..self.offset + n];
+        self.offset += n;
+        Ok(slice)
+    }
+
+    /// Consumes the magic bytes at the start of a file.
+    pub(crate) fn magic(&mut self, expected: &[u8]) -> FormatResult<()> {
+        let found = self.take(expected.len())?;
+        if found != expected {
+            return Err(FormatError::BadMagic {
+                expected: expected.to_vec(),
+                found: found.to_vec(),
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn array<const N: usize>(&mut self) -> FormatResult<[u8; N]> {
+        let mut out = [0; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    pub(crate) fn u8(&mut self) -> FormatResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> FormatResult<u16> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn u32(&mut self) -> FormatResult<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn blob(&mut self) -> FormatResult<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    pub(crate) fn string(&mut self) -> FormatResult<String> {
+        String::from_utf8(self.blob()?.to_vec())
+            .map_err(|e| FormatError::InvalidData(format!("invalid UTF-8 string: {e}")))
+    }
+}
+
+/// CRC-32 (IEEE 802.3, as used by zip and PNG) of `bytes`.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Checks `body` against the little-endian CRC-32 stored after it.
+pub(crate) fn check_crc32(body: &[u8], checksum: &[u8]) -> FormatResult<()> {
+    let expected = u32::from_le_bytes(checksum.try_into().unwrap_or_default());
+    let found = crc32(body);
+    if found != expected {
+        return Err(FormatError::ChecksumMismatch {
+            line: None,
+            expected,
+            found,
+        });
+    }
+    Ok(())
+}
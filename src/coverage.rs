@@ -0,0 +1,315 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{collections::BTreeMap, fmt::Write, fs, path::Path};
+
+use crate::{
+    FormatError, FormatResult, Op,
+    codec::{Reader, write_bytes},
+    cpu::CPU,
+    observer::Observer,
+};
+
+/// How often a conditional branch went each way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BranchCounts {
+    pub taken: u64,
+    pub not_taken: u64,
+}
+
+impl BranchCounts {
+    /// Outcomes seen at least once: 0, 1 or 2.
+    pub fn covered(&self) -> usize {
+        (self.taken > 0) as usize + (self.not_taken > 0) as usize
+    }
+}
+
+/// Coverage of one source line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineCoverage {
+    /// 1-based source line.
+    pub line: usize,
+    pub addr: u8,
+    pub hits: u64,
+    /// Outcomes if the line is a conditional branch.
+    pub branch: Option<BranchCounts>,
+}
+
+/// An [`Observer`] that records executed addresses and branch outcomes.
+///
+/// Coverage of several runs of the same program is combined with
+/// [`merge`](Self::merge), or across processes by saving it to a file. The
+/// assembler's [`line_map`](crate::assembler::Assembler::line_map) turns
+/// addresses into source lines for the text and LCOV reports.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{assembler::Assembler, coverage::Coverage, nanocore::NanoCore};
+///
+/// let asm = "LDI R0 1\nJZ skip\nHLT\nskip:\nINC R0\nHLT";
+/// let mut assembler = Assembler::default();
+/// assembler.assemble(asm).unwrap();
+///
+/// let mut nano = NanoCore::new();
+/// nano.load_program(&assembler.program, 0x00).unwrap();
+/// nano.add_observer(Coverage::new(&assembler.program));
+/// nano.run().unwrap();
+///
+/// let coverage = nano.observer::<Coverage>().unwrap();
+/// let lines = coverage.lines(asm, &assembler.line_map);
+/// let covered: Vec<_> = lines.iter().map(|l| l.hits > 0).collect();
+/// assert_eq!(covered, [true, true, true, false, false]);
+/// assert_eq!(lines[1].branch.unwrap().not_taken, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    /// The program the counts belong to.
+    pub program: Vec<u8>,
+    /// Instructions executed at each address.
+    pub hits: [u64; 256],
+    /// Outcomes of each conditional branch that executed, by address.
+    pub branches: BTreeMap<u8, BranchCounts>,
+}
+
+impl Coverage {
+    pub const MAGIC: &'static [u8; 4] = b"NCCV";
+    pub const VERSION: u8 = 1;
+
+    pub fn new(program: &[u8]) -> Self {
+        Self {
+            program: program.to_vec(),
+            hits: [0; 256],
+            branches: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the counts of another run of the same program.
+    pub fn merge(&mut self, other: &Coverage) {
+        for (hits, other) in self.hits.iter_mut().zip(other.hits) {
+            *hits += other;
+        }
+        for (&addr, counts) in &other.branches {
+            let entry = self.branches.entry(addr).or_default();
+            entry.taken += counts.taken;
+            entry.not_taken += counts.not_taken;
+        }
+    }
+
+    /// Coverage of every source line that holds an instruction, in line
+    /// order. Lines of `.DB` and `.STRING` data are skipped.
+    pub fn lines(&self, asm: &str, line_map: &BTreeMap<u8, usize>) -> Vec<LineCoverage> {
+        let source: Vec<&str> = asm.lines().collect();
+        let mut lines: Vec<_> = line_map
+            .iter()
+            .filter(|&(_, &line)| {
+                source
+                    .get(line - 1)
+                    .is_some_and(|text| !text.trim_start().starts_with('.'))
+            })
+            .map(|(&addr, &line)| {
+                let is_branch = self
+                    .program
+                    .get(addr as usize)
+                    .is_some_and(|&byte| Op::decode_or_nop(byte).is_conditional_branch());
+                LineCoverage {
+                    line,
+                    addr,
+                    hits: self.hits[addr as usize],
+                    branch: is_branch
+                        .then(|| self.branches.get(&addr).copied().unwrap_or_default()),
+                }
+            })
+            .collect();
+        lines.sort_by_key(|l| l.line);
+        lines
+    }
+
+    /// Annotates `asm` like `gcov`: each line is prefixed with its execution
+    /// count, `#####` if it holds an instruction that never ran, or `-`.
+    /// Branch lines are followed by their outcomes.
+    pub fn report(&self, asm: &str, line_map: &BTreeMap<u8, usize>) -> String {
+        let lines = self.lines(asm, line_map);
+        let by_line: BTreeMap<usize, &LineCoverage> = lines.iter().map(|l| (l.line, l)).collect();
+
+        let hit = lines.iter().filter(|l| l.hits > 0).count();
+        let branches: Vec<_> = lines.iter().filter_map(|l| l.branch).collect();
+        let outcomes = branches.iter().map(BranchCounts::covered).sum::<usize>();
+        let percent = |n: usize, of: usize| n as f64 * 100.0 / of.max(1) as f64;
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "== Coverage: {}/{} lines ({:.1}%), {}/{} branch outcomes ({:.1}%) ==",
+            hit,
+            lines.len(),
+            percent(hit, lines.len()),
+            outcomes,
+            branches.len() * 2,
+            percent(outcomes, branches.len() * 2)
+        );
+
+        for (idx, text) in asm.lines().enumerate() {
+            let line = idx + 1;
+            let Some(coverage) = by_line.get(&line) else {
+                let _ = writeln!(out, "{:>9}:{:>5}:{}", "-", line, text);
+                continue;
+            };
+
+            let count = match coverage.hits {
+                0 => "#####".to_owned(),
+                hits => hits.to_string(),
+            };
+            let _ = writeln!(out, "{:>9}:{:>5}:{}", count, line, text);
+
+            if let Some(branch) = coverage.branch
+                && coverage.hits > 0
+            {
+                let _ = writeln!(
+                    out,
+                    "{:>16}branch taken {}, not taken {}",
+                    "", branch.taken, branch.not_taken
+                );
+            }
+        }
+
+        out
+    }
+
+    /// An LCOV tracefile for `source_path`, readable by `genhtml` and CI
+    /// coverage services.
+    pub fn lcov(&self, source_path: &str, asm: &str, line_map: &BTreeMap<u8, usize>) -> String {
+        let lines = self.lines(asm, line_map);
+        let mut out = String::new();
+        let _ = writeln!(out, "TN:\nSF:{source_path}");
+
+        let mut branches = 0;
+        let mut branches_hit = 0;
+        for l in &lines {
+            let Some(branch) = l.branch else {
+                continue;
+            };
+            for (outcome, count) in [branch.taken, branch.not_taken].into_iter().enumerate() {
+                let count = match l.hits {
+                    0 => "-".to_owned(),
+                    _ => count.to_string(),
+                };
+                let _ = writeln!(out, "BRDA:{},0,{},{}", l.line, outcome, count);
+            }
+            branches += 2;
+            branches_hit += branch.covered();
+        }
+        let _ = writeln!(out, "BRF:{branches}\nBRH:{branches_hit}");
+
+        for l in &lines {
+            let _ = writeln!(out, "DA:{},{}", l.line, l.hits);
+        }
+        let hit = lines.iter().filter(|l| l.hits > 0).count();
+        let _ = writeln!(out, "LF:{}\nLH:{}\nend_of_record", lines.len(), hit);
+
+        out
+    }
+
+    /// Encodes the counts in the coverage data file format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(2048);
+        out.extend(Self::MAGIC);
+        out.push(Self::VERSION);
+        write_bytes(&mut out, &self.program);
+
+        for hits in self.hits {
+            out.extend(hits.to_le_bytes());
+        }
+
+        out.extend((self.branches.len() as u32).to_le_bytes());
+        for (&addr, counts) in &self.branches {
+            out.push(addr);
+            out.extend(counts.taken.to_le_bytes());
+            out.extend(counts.not_taken.to_le_bytes());
+        }
+
+        out
+    }
+
+    /// Decodes counts written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FormatError`] if the magic or version does not match or
+    /// the data is truncated.
+    pub fn from_bytes(bytes: &[u8]) -> FormatResult<Self> {
+        let mut r = Reader::new(bytes);
+        r.magic(Self::MAGIC)?;
+
+        let version = r.u8()?;
+        if version == 0 || version > Self::VERSION {
+            return Err(FormatError::UnsupportedVersion { version });
+        }
+
+        let mut coverage = Self::new(r.blob()?);
+        for hits in &mut coverage.hits {
+            *hits = u64::from_le_bytes(r.array()?);
+        }
+
+        for _ in 0..r.u32()? {
+            let addr = r.u8()?;
+            let taken = u64::from_le_bytes(r.array()?);
+            let not_taken = u64::from_le_bytes(r.array()?);
+            coverage
+                .branches
+                .insert(addr, BranchCounts { taken, not_taken });
+        }
+
+        Ok(coverage)
+    }
+
+    /// Writes the counts to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::Io`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> FormatResult<()> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Reads counts from `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::Io`] if the file cannot be read, or any error
+    /// from [`from_bytes`](Self::from_bytes).
+    pub fn load(path: impl AsRef<Path>) -> FormatResult<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+impl Observer for Coverage {
+    fn after_execute(&mut self, cpu: &CPU, pc: u8, op: Op, _t_states: u64) {
+        self.hits[pc as usize] += 1;
+
+        if op.is_conditional_branch() {
+            // Branches leave the flags untouched, so the condition still
+            // holds as it did when the branch executed.
+            let counts = self.branches.entry(pc).or_default();
+            if cpu.branch_condition(op) {
+                counts.taken += 1;
+            } else {
+                counts.not_taken += 1;
+            }
+        }
+    }
+}
@@ -14,7 +14,7 @@
 //! language programming.
 //!
 
-use crate::{end_color, start_color};
+use crate::{Op, bus::Bus, end_color, start_color};
 
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Debug)]
@@ -22,9 +22,14 @@ pub struct CPU {
     pub registers: [u8; 16],
     pub pc: u8,
     pub sp: u8,
-    pub memory: [u8; 256],
+    pub bus: Bus,
     pub flags: u8,
     pub is_halted: bool,
+
+    /// Set when an interrupt has been requested but not yet serviced.
+    pub irq_pending: bool,
+    /// Address of the byte holding the interrupt handler address.
+    pub irq_vector: u8,
 }
 
 impl Default for CPU {
@@ -40,20 +45,37 @@ impl CPU {
             registers: [0; 16],
             pc: 0,
             sp: 0xFF,
-            memory: [0; 256],
+            bus: Bus::new(),
             flags: 0,
             is_halted: false,
+
+            irq_pending: false,
+            irq_vector: Self::IRQ_VECTOR,
         }
     }
 
     pub const FLAG_Z: u8 = 0b0000_0001;
     pub const FLAG_C: u8 = 0b0000_0010;
     pub const FLAG_N: u8 = 0b0000_0100;
-    pub const FLAG_Y: u8 = 0b0000_1000;
+    pub const FLAG_V: u8 = 0b0000_1000; // Signed overflow
+    #[deprecated(note = "renamed to `FLAG_V`")]
+    pub const FLAG_Y: u8 = Self::FLAG_V;
+    pub const FLAG_I: u8 = 0b0001_0000; // Interrupts enabled
+
+    /// Flag letters and bits, in [`flag_names`](Self::flag_names) order.
+    pub const FLAG_NAMES: [(char, u8); 5] = [
+        ('I', Self::FLAG_I),
+        ('V', Self::FLAG_V),
+        ('N', Self::FLAG_N),
+        ('C', Self::FLAG_C),
+        ('Z', Self::FLAG_Z),
+    ];
 
     pub const STACK_MAX: u8 = 0xFF;
     pub const STACK_MIN: u8 = 0xEA;
 
+    pub const IRQ_VECTOR: u8 = 0xA9;
+
     pub fn set_flag(&mut self, bit: u8) {
         self.flags |= bit;
     }
@@ -66,6 +88,66 @@ impl CPU {
         (self.flags & bit) != 0
     }
 
+    /// Evaluates the condition of a conditional branch against the flags.
+    /// Returns `false` for other instructions.
+    ///
+    /// The comparison branches read the flags as left by `CMP Rx Ry`: C is
+    /// set when `Rx < Ry` unsigned, and N differs from V when
+    /// `Rx < Ry` signed.
+    pub fn branch_condition(&self, op: Op) -> bool {
+        let z = self.get_flag(CPU::FLAG_Z);
+        let c = self.get_flag(CPU::FLAG_C);
+        let n = self.get_flag(CPU::FLAG_N);
+        let v = self.get_flag(CPU::FLAG_V);
+
+        match op {
+            Op::JZ => z,
+            Op::JNZ => !z,
+            Op::JC | Op::JLT => c,
+            Op::JNC | Op::JGE => !c,
+            Op::JN => n,
+            Op::JNN => !n,
+            Op::JGT => !c && !z,
+            Op::JLE => c || z,
+            Op::JLTS => n != v,
+            Op::JGES => n == v,
+            Op::JGTS => !z && n == v,
+            Op::JLES => z || n != v,
+            Op::JV => v,
+            Op::JNV => !v,
+            _ => false,
+        }
+    }
+
+    /// Sets `bit` if `value` is `true`, clears it otherwise.
+    pub fn assign_flag(&mut self, bit: u8, value: bool) {
+        if value {
+            self.set_flag(bit);
+        } else {
+            self.clear_flag(bit);
+        }
+    }
+
+    /// The flags as letters, most significant first, with `-` for clear
+    /// bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanocore::cpu::CPU;
+    ///
+    /// let mut cpu = CPU::new();
+    /// cpu.set_flag(CPU::FLAG_V);
+    /// cpu.set_flag(CPU::FLAG_Z);
+    /// assert_eq!(cpu.flag_names(), "-V--Z");
+    /// ```
+    pub fn flag_names(&self) -> String {
+        Self::FLAG_NAMES
+            .iter()
+            .map(|&(name, bit)| if self.get_flag(bit) { name } else { '-' })
+            .collect()
+    }
+
     pub fn update_zn_flags(&mut self, result: u8) {
         match result {
             0 => self.set_flag(Self::FLAG_Z),
@@ -78,7 +160,7 @@ impl CPU {
         }
     }
 
-    pub fn print_state(&self, cycle: u16) {
+    pub fn print_state(&self, cycle: u64, t_states: u64) {
         println!();
 
         start_color();
@@ -89,11 +171,12 @@ impl CPU {
 
         start_color();
         print!(
-            "│ Cycle: {cycle:03} / PC: {:#04X} ({:03}) / Flags: {:08b} {}│",
+            "│ Cycle: {cycle:03} / T: {t_states:05} / PC: {:#04X} ({:03}) / Flags: {:08b} [{}] {}│",
             self.pc,
             self.pc,
             self.flags,
-            " ".repeat(self.registers.len() * 6 - 32)
+            self.flag_names(),
+            " ".repeat(self.registers.len() * 6 - 51)
         );
         end_color();
 
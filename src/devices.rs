@@ -0,0 +1,279 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::collections::VecDeque;
+
+use crate::bus::{Bus, Device};
+
+/// 8x8 character display. Each byte is one ASCII cell, row-major.
+#[derive(Debug, Clone)]
+pub struct Screen {
+    pub cells: [u8; 64],
+}
+
+impl Default for Screen {
+    fn default() -> Self {
+        Self { cells: [0; 64] }
+    }
+}
+
+impl Screen {
+    pub const BASE: u8 = 0xAA;
+    pub const END: u8 = 0xE9;
+    pub const WIDTH: u8 = 8;
+    pub const HEIGHT: u8 = 8;
+
+    pub fn char_at(&self, x: u8, y: u8) -> u8 {
+        self.cells[(y * Self::WIDTH + x) as usize]
+    }
+}
+
+impl Device for Screen {
+    fn name(&self) -> &str {
+        "Screen"
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        self.peek(offset)
+    }
+
+    fn write(&mut self, offset: u8, value: u8) {
+        self.cells[offset as usize] = value;
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        self.cells[offset as usize]
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        self.cells.to_vec()
+    }
+
+    fn load_state(&mut self, state: &[u8]) {
+        let len = state.len().min(self.cells.len());
+        self.cells[..len].copy_from_slice(&state[..len]);
+    }
+}
+
+/// Key buffer.
+///
+/// | Offset | Read                               | Write   |
+/// | -----: | :--------------------------------- | :------ |
+/// |    `0` | Number of pending keys (saturated) | ignored |
+/// |    `1` | Next key, or `0` if none           | ignored |
+///
+/// Requests an interrupt on the cycle after a key is pushed.
+#[derive(Debug, Default, Clone)]
+pub struct Keyboard {
+    pub keys: VecDeque<u8>,
+    pub irq: bool,
+}
+
+impl Keyboard {
+    pub const BASE: u8 = 0xA1;
+    pub const END: u8 = 0xA2;
+
+    /// Queues a key press for the program to read.
+    pub fn push_key(&mut self, key: u8) {
+        self.keys.push_back(key);
+        self.irq = true;
+    }
+}
+
+impl Device for Keyboard {
+    fn name(&self) -> &str {
+        "Keyboard"
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        match offset {
+            1 => self.keys.pop_front().unwrap_or(0),
+            _ => self.peek(offset),
+        }
+    }
+
+    fn write(&mut self, _offset: u8, _value: u8) {}
+
+    fn peek(&self, offset: u8) -> u8 {
+        match offset {
+            0 => self.keys.len().min(u8::MAX as usize) as u8,
+            1 => self.keys.front().copied().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn tick(&mut self) -> bool {
+        std::mem::take(&mut self.irq)
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut state = vec![self.irq as u8];
+        state.extend(&self.keys);
+        state
+    }
+
+    fn load_state(&mut self, state: &[u8]) {
+        if let Some((&irq, keys)) = state.split_first() {
+            self.irq = irq != 0;
+            self.keys = keys.iter().copied().collect();
+        }
+    }
+}
+
+/// Down-counting timer.
+///
+/// | Offset | Read                          | Write                         |
+/// | -----: | :---------------------------- | :---------------------------- |
+/// |    `0` | Current count                 | Set count                     |
+/// |    `1` | Reload value (`0` = stopped)  | Set reload value and count    |
+/// |    `2` | `1` if expired, then clears   | Clear expired                 |
+///
+/// Requests an interrupt every time the count reaches zero.
+#[derive(Debug, Default, Clone)]
+pub struct Timer {
+    pub count: u8,
+    pub reload: u8,
+    pub expired: bool,
+}
+
+impl Timer {
+    pub const BASE: u8 = 0xA3;
+    pub const END: u8 = 0xA5;
+}
+
+impl Device for Timer {
+    fn name(&self) -> &str {
+        "Timer"
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        let value = self.peek(offset);
+        if offset == 2 {
+            self.expired = false;
+        }
+        value
+    }
+
+    fn write(&mut self, offset: u8, value: u8) {
+        match offset {
+            0 => self.count = value,
+            1 => {
+                self.reload = value;
+                self.count = value;
+            }
+            _ => self.expired = false,
+        }
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        match offset {
+            0 => self.count,
+            1 => self.reload,
+            _ => self.expired as u8,
+        }
+    }
+
+    fn tick(&mut self) -> bool {
+        if self.reload == 0 {
+            return false;
+        }
+
+        self.count = self.count.saturating_sub(1);
+        if self.count == 0 {
+            self.count = self.reload;
+            self.expired = true;
+            return true;
+        }
+        false
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        vec![self.count, self.reload, self.expired as u8]
+    }
+
+    fn load_state(&mut self, state: &[u8]) {
+        if let &[count, reload, expired] = state {
+            self.count = count;
+            self.reload = reload;
+            self.expired = expired != 0;
+        }
+    }
+}
+
+/// Pseudo-random number generator (16-bit xorshift).
+///
+/// Every read returns a new byte; writing reseeds the generator. The default
+/// seed is fixed so runs are reproducible.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    pub state: u16,
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self { state: 0xACE1 }
+    }
+}
+
+impl Rng {
+    pub const BASE: u8 = 0xA0;
+    pub const END: u8 = 0xA0;
+}
+
+impl Device for Rng {
+    fn name(&self) -> &str {
+        "RNG"
+    }
+
+    fn read(&mut self, _offset: u8) -> u8 {
+        self.state ^= self.state << 7;
+        self.state ^= self.state >> 9;
+        self.state ^= self.state << 8;
+        self.peek(0)
+    }
+
+    fn write(&mut self, _offset: u8, value: u8) {
+        self.state = u16::from_le_bytes([value, 0xA5]);
+    }
+
+    fn peek(&self, _offset: u8) -> u8 {
+        (self.state >> 8) as u8
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        self.state.to_le_bytes().to_vec()
+    }
+
+    fn load_state(&mut self, state: &[u8]) {
+        if let &[lo, hi] = state {
+            self.state = u16::from_le_bytes([lo, hi]);
+        }
+    }
+}
+
+/// Maps the screen, keyboard, timer and RNG at their default addresses.
+///
+/// # Errors
+///
+/// Returns [`EmulatorError::BusConflict`](crate::EmulatorError::BusConflict)
+/// if any of the default ranges is already claimed.
+pub fn map_standard(bus: &mut Bus) -> crate::EmulatorResult<()> {
+    bus.map(Rng::BASE, Rng::END, Rng::default())?;
+    bus.map(Keyboard::BASE, Keyboard::END, Keyboard::default())?;
+    bus.map(Timer::BASE, Timer::END, Timer::default())?;
+    bus.map(Screen::BASE, Screen::END, Screen::default())?;
+    Ok(())
+}
@@ -0,0 +1,414 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+use crate::{Op, banking::BankWindow, loader::Executable, nanocore::Operands};
+
+/// Data bytes per `.DB` line.
+const DB_PER_LINE: usize = 8;
+/// Shortest run of printable characters written as `.STRING`.
+const MIN_STRING: usize = 4;
+
+/// A decoded instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub addr: u8,
+    pub op: Op,
+    pub operands: Operands,
+}
+
+impl Instruction {
+    /// Decodes the instruction at the start of `bytes`, which is located at
+    /// `addr`.
+    ///
+    /// Returns `None` for anything the assembler could not have produced: an
+    /// illegal opcode, a register above R15, or an instruction cut off by
+    /// the end of `bytes`.
+    pub fn decode(addr: u8, bytes: &[u8]) -> Option<Self> {
+        let op = Op::try_from(*bytes.first()?).ok()?;
+        let len = op.instruction_len() as usize;
+        if bytes.len() < len {
+            return None;
+        }
+
+        let byte = |i: usize| if i < len { bytes[i] } else { 0 };
+        let operands = Operands::decode(op, byte(1), byte(2));
+        let register = match operands {
+            Operands::Reg(r) | Operands::RegImm(r, _) | Operands::RegAddr(r, _) => r,
+            _ => 0,
+        };
+        (register < 16).then_some(Self { addr, op, operands })
+    }
+
+    /// The jump, branch or call target.
+    pub fn target(&self) -> Option<u8> {
+        match (self.op, self.operands) {
+            (Op::JMP | Op::CALL, Operands::Addr(target)) => Some(target),
+            (op, Operands::Addr(target)) if op.is_conditional_branch() => Some(target),
+            _ => None,
+        }
+    }
+
+    /// Whether execution can continue with the next instruction.
+    pub fn falls_through(&self) -> bool {
+        !matches!(self.op, Op::HLT | Op::JMP | Op::JMPR | Op::RET | Op::RETI)
+    }
+
+    fn next(&self) -> u8 {
+        self.addr.wrapping_add(self.op.instruction_len())
+    }
+
+    /// Assembly text, with targets named by `labels` where possible.
+    fn text<'a>(&self, labels: impl Fn(u8) -> Option<&'a String>) -> String {
+        let operands = match self.operands {
+            Operands::None => String::new(),
+            Operands::Reg(r) => format!(" R{r}"),
+            Operands::RegImm(r, value) => format!(" R{r} {value}"),
+            Operands::RegReg(a, b) => format!(" R{a} R{b}"),
+            Operands::RegAddr(r, addr) => format!(" R{r} {addr:#04X}"),
+            Operands::Addr(target) => match labels(target) {
+                Some(label) => format!(" {label}"),
+                None => format!(" {target:#04X}"),
+            },
+        };
+        format!("{}{}", self.op, operands)
+    }
+}
+
+/// Finds the instructions reachable from `entries` in `bytes`, which are
+/// loaded at `base`, by following fall-through, jumps, branches and calls.
+///
+/// Paths stop at an undecodable byte, at a target outside `bytes`, and where
+/// an instruction would overlap one already found. Indirect jumps
+/// (`JMPR`, `CALLR`) and interrupt handlers cannot be followed, so code only
+/// reached that way is left as data.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::disassembler;
+///
+/// // JMP 0x04; <data>; HLT
+/// let code = disassembler::trace(&[0x16, 0x04, 0xFF, 0xFF, 0x00], 0x00, &[0x00]);
+/// assert_eq!(code.keys().copied().collect::<Vec<_>>(), [0x00, 0x04]);
+/// ```
+pub fn trace(bytes: &[u8], base: u8, entries: &[u8]) -> BTreeMap<u8, Instruction> {
+    let offset = |addr: u8| {
+        (addr as usize)
+            .checked_sub(base as usize)
+            .filter(|&offset| offset < bytes.len())
+    };
+
+    let mut code = BTreeMap::new();
+    let mut covered = vec![false; bytes.len()];
+    let mut pending = entries.to_vec();
+
+    while let Some(addr) = pending.pop() {
+        let Some(start) = offset(addr) else {
+            continue;
+        };
+        let Some(instruction) = Instruction::decode(addr, &bytes[start..]) else {
+            continue;
+        };
+        let end = start + instruction.op.instruction_len() as usize;
+        if covered[start..end].iter().any(|&c| c) {
+            continue;
+        }
+
+        covered[start..end].fill(true);
+        code.insert(addr, instruction);
+        pending.extend(instruction.target());
+        if instruction.falls_through() {
+            pending.push(instruction.next());
+        }
+    }
+
+    code
+}
+
+/// One block of output: main memory or a bank.
+struct Region {
+    bank: Option<u8>,
+    base: u8,
+    bytes: Vec<u8>,
+    code: BTreeMap<u8, Instruction>,
+    labels: HashMap<u8, String>,
+}
+
+impl Region {
+    fn contains(&self, addr: u8) -> bool {
+        addr >= self.base && ((addr - self.base) as usize) < self.bytes.len()
+    }
+
+    /// Whether a label can go at `addr`: the start of an instruction or
+    /// anywhere in data.
+    fn at_boundary(&self, addr: u8) -> bool {
+        self.contains(addr)
+            && (self.code.contains_key(&addr)
+                || !self
+                    .code
+                    .range(..addr)
+                    .next_back()
+                    .is_some_and(|(_, i)| i.next() > addr && i.next() != 0))
+    }
+}
+
+/// Turns `executable` back into assembly that the
+/// [`Assembler`](crate::assembler::Assembler) reassembles to the same bytes.
+///
+/// Code is found with [`trace`] from the entry point, and every other byte
+/// becomes `.DB` or `.STRING` data. Jump and call targets are labelled with
+/// the executable's symbols, or else `sub_XX` for subroutines and `lbl_XX`
+/// for other targets. Banked code is traced from every jump into the bank
+/// window, since the selected bank is not known.
+///
+/// `NOP` is written as `.DB 0x01`, because the assembler emits nothing for
+/// it. The entry point and load address are only recorded in comments;
+/// the main program is written from `0x00`.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{assembler::Assembler, disassembler, loader::Executable};
+///
+/// let mut assembler = Assembler::default();
+/// assembler.assemble("LDI R0 3\nloop:\nDEC R0\nJNZ loop\nHLT").unwrap();
+///
+/// let asm = disassembler::disassemble(&Executable::from_raw(assembler.program.clone()));
+/// assert!(asm.contains("    JNZ lbl_03"));
+///
+/// let mut reassembled = Assembler::default();
+/// reassembled.assemble(&asm).unwrap();
+/// assert_eq!(reassembled.program, assembler.program);
+/// ```
+pub fn disassemble(executable: &Executable) -> String {
+    let mut regions = regions(executable);
+    let has_banks = regions.len() > 1;
+    let in_window = |addr: u8| has_banks && (BankWindow::BASE..=BankWindow::END).contains(&addr);
+
+    // Main code and bank code can jump into each other, so trace until no
+    // new entry points turn up.
+    let mut main_entries = vec![executable.entry];
+    let mut window_entries = Vec::new();
+    loop {
+        for region in &mut regions {
+            let entries = match region.bank {
+                None => &main_entries,
+                Some(_) => &window_entries,
+            };
+            region.code = trace(&region.bytes, region.base, entries);
+        }
+
+        let targets: Vec<u8> = regions
+            .iter()
+            .flat_map(|r| r.code.values().filter_map(Instruction::target))
+            .collect();
+        let (window, main): (Vec<u8>, Vec<u8>) = targets.into_iter().partition(|&t| in_window(t));
+
+        let before = (main_entries.len(), window_entries.len());
+        for (entries, new) in [(&mut main_entries, main), (&mut window_entries, window)] {
+            for target in new {
+                if !entries.contains(&target) {
+                    entries.push(target);
+                }
+            }
+        }
+        if (main_entries.len(), window_entries.len()) == before {
+            break;
+        }
+    }
+
+    label(&mut regions, executable, has_banks);
+
+    let mut out = String::from("; Disassembled by ncd\n");
+    writeln!(
+        out,
+        "; Load address: {:#04X}, entry point: {:#04X}",
+        executable.load_address, executable.entry
+    )
+    .unwrap();
+
+    for region in &regions {
+        out.push('\n');
+        if let Some(bank) = region.bank {
+            writeln!(out, ".BANK {bank}").unwrap();
+        }
+        write_region(&mut out, region, &regions[0].labels);
+    }
+    out
+}
+
+/// Main memory and each non-empty bank, as in the executable.
+fn regions(executable: &Executable) -> Vec<Region> {
+    let region = |bank, base, bytes| Region {
+        bank,
+        base,
+        bytes,
+        code: BTreeMap::new(),
+        labels: HashMap::new(),
+    };
+
+    let mut banks: BTreeMap<u8, Vec<u8>> = BTreeMap::new();
+    for segment in &executable.segments {
+        let Some(bank) = segment.bank else {
+            continue;
+        };
+        let bytes = banks.entry(bank).or_default();
+        let offset = (segment.addr - BankWindow::BASE) as usize;
+        if bytes.len() < offset + segment.bytes.len() {
+            bytes.resize(offset + segment.bytes.len(), 0);
+        }
+        bytes[offset..offset + segment.bytes.len()].copy_from_slice(&segment.bytes);
+    }
+
+    std::iter::once(region(None, 0x00, executable.main_image()))
+        .chain(
+            banks
+                .into_iter()
+                .filter(|(_, bytes)| !bytes.is_empty())
+                .map(|(bank, bytes)| region(Some(bank), BankWindow::BASE, bytes)),
+        )
+        .collect()
+}
+
+/// Names every target that can carry a label. Jumps into the bank window
+/// from main memory stay numeric, since they could reach any bank.
+fn label(regions: &mut [Region], executable: &Executable, has_banks: bool) {
+    let in_window = |addr: u8| has_banks && (BankWindow::BASE..=BankWindow::END).contains(&addr);
+
+    let mut symbols: BTreeMap<u8, String> = BTreeMap::new();
+    for (name, addr) in executable.labels() {
+        if !in_window(addr) && symbols.get(&addr).is_none_or(|other| name < *other) {
+            symbols.insert(addr, name);
+        }
+    }
+
+    // (region, target, called)
+    let mut targets = Vec::new();
+    for (index, region) in regions.iter().enumerate() {
+        for instruction in region.code.values() {
+            let Some(target) = instruction.target() else {
+                continue;
+            };
+            let called = instruction.op == Op::CALL;
+            match (region.bank, in_window(target)) {
+                (Some(_), true) => targets.push((index, target, called)),
+                (_, false) => targets.push((0, target, called)),
+                (None, true) => {}
+            }
+        }
+    }
+    targets.push((0, executable.entry, false));
+    targets.extend(symbols.keys().map(|&addr| (0, addr, false)));
+
+    for (index, target, called) in targets {
+        let region = &regions[index];
+        if !region.at_boundary(target) {
+            continue;
+        }
+
+        let prefix = region
+            .bank
+            .map_or(String::new(), |bank| format!("b{bank}_"));
+        let name = match symbols.get(&target) {
+            Some(symbol) if region.bank.is_none() => symbol.clone(),
+            _ if region.bank.is_none()
+                && target == executable.entry
+                && !symbols.values().any(|s| s == "start") =>
+            {
+                "start".to_string()
+            }
+            _ if called => format!("{prefix}sub_{target:02x}"),
+            _ => format!("{prefix}lbl_{target:02x}"),
+        };
+
+        let labels = &mut regions[index].labels;
+        // A call anywhere makes the target a subroutine.
+        if called || !labels.contains_key(&target) {
+            labels.insert(target, name);
+        }
+    }
+}
+
+/// Writes the labels, code and data of `region`. Targets without a label
+/// in `region` are looked up in `main`.
+fn write_region(out: &mut String, region: &Region, main: &HashMap<u8, String>) {
+    let names = |target: u8| region.labels.get(&target).or_else(|| main.get(&target));
+
+    let mut offset = 0;
+    while offset < region.bytes.len() {
+        let addr = region.base + offset as u8;
+        if let Some(label) = region.labels.get(&addr) {
+            writeln!(out, "{label}:").unwrap();
+        }
+
+        if let Some(instruction) = region.code.get(&addr) {
+            match instruction.op {
+                Op::NOP => line(out, ".DB 0x01", &format!("{addr:#04X} NOP")),
+                _ => line(out, &instruction.text(names), &format!("{addr:#04X}")),
+            }
+            offset += instruction.op.instruction_len() as usize;
+            continue;
+        }
+
+        let end = (offset + 1..region.bytes.len())
+            .find(|&o| {
+                let addr = region.base + o as u8;
+                region.code.contains_key(&addr) || region.labels.contains_key(&addr)
+            })
+            .unwrap_or(region.bytes.len());
+        write_data(out, &region.bytes[offset..end], addr);
+        offset = end;
+    }
+}
+
+/// Writes `data` as `.STRING` runs of printable text and `.DB` lines.
+fn write_data(out: &mut String, data: &[u8], addr: u8) {
+    let printable = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .take_while(|&&b| (0x20..=0x7E).contains(&b) && b != b';')
+            .count()
+    };
+
+    let mut i = 0;
+    while i < data.len() {
+        let comment = format!("{:#04X}", addr.wrapping_add(i as u8));
+        let text = printable(&data[i..]);
+        if text >= MIN_STRING {
+            let string = String::from_utf8_lossy(&data[i..i + text]);
+            line(out, &format!(".STRING \"{string}\""), &comment);
+            i += text;
+            continue;
+        }
+
+        let mut end = i + 1;
+        while end < data.len() && end - i < DB_PER_LINE && printable(&data[end..]) < MIN_STRING {
+            end += 1;
+        }
+        let bytes: Vec<String> = data[i..end].iter().map(|b| format!("{b:#04X}")).collect();
+        line(out, &format!(".DB {}", bytes.join(" ")), &comment);
+        i = end;
+    }
+}
+
+fn line(out: &mut String, text: &str, comment: &str) {
+    writeln!(out, "    {text:<23} ; {comment}").unwrap();
+}
@@ -16,6 +16,8 @@
 
 use std::fmt;
 
+use crate::protection::AccessKind;
+
 /// Errors that can occur during emulation.
 #[derive(Debug, Clone, PartialEq)]
 pub enum EmulatorError {
@@ -40,6 +42,13 @@ pub enum EmulatorError {
     DivisionByZero { op: String },
     /// I/O operation error.
     IoError(String),
+    /// A device mapping is empty or overlaps an existing one.
+    BusConflict { start: u8, end: u8 },
+    /// The byte at `pc` is not a valid opcode.
+    IllegalOpcode { pc: u8, byte: u8 },
+    /// The instruction at `pc` accessed `addr` in a way its memory region
+    /// does not permit.
+    AccessViolation { addr: u8, pc: u8, kind: AccessKind },
 }
 
 impl fmt::Display for EmulatorError {
@@ -67,6 +76,23 @@ impl fmt::Display for EmulatorError {
             Self::IoError(msg) => {
                 write!(f, "I/O error: {}", msg)
             }
+            Self::BusConflict { start, end } => {
+                write!(
+                    f,
+                    "Cannot map device at {:#04X}-{:#04X}: range is empty or already mapped",
+                    start, end
+                )
+            }
+            Self::IllegalOpcode { pc, byte } => {
+                write!(f, "Illegal opcode {:#04X} at PC={:#04X}", byte, pc)
+            }
+            Self::AccessViolation { addr, pc, kind } => {
+                write!(
+                    f,
+                    "Access violation: {} access to {:#04X} at PC={:#04X}",
+                    kind, addr, pc
+                )
+            }
         }
     }
 }
@@ -81,6 +107,7 @@ pub enum AssemblerError {
     InvalidValue { value: String, line: usize },
     InvalidHexAddress { value: String, line: usize },
     UndefinedLabel { label: String, line: usize },
+    UnresolvedExtern { name: String, line: usize },
 }
 
 impl fmt::Display for AssemblerError {
@@ -101,14 +128,147 @@ impl fmt::Display for AssemblerError {
             Self::UndefinedLabel { label, line } => {
                 write!(f, "Line {}: Undefined label '{}'", line, label)
             }
+            Self::UnresolvedExtern { name, line } => {
+                write!(
+                    f,
+                    "Line {}: External symbol '{}' must be linked with ncld",
+                    line, name
+                )
+            }
         }
     }
 }
 
 impl std::error::Error for AssemblerError {}
 
+/// Errors that can occur while decoding a program or data file format.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatError {
+    /// The file does not start with the expected magic bytes.
+    BadMagic { expected: Vec<u8>, found: Vec<u8> },
+    /// The file was written by a newer, unknown version of the format.
+    UnsupportedVersion { version: u8 },
+    /// The file ends before `needed` more bytes could be read at `offset`.
+    Truncated { offset: usize, needed: usize },
+    /// A field holds a value the format does not allow.
+    InvalidData(String),
+    /// The stored checksum does not match the data. `line` is the 1-based
+    /// record line for text formats such as Intel HEX.
+    ChecksumMismatch {
+        line: Option<usize>,
+        expected: u32,
+        found: u32,
+    },
+    /// The file could not be read or written.
+    Io(String),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadMagic { expected, found } => {
+                write!(
+                    f,
+                    "Bad magic: expected {:?}, found {:?}",
+                    String::from_utf8_lossy(expected),
+                    String::from_utf8_lossy(found)
+                )
+            }
+            Self::UnsupportedVersion { version } => {
+                write!(f, "Unsupported format version {}", version)
+            }
+            Self::Truncated { offset, needed } => {
+                write!(
+                    f,
+                    "Unexpected end of file at offset {}: {} more bytes needed",
+                    offset, needed
+                )
+            }
+            Self::InvalidData(msg) => {
+                write!(f, "Invalid data: {}", msg)
+            }
+            Self::ChecksumMismatch {
+                line,
+                expected,
+                found,
+            } => {
+                if let Some(line) = line {
+                    write!(f, "Line {}: ", line)?;
+                }
+                write!(
+                    f,
+                    "Checksum mismatch: expected {:#04X}, found {:#04X}",
+                    expected, found
+                )
+            }
+            Self::Io(msg) => {
+                write!(f, "I/O error: {}", msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<std::io::Error> for FormatError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+/// Errors that can occur while linking object files.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkError {
+    /// `module` refers to a symbol that no module exports.
+    UndefinedSymbol { symbol: String, module: String },
+    /// Two modules export the same symbol.
+    DuplicateSymbol {
+        symbol: String,
+        first: String,
+        second: String,
+    },
+    /// `module` would end at `end`, past the end of memory.
+    OutOfMemory { module: String, end: usize },
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UndefinedSymbol { symbol, module } => {
+                write!(f, "{}: Undefined symbol '{}'", module, symbol)
+            }
+            Self::DuplicateSymbol {
+                symbol,
+                first,
+                second,
+            } => {
+                write!(
+                    f,
+                    "Symbol '{}' is exported by both {} and {}",
+                    symbol, first, second
+                )
+            }
+            Self::OutOfMemory { module, end } => {
+                write!(
+                    f,
+                    "{}: Program ends at {:#06X}, past the end of memory",
+                    module, end
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
 /// Result type for emulator operations.
 pub type EmulatorResult<T> = std::result::Result<T, EmulatorError>;
 
 /// Result type for assembler operations.
 pub type AssemblerResult<T> = std::result::Result<T, AssemblerError>;
+
+/// Result type for binary format decoding.
+pub type FormatResult<T> = std::result::Result<T, FormatError>;
+
+/// Result type for linking.
+pub type LinkResult<T> = std::result::Result<T, LinkError>;
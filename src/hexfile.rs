@@ -0,0 +1,344 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+    FormatError, FormatResult,
+    banking::BankWindow,
+    loader::{Executable, Segment},
+};
+
+/// Data bytes per record written.
+const RECORD_SIZE: usize = 16;
+
+/// Encodes `executable` as Intel HEX. The entry point is kept in the start
+/// address record; symbols are not stored.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{hexfile, loader::Executable};
+///
+/// let executable = Executable::from_raw(vec![0x02, 0x00, 0x07, 0x00]); // LDI R0 7; HLT
+/// let text = hexfile::to_ihex(&executable);
+/// assert_eq!(text, ":0400000002000700F3\n:0400000500000000F7\n:00000001FF\n");
+/// assert_eq!(hexfile::from_ihex(&text).unwrap(), executable);
+/// ```
+pub fn to_ihex(executable: &Executable) -> String {
+    let mut out = String::new();
+    let mut upper = 0;
+    for (addr, data) in records(executable) {
+        if addr >> 16 != upper {
+            upper = addr >> 16;
+            ihex_record(&mut out, 0, 0x04, &(upper as u16).to_be_bytes());
+        }
+        ihex_record(&mut out, addr as u16, 0x00, data);
+    }
+    ihex_record(&mut out, 0, 0x05, &(executable.entry as u32).to_be_bytes());
+    ihex_record(&mut out, 0, 0x01, &[]);
+    out
+}
+
+fn ihex_record(out: &mut String, addr: u16, kind: u8, data: &[u8]) {
+    let mut bytes = vec![data.len() as u8];
+    bytes.extend(addr.to_be_bytes());
+    bytes.push(kind);
+    bytes.extend(data);
+    let sum = bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    bytes.push(sum.wrapping_neg());
+
+    writeln!(out, ":{}", hex::encode_upper(bytes)).unwrap();
+}
+
+/// Decodes Intel HEX.
+///
+/// Data, end-of-file, extended segment and linear address, and start
+/// address records are understood.
+///
+/// # Errors
+///
+/// Returns [`FormatError::ChecksumMismatch`] for a corrupted record, or
+/// [`FormatError::InvalidData`] for a malformed record or an address that
+/// is neither in main memory nor in a bank window.
+pub fn from_ihex(text: &str) -> FormatResult<Executable> {
+    let mut bytes = BTreeMap::new();
+    let mut base = 0u32;
+    let mut entry = None;
+
+    for (i, line) in lines(text) {
+        let record = line
+            .strip_prefix(':')
+            .ok_or_else(|| invalid(i, "record does not start with ':'"))?;
+        let record = decode(i, record)?;
+        if record.is_empty() {
+            return Err(invalid(i, "record is too short"));
+        }
+
+        let (sum, checksum) = record.split_at(record.len() - 1);
+        let expected = sum
+            .iter()
+            .fold(0u8, |sum, b| sum.wrapping_add(*b))
+            .wrapping_neg();
+        check(i, expected, checksum[0])?;
+
+        let [len, hi, lo, kind, data @ ..] = sum else {
+            return Err(invalid(i, "record is too short"));
+        };
+        if data.len() != *len as usize {
+            return Err(invalid(i, "length does not match the data"));
+        }
+
+        let field = || data.iter().fold(0u32, |v, b| (v << 8) | *b as u32);
+        match kind {
+            0x00 => {
+                let addr = base + u16::from_be_bytes([*hi, *lo]) as u32;
+                for (offset, &byte) in data.iter().enumerate() {
+                    bytes.insert(addr + offset as u32, byte);
+                }
+            }
+            0x01 => break,
+            0x02 => base = field() << 4,
+            0x04 => base = field() << 16,
+            0x03 => {
+                let [cs_hi, cs_lo, ip_hi, ip_lo] = data[..] else {
+                    return Err(invalid(i, "start address must be 4 bytes"));
+                };
+                let segment = u16::from_be_bytes([cs_hi, cs_lo]) as u32;
+                entry = Some((segment << 4) + u16::from_be_bytes([ip_hi, ip_lo]) as u32);
+            }
+            0x05 => entry = Some(field()),
+            _ => return Err(invalid(i, &format!("unknown record type {:#04X}", kind))),
+        }
+    }
+
+    build(bytes, entry)
+}
+
+/// Encodes `executable` as S-records: an `S0` header, `S1` data, an `S5`
+/// record count and an `S9` start address. If bank 255 is used, its
+/// addresses need 24 bits, and `S2` and `S8` records are written instead.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{hexfile, loader::Executable};
+///
+/// let executable = Executable::from_raw(vec![0x02, 0x00, 0x07, 0x00]); // LDI R0 7; HLT
+/// let text = hexfile::to_srec(&executable);
+/// assert_eq!(text.lines().nth(1), Some("S107000002000700EF"));
+/// assert_eq!(hexfile::from_srec(&text).unwrap(), executable);
+/// ```
+pub fn to_srec(executable: &Executable) -> String {
+    let mut out = String::new();
+    srec_record(&mut out, 0, 0, 2, b"nanocore");
+
+    let records = records(executable);
+    let wide = records.iter().any(|&(addr, _)| addr > 0xFFFF);
+    let (data_kind, start_kind, addr_len) = if wide { (2, 8, 3) } else { (1, 9, 2) };
+    for (addr, data) in &records {
+        srec_record(&mut out, data_kind, *addr, addr_len, data);
+    }
+    srec_record(&mut out, 5, records.len() as u32, 2, &[]);
+    srec_record(&mut out, start_kind, executable.entry as u32, addr_len, &[]);
+    out
+}
+
+fn srec_record(out: &mut String, kind: u8, addr: u32, addr_len: usize, data: &[u8]) {
+    let mut bytes = vec![(data.len() + addr_len + 1) as u8];
+    bytes.extend(&addr.to_be_bytes()[4 - addr_len..]);
+    bytes.extend(data);
+    let sum = bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    bytes.push(!sum);
+
+    writeln!(out, "S{}{}", kind, hex::encode_upper(bytes)).unwrap();
+}
+
+/// Decodes S-records with 16, 24 or 32-bit addresses.
+///
+/// # Errors
+///
+/// Returns [`FormatError::ChecksumMismatch`] for a corrupted record, or
+/// [`FormatError::InvalidData`] for a malformed record or an address that
+/// is neither in main memory nor in a bank window.
+pub fn from_srec(text: &str) -> FormatResult<Executable> {
+    let mut bytes = BTreeMap::new();
+    let mut entry = None;
+
+    for (i, line) in lines(text) {
+        let mut chars = line.chars();
+        let (Some('S'), Some(kind)) = (chars.next(), chars.next()) else {
+            return Err(invalid(i, "record does not start with 'S'"));
+        };
+        let record = decode(i, chars.as_str())?;
+
+        let addr_len = match kind {
+            '0' | '1' | '5' | '9' => 2,
+            '2' | '6' | '8' => 3,
+            '3' | '7' => 4,
+            _ => return Err(invalid(i, &format!("unknown record type S{}", kind))),
+        };
+        // Count, address and checksum.
+        if record.len() < 1 + addr_len + 1 {
+            return Err(invalid(i, "record is too short"));
+        }
+
+        let (sum, checksum) = record.split_at(record.len() - 1);
+        let expected = !sum.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+        check(i, expected, checksum[0])?;
+
+        if sum[0] as usize != record.len() - 1 {
+            return Err(invalid(i, "byte count does not match the record"));
+        }
+
+        let (addr, data) = sum[1..].split_at(addr_len);
+        let addr = addr.iter().fold(0u32, |v, b| (v << 8) | *b as u32);
+
+        match kind {
+            '1' | '2' | '3' => {
+                for (offset, &byte) in data.iter().enumerate() {
+                    bytes.insert(addr + offset as u32, byte);
+                }
+            }
+            '7' | '8' | '9' => entry = Some(addr),
+            _ => {}
+        }
+    }
+
+    build(bytes, entry)
+}
+
+/// Returns `true` if `bytes` looks like Intel HEX text.
+pub fn is_ihex(bytes: &[u8]) -> bool {
+    bytes.first() == Some(&b':') && is_record_text(bytes, b':')
+}
+
+/// Returns `true` if `bytes` looks like S-record text.
+pub fn is_srec(bytes: &[u8]) -> bool {
+    bytes.first() == Some(&b'S')
+        && bytes.get(1).is_some_and(u8::is_ascii_digit)
+        && is_record_text(bytes, b'S')
+}
+
+/// Only hex digits, line breaks and the record mark: raw programs almost
+/// never consist of those bytes alone.
+fn is_record_text(bytes: &[u8], mark: u8) -> bool {
+    bytes
+        .iter()
+        .all(|&b| b.is_ascii_hexdigit() || b == mark || b == b'\n' || b == b'\r')
+}
+
+/// The bytes of every segment in records of up to [`RECORD_SIZE`], with
+/// banks moved above main memory: main memory is stored at `0x0000`–`0x00FF`,
+/// and bank `n` at `(n + 1) * 0x100` plus its address in the [`BankWindow`],
+/// so bank 255 lies just above `0xFFFF`.
+fn records(executable: &Executable) -> Vec<(u32, &[u8])> {
+    executable
+        .segments
+        .iter()
+        .flat_map(|segment| {
+            let page = segment.bank.map_or(0, |bank| bank as u32 + 1);
+            let base = (page << 8) + segment.addr as u32;
+            segment
+                .bytes
+                .chunks(RECORD_SIZE)
+                .enumerate()
+                .map(move |(i, chunk)| (base + (i * RECORD_SIZE) as u32, chunk))
+        })
+        .collect()
+}
+
+/// Groups loaded bytes into segments, splitting them back into main memory
+/// and banks.
+fn build(bytes: BTreeMap<u32, u8>, entry: Option<u32>) -> FormatResult<Executable> {
+    let mut segments: Vec<Segment> = Vec::new();
+
+    for (addr, byte) in bytes {
+        let page = addr >> 8;
+        let offset = (addr & 0xFF) as u8;
+        let bank = match page {
+            0 => None,
+            1..=0x100 if (BankWindow::BASE..=BankWindow::END).contains(&offset) => {
+                Some((page - 1) as u8)
+            }
+            _ => {
+                return Err(FormatError::InvalidData(format!(
+                    "address {:#06X} is outside main memory and the bank windows",
+                    addr
+                )));
+            }
+        };
+
+        match segments.last_mut() {
+            Some(last) if last.bank == bank && last.end() == offset as usize => {
+                last.bytes.push(byte)
+            }
+            _ => segments.push(Segment {
+                bank,
+                addr: offset,
+                bytes: vec![byte],
+            }),
+        }
+    }
+
+    let load_address = segments
+        .iter()
+        .find(|s| s.bank.is_none())
+        .map_or(0x00, |s| s.addr);
+    let entry = match entry {
+        Some(entry) => u8::try_from(entry).map_err(|_| {
+            FormatError::InvalidData(format!("entry point {:#06X} is outside memory", entry))
+        })?,
+        None => load_address,
+    };
+
+    Ok(Executable {
+        load_address,
+        entry,
+        segments,
+        symbols: Vec::new(),
+    })
+}
+
+/// Non-empty lines with their 1-based numbers.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn decode(line: usize, record: &str) -> FormatResult<Vec<u8>> {
+    match hex::decode(record) {
+        Ok(bytes) if !bytes.is_empty() => Ok(bytes),
+        _ => Err(invalid(line, "record is not a sequence of hex bytes")),
+    }
+}
+
+fn check(line: usize, expected: u8, found: u8) -> FormatResult<()> {
+    if expected == found {
+        return Ok(());
+    }
+    Err(FormatError::ChecksumMismatch {
+        line: Some(line),
+        expected: expected as u32,
+        found: found as u32,
+    })
+}
+
+fn invalid(line: usize, msg: &str) -> FormatError {
+    FormatError::InvalidData(format!("line {}: {}", line, msg))
+}
@@ -0,0 +1,94 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use crate::{Op, nanocore::Operands};
+
+/// An instruction decoded by
+/// [`NanoCore::fetch_decode`](crate::nanocore::NanoCore::fetch_decode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decoded {
+    pub opcode: u8,
+    pub op: Op,
+    pub operands: Operands,
+}
+
+/// Predecoded instructions, indexed by address.
+///
+/// Only instructions that lie entirely in RAM are cached. Any write to one of
+/// an instruction's bytes evicts it, so self-modifying code still sees the
+/// new bytes.
+#[derive(Debug, Clone)]
+pub struct ICache {
+    entries: Box<[Option<Decoded>; 256]>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl Default for ICache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ICache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            entries: Box::new([None; 256]),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the instruction cached at `addr`, counting the hit or miss.
+    pub fn get(&mut self, addr: u8) -> Option<Decoded> {
+        let entry = self.entries[addr as usize];
+        match entry {
+            Some(_) => self.hits += 1,
+            None => self.misses += 1,
+        }
+        entry
+    }
+
+    pub fn insert(&mut self, addr: u8, decoded: Decoded) {
+        self.entries[addr as usize] = Some(decoded);
+    }
+
+    /// Evicts every instruction that includes the byte at `addr`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanocore::{Op, icache::{Decoded, ICache}, nanocore::Operands};
+    ///
+    /// let mut icache = ICache::new();
+    /// let decoded = Decoded { opcode: 0x02, op: Op::LDI, operands: Operands::RegImm(0, 1) };
+    /// icache.insert(0x10, decoded);
+    ///
+    /// icache.invalidate(0x12); // the immediate byte
+    /// assert_eq!(icache.get(0x10), None);
+    /// ```
+    pub fn invalidate(&mut self, addr: u8) {
+        for back in 0..3 {
+            self.entries[addr.wrapping_sub(back) as usize] = None;
+        }
+    }
+
+    /// Evicts everything.
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+}
@@ -0,0 +1,165 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{any::Any, collections::VecDeque, io::Read};
+
+/// Input source and output sink used by the `IN` and `PRINT` instructions.
+///
+/// [`NanoCore`](crate::nanocore::NanoCore) holds a boxed backend so that the
+/// emulator can be embedded in test harnesses and the TUI without blocking
+/// on the real stdin. Backends are `Any` so callers can get the concrete
+/// type back with [`NanoCore::io_backend`](crate::nanocore::NanoCore::io_backend).
+pub trait IoBackend: Any + std::fmt::Debug {
+    /// Reads a single byte for the `IN` instruction.
+    fn read_byte(&mut self) -> crate::EmulatorResult<u8>;
+
+    /// Writes a single byte produced by the `PRINT` instruction.
+    fn write_byte(&mut self, byte: u8) -> crate::EmulatorResult<()>;
+}
+
+/// Reads from the process stdin and prints to stdout.
+///
+/// This is the default backend and matches the behavior of the `nanocore` binary.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StdIo;
+
+impl IoBackend for StdIo {
+    fn read_byte(&mut self) -> crate::EmulatorResult<u8> {
+        let mut buffer = [0; 1];
+        std::io::stdin()
+            .read_exact(&mut buffer)
+            .map_err(|e| crate::EmulatorError::IoError(e.to_string()))?;
+
+        Ok(buffer[0])
+    }
+
+    fn write_byte(&mut self, byte: u8) -> crate::EmulatorResult<()> {
+        print!("{}", byte as char);
+        Ok(())
+    }
+}
+
+/// Fully in-memory backend.
+///
+/// Input is consumed from a fixed buffer and output is captured for later
+/// inspection. Reading past the end of the input is an
+/// [`EmulatorError::IoError`](crate::EmulatorError::IoError).
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::io::{BufferIo, IoBackend};
+///
+/// let mut io = BufferIo::new("A");
+/// assert_eq!(io.read_byte().unwrap(), b'A');
+/// assert!(io.read_byte().is_err());
+///
+/// io.write_byte(b'!').unwrap();
+/// assert_eq!(io.output_string(), "!");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct BufferIo {
+    pub input: VecDeque<u8>,
+    pub output: Vec<u8>,
+}
+
+impl BufferIo {
+    #[must_use]
+    pub fn new(input: impl AsRef<[u8]>) -> Self {
+        Self {
+            input: input.as_ref().iter().copied().collect(),
+            output: Vec::new(),
+        }
+    }
+
+    /// Returns the captured output as a (lossy) UTF-8 string.
+    #[must_use]
+    pub fn output_string(&self) -> String {
+        String::from_utf8_lossy(&self.output).into_owned()
+    }
+}
+
+impl IoBackend for BufferIo {
+    fn read_byte(&mut self) -> crate::EmulatorResult<u8> {
+        self.input
+            .pop_front()
+            .ok_or_else(|| crate::EmulatorError::IoError("input buffer exhausted".to_string()))
+    }
+
+    fn write_byte(&mut self, byte: u8) -> crate::EmulatorResult<()> {
+        self.output.push(byte);
+        Ok(())
+    }
+}
+
+/// Scripted backend that never blocks and never fails.
+///
+/// Each `IN` consumes the next byte of the script; once the script runs out
+/// every further read returns the fallback byte (`0` by default). Output is
+/// captured like [`BufferIo`]. The TUI uses this so that `IN` cannot freeze
+/// the terminal.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::io::{IoBackend, ScriptedIo};
+///
+/// let mut io = ScriptedIo::new([1, 2]).with_fallback(0xFF);
+/// assert_eq!(io.read_byte().unwrap(), 1);
+/// assert_eq!(io.read_byte().unwrap(), 2);
+/// assert_eq!(io.read_byte().unwrap(), 0xFF);
+/// assert_eq!(io.reads, 3);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ScriptedIo {
+    pub script: VecDeque<u8>,
+    pub fallback: u8,
+    pub reads: usize,
+    pub output: Vec<u8>,
+}
+
+impl ScriptedIo {
+    #[must_use]
+    pub fn new(script: impl AsRef<[u8]>) -> Self {
+        Self {
+            script: script.as_ref().iter().copied().collect(),
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn with_fallback(mut self, fallback: u8) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Appends bytes to the end of the script.
+    pub fn push(&mut self, bytes: impl AsRef<[u8]>) {
+        self.script.extend(bytes.as_ref());
+    }
+}
+
+impl IoBackend for ScriptedIo {
+    fn read_byte(&mut self) -> crate::EmulatorResult<u8> {
+        self.reads += 1;
+        Ok(self.script.pop_front().unwrap_or(self.fallback))
+    }
+
+    fn write_byte(&mut self, byte: u8) -> crate::EmulatorResult<()> {
+        self.output.push(byte);
+        Ok(())
+    }
+}
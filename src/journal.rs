@@ -0,0 +1,133 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::collections::VecDeque;
+
+use crate::trace::TraceEntry;
+
+/// Everything needed to undo one cycle.
+///
+/// Registers, flags and device state are stored whole; RAM is stored as the
+/// previous value of each byte the cycle wrote.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    /// Value of [`NanoCore::cycle`](crate::nanocore::NanoCore::cycle) before the cycle ran.
+    pub cycle: u64,
+    pub t_states: u64,
+    pub registers: [u8; 16],
+    pub pc: u8,
+    pub sp: u8,
+    pub flags: u8,
+    pub is_halted: bool,
+    pub irq_pending: bool,
+    /// `(address, old value)` for every RAM write, in order.
+    pub memory: Vec<(u8, u8)>,
+    /// [`Device::save_state`](crate::bus::Device::save_state) of every mapping, in bus order.
+    pub devices: Vec<Vec<u8>>,
+    pub output_len: usize,
+    pub current_instruction: Option<TraceEntry>,
+    pub log_len: usize,
+    /// Instruction log entry dropped to make room during this cycle.
+    pub log_evicted: Option<TraceEntry>,
+}
+
+/// Bounded history of executed cycles, used by
+/// [`NanoCore::step_back`](crate::nanocore::NanoCore::step_back) and
+/// [`NanoCore::rewind_to`](crate::nanocore::NanoCore::rewind_to).
+///
+/// Side effects outside the emulator, such as bytes consumed from or written
+/// to the [`IoBackend`](crate::io::IoBackend), are not undone.
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    /// Maximum number of cycles kept. The oldest entry is dropped when full.
+    pub depth: usize,
+    entries: VecDeque<JournalEntry>,
+    pending: Option<JournalEntry>,
+}
+
+impl Journal {
+    pub const DEFAULT_DEPTH: usize = 1024;
+
+    #[must_use]
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            entries: VecDeque::with_capacity(depth.min(Self::DEFAULT_DEPTH)),
+            pending: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> &VecDeque<JournalEntry> {
+        &self.entries
+    }
+
+    /// The earliest cycle that can still be rewound to.
+    pub fn oldest_cycle(&self) -> Option<u64> {
+        self.entries.front().map(|e| e.cycle)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.pending = None;
+    }
+
+    /// Starts recording a cycle.
+    pub(crate) fn begin(&mut self, entry: JournalEntry) {
+        self.pending = Some(entry);
+    }
+
+    /// Records the previous value of a RAM byte about to be written.
+    pub(crate) fn record_write(&mut self, addr: u8, old: u8) {
+        if let Some(entry) = &mut self.pending {
+            entry.memory.push((addr, old));
+        }
+    }
+
+    /// Records an instruction log entry about to be dropped.
+    pub(crate) fn record_eviction(&mut self, evicted: TraceEntry) {
+        if let Some(entry) = &mut self.pending {
+            entry.log_evicted = Some(evicted);
+        }
+    }
+
+    /// Finishes the cycle started by [`begin`](Self::begin).
+    pub(crate) fn commit(&mut self) {
+        let Some(entry) = self.pending.take() else {
+            return;
+        };
+
+        if self.depth == 0 {
+            return;
+        }
+
+        if self.entries.len() >= self.depth {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<JournalEntry> {
+        self.entries.pop_back()
+    }
+}
@@ -15,14 +15,39 @@
 //!
 
 pub mod assembler;
+pub mod banking;
+pub mod bus;
+mod codec;
+pub mod coverage;
 pub mod cpu;
+pub mod devices;
+pub mod disassembler;
 pub mod error;
+pub mod hexfile;
+pub mod icache;
+pub mod io;
+pub mod journal;
+pub mod linker;
+pub mod loader;
+pub mod loop_detect;
 pub mod nanocore;
+pub mod object;
+pub mod observer;
+pub mod profiler;
+pub mod protection;
+pub mod snapshot;
+pub mod system;
+pub mod timing;
+pub mod trace;
 pub mod tui;
+pub mod watch;
 
-pub use error::{AssemblerError, AssemblerResult, EmulatorError, EmulatorResult};
+pub use error::{
+    AssemblerError, AssemblerResult, EmulatorError, EmulatorResult, FormatError, FormatResult,
+    LinkError, LinkResult,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Op {
     HLT, // Halt: HLT
 
@@ -77,19 +102,90 @@ pub enum Op {
     CALL,  // CALL a function: CALL raise_to_power
     CALLR, // CALL a function at address in register: CALLR Rx
     RET,   // Return from a function
+
+    EI,   // Enable interrupts
+    DI,   // Disable interrupts
+    RETI, // Return from an interrupt handler (restores flags and PC)
+
+    JC,   // Jump if carry set: JC 0xAB
+    JNC,  // Jump if carry not set: JNC 0xAB
+    JN,   // Jump if negative flag set: JN 0xAB
+    JNN,  // Jump if negative flag not set: JNN 0xAB
+    JLT,  // After CMP Rx Ry, jump if Rx < Ry (unsigned): JLT 0xAB
+    JGE,  // After CMP Rx Ry, jump if Rx >= Ry (unsigned): JGE 0xAB
+    JGT,  // After CMP Rx Ry, jump if Rx > Ry (unsigned): JGT 0xAB
+    JLE,  // After CMP Rx Ry, jump if Rx <= Ry (unsigned): JLE 0xAB
+    JLTS, // After CMP Rx Ry, jump if Rx < Ry (signed): JLTS 0xAB
+    JGES, // After CMP Rx Ry, jump if Rx >= Ry (signed): JGES 0xAB
+    JGTS, // After CMP Rx Ry, jump if Rx > Ry (signed): JGTS 0xAB
+    JLES, // After CMP Rx Ry, jump if Rx <= Ry (signed): JLES 0xAB
+
+    ADC,  // Add with carry: ADC Rx Ry (Rx = Rx + Ry + C)
+    ADCI, // Add immediate with carry: ADCI Rx 123 (Rx = Rx + 123 + C)
+    SBC,  // Subtract with borrow: SBC Rx Ry (Rx = Rx - Ry - C)
+    SBCI, // Subtract immediate with borrow: SBCI Rx 123 (Rx = Rx - 123 - C)
+    RCL,  // Rotate left through carry: RCL Rx
+    RCR,  // Rotate right through carry: RCR Rx
+
+    IDIV, // Signed divide: IDIV Rx Ry (Rx = Rx / Ry)
+    IMOD, // Signed remainder: IMOD Rx Ry (Rx = Rx % Ry)
+    JV,   // Jump if overflow (V set): JV 0x10
+    JNV,  // Jump if no overflow (V clear): JNV 0x10
+
+    TAS, // Test-and-set: TAS Rx 0xAB (Rx = [0xAB], [0xAB] = 1, atomically)
 }
 
 impl Op {
+    /// Returns `true` for instructions that may change the PC other than by
+    /// advancing it (jumps, calls and returns).
+    pub fn is_control_flow(&self) -> bool {
+        self.is_conditional_branch()
+            || matches!(
+                self,
+                Op::JMP | Op::JMPR | Op::CALL | Op::CALLR | Op::RET | Op::RETI
+            )
+    }
+
+    /// Returns `true` for jumps taken only when a flag condition holds.
+    pub fn is_conditional_branch(&self) -> bool {
+        matches!(
+            self,
+            Op::JZ
+                | Op::JNZ
+                | Op::JC
+                | Op::JNC
+                | Op::JN
+                | Op::JNN
+                | Op::JLT
+                | Op::JGE
+                | Op::JGT
+                | Op::JLE
+                | Op::JLTS
+                | Op::JGES
+                | Op::JGTS
+                | Op::JLES
+                | Op::JV
+                | Op::JNV
+        )
+    }
+
     pub fn instruction_len(&self) -> u8 {
+        if self.is_conditional_branch() {
+            return 2;
+        }
+
         match self {
             Op::LDI
             | Op::LDA
             | Op::STORE
+            | Op::TAS
             | Op::ADDI
             | Op::SUBI
             | Op::MULI
             | Op::DIVI
-            | Op::MODI => 3,
+            | Op::MODI
+            | Op::ADCI
+            | Op::SBCI => 3,
             Op::LDR | Op::MOV | Op::PUSH | Op::POP | Op::ADD | Op::SUB | Op::INC | Op::DEC => 2,
             Op::AND
             | Op::OR
@@ -100,8 +196,10 @@ impl Op {
             | Op::SHR
             | Op::ROL
             | Op::ROR => 2,
-            Op::JMP | Op::JMPR | Op::JZ | Op::JNZ | Op::PRINT | Op::IN => 2,
+            Op::JMP | Op::JMPR | Op::PRINT | Op::IN => 2,
             Op::MUL | Op::DIV | Op::MOD | Op::CALL | Op::CALLR | Op::STR => 2,
+            Op::ADC | Op::SBC | Op::RCL | Op::RCR => 2,
+            Op::IDIV | Op::IMOD => 2,
             _ => 1,
         }
     }
@@ -176,6 +274,37 @@ impl From<Op> for &str {
             Op::CALL => "CALL",
             Op::CALLR => "CALLR",
             Op::RET => "RET",
+
+            Op::EI => "EI",
+            Op::DI => "DI",
+            Op::RETI => "RETI",
+
+            Op::JC => "JC",
+            Op::JNC => "JNC",
+            Op::JN => "JN",
+            Op::JNN => "JNN",
+            Op::JLT => "JLT",
+            Op::JGE => "JGE",
+            Op::JGT => "JGT",
+            Op::JLE => "JLE",
+            Op::JLTS => "JLTS",
+            Op::JGES => "JGES",
+            Op::JGTS => "JGTS",
+            Op::JLES => "JLES",
+
+            Op::ADC => "ADC",
+            Op::ADCI => "ADCI",
+            Op::SBC => "SBC",
+            Op::SBCI => "SBCI",
+            Op::RCL => "RCL",
+            Op::RCR => "RCR",
+
+            Op::IDIV => "IDIV",
+            Op::IMOD => "IMOD",
+            Op::JV => "JV",
+            Op::JNV => "JNV",
+
+            Op::TAS => "TAS",
         }
     }
 }
@@ -237,6 +366,37 @@ impl TryFrom<&str> for Op {
             "CALLR" => Ok(Op::CALLR),
             "RET" => Ok(Op::RET),
 
+            "EI" => Ok(Op::EI),
+            "DI" => Ok(Op::DI),
+            "RETI" => Ok(Op::RETI),
+
+            "JC" => Ok(Op::JC),
+            "JNC" => Ok(Op::JNC),
+            "JN" => Ok(Op::JN),
+            "JNN" => Ok(Op::JNN),
+            "JLT" => Ok(Op::JLT),
+            "JGE" => Ok(Op::JGE),
+            "JGT" => Ok(Op::JGT),
+            "JLE" => Ok(Op::JLE),
+            "JLTS" => Ok(Op::JLTS),
+            "JGES" => Ok(Op::JGES),
+            "JGTS" => Ok(Op::JGTS),
+            "JLES" => Ok(Op::JLES),
+
+            "ADC" => Ok(Op::ADC),
+            "ADCI" => Ok(Op::ADCI),
+            "SBC" => Ok(Op::SBC),
+            "SBCI" => Ok(Op::SBCI),
+            "RCL" => Ok(Op::RCL),
+            "RCR" => Ok(Op::RCR),
+
+            "IDIV" => Ok(Op::IDIV),
+            "IMOD" => Ok(Op::IMOD),
+            "JV" => Ok(Op::JV),
+            "JNV" => Ok(Op::JNV),
+
+            "TAS" => Ok(Op::TAS),
+
             _ => Err(crate::AssemblerError::SyntaxError {
                 line: 0, // Line number not available here, will be filled by caller
                 message: format!("Invalid operation: {value}"),
@@ -245,9 +405,21 @@ impl TryFrom<&str> for Op {
     }
 }
 
-impl From<u8> for Op {
-    fn from(value: u8) -> Self {
-        match value {
+impl Op {
+    /// Decodes an opcode byte, treating unknown bytes as [`Op::NOP`].
+    ///
+    /// This is the lenient decoding used by debugger views and by the
+    /// emulator's compatibility mode.
+    pub fn decode_or_nop(byte: u8) -> Op {
+        Op::try_from(byte).unwrap_or(Op::NOP)
+    }
+}
+
+impl TryFrom<u8> for Op {
+    type Error = crate::EmulatorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(match value {
             0x00 => Op::HLT,
             0x01 => Op::NOP,
 
@@ -300,8 +472,44 @@ impl From<u8> for Op {
             0x26 => Op::CALLR,
             0x21 => Op::RET,
 
-            _ => Op::NOP,
-        }
+            0x28 => Op::EI,
+            0x29 => Op::DI,
+            0x2A => Op::RETI,
+
+            0x2B => Op::JC,
+            0x2C => Op::JNC,
+            0x2D => Op::JN,
+            0x2E => Op::JNN,
+            0x2F => Op::JLT,
+            0x30 => Op::JGE,
+            0x31 => Op::JGT,
+            0x32 => Op::JLE,
+            0x33 => Op::JLTS,
+            0x34 => Op::JGES,
+            0x35 => Op::JGTS,
+            0x36 => Op::JLES,
+
+            0x37 => Op::ADC,
+            0x38 => Op::ADCI,
+            0x39 => Op::SBC,
+            0x3A => Op::SBCI,
+            0x3B => Op::RCL,
+            0x3C => Op::RCR,
+
+            0x3D => Op::IDIV,
+            0x3E => Op::IMOD,
+            0x3F => Op::JV,
+            0x40 => Op::JNV,
+
+            0x41 => Op::TAS,
+
+            _ => {
+                return Err(crate::EmulatorError::IllegalOpcode {
+                    pc: 0, // PC not available here, will be filled by caller
+                    byte: value,
+                });
+            }
+        })
     }
 }
 
@@ -359,6 +567,37 @@ impl From<Op> for u8 {
             Op::CALL => 0x20,
             Op::CALLR => 0x26,
             Op::RET => 0x21,
+
+            Op::EI => 0x28,
+            Op::DI => 0x29,
+            Op::RETI => 0x2A,
+
+            Op::JC => 0x2B,
+            Op::JNC => 0x2C,
+            Op::JN => 0x2D,
+            Op::JNN => 0x2E,
+            Op::JLT => 0x2F,
+            Op::JGE => 0x30,
+            Op::JGT => 0x31,
+            Op::JLE => 0x32,
+            Op::JLTS => 0x33,
+            Op::JGES => 0x34,
+            Op::JGTS => 0x35,
+            Op::JLES => 0x36,
+
+            Op::ADC => 0x37,
+            Op::ADCI => 0x38,
+            Op::SBC => 0x39,
+            Op::SBCI => 0x3A,
+            Op::RCL => 0x3B,
+            Op::RCR => 0x3C,
+
+            Op::IDIV => 0x3D,
+            Op::IMOD => 0x3E,
+            Op::JV => 0x3F,
+            Op::JNV => 0x40,
+
+            Op::TAS => 0x41,
         }
     }
 }
@@ -0,0 +1,277 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    LinkError, LinkResult,
+    loader::{Executable, Segment, Symbol, SymbolKind},
+    object::Object,
+};
+
+/// Where a module's code was placed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub module: String,
+    pub addr: u8,
+    pub size: usize,
+}
+
+/// A label at its final address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkedSymbol {
+    pub name: String,
+    pub addr: u8,
+    pub module: String,
+    pub global: bool,
+}
+
+/// The output of [`Linker::link`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Linked {
+    /// The program. Global labels keep their names and local labels are
+    /// named `module.label`.
+    pub executable: Executable,
+    pub sections: Vec<Section>,
+    /// Every label, sorted by address, then module and name.
+    pub symbols: Vec<LinkedSymbol>,
+}
+
+/// Combines [`Object`]s into one [`Executable`].
+///
+/// Modules are placed one after another from the base address, in the order
+/// they were added. Each jump or call target naming a label is then
+/// rewritten: a label of the same module wins, otherwise the symbol must be
+/// exported with `.GLOBAL` by exactly one module.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{assembler::Assembler, linker::Linker, nanocore::NanoCore, object::Object};
+///
+/// let object = |asm: &str| {
+///     let mut assembler = Assembler::default();
+///     assembler.assemble(asm).unwrap();
+///     Object::from_assembler(&assembler).unwrap()
+/// };
+///
+/// let mut linker = Linker::new().with_entry("main");
+/// linker.add("lib", object(".GLOBAL seven\nseven:\nLDI R0 7\nRET"));
+/// linker.add("main", object(".GLOBAL main\n.EXTERN seven\nmain:\nCALL seven\nHLT"));
+/// let linked = linker.link().unwrap();
+/// assert_eq!(linked.sections[1].addr, 0x04);
+///
+/// let mut nano = NanoCore::new();
+/// linked.executable.load(&mut nano).unwrap();
+/// nano.run().unwrap();
+/// assert_eq!(nano.cpu.registers[0], 7);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Linker {
+    /// Address of the first module.
+    pub base: u8,
+    /// Global symbol to start at, instead of the base address.
+    pub entry: Option<String>,
+    pub modules: Vec<(String, Object)>,
+}
+
+impl Linker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Places the first module at `base`.
+    pub fn with_base(mut self, base: u8) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Starts the program at the global `symbol`.
+    pub fn with_entry(mut self, symbol: impl Into<String>) -> Self {
+        self.entry = Some(symbol.into());
+        self
+    }
+
+    /// Appends a module. `module` names it in the map and in errors.
+    pub fn add(&mut self, module: impl Into<String>, object: Object) {
+        self.modules.push((module.into(), object));
+    }
+
+    /// Places the modules, resolves their symbols and relocates the code.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinkError::OutOfMemory`] if the modules do not fit,
+    /// [`LinkError::DuplicateSymbol`] if two modules export the same name,
+    /// and [`LinkError::UndefinedSymbol`] for a reference, or the entry
+    /// symbol, that nothing defines.
+    pub fn link(&self) -> LinkResult<Linked> {
+        let mut sections = Vec::new();
+        let mut addr = self.base as usize;
+        for (module, object) in &self.modules {
+            if addr + object.code.len() > 256 {
+                return Err(LinkError::OutOfMemory {
+                    module: module.clone(),
+                    end: addr + object.code.len(),
+                });
+            }
+            sections.push(Section {
+                module: module.clone(),
+                addr: addr as u8,
+                size: object.code.len(),
+            });
+            addr += object.code.len();
+        }
+
+        let mut symbols = Vec::new();
+        let mut globals: HashMap<&str, (u8, &str)> = HashMap::new();
+        for ((module, object), section) in self.modules.iter().zip(&sections) {
+            for symbol in &object.symbols {
+                let addr = section.addr.wrapping_add(symbol.value);
+                if symbol.global
+                    && let Some((_, first)) = globals.insert(&symbol.name, (addr, module))
+                {
+                    return Err(LinkError::DuplicateSymbol {
+                        symbol: symbol.name.clone(),
+                        first: first.to_string(),
+                        second: module.clone(),
+                    });
+                }
+                symbols.push(LinkedSymbol {
+                    name: symbol.name.clone(),
+                    addr,
+                    module: module.clone(),
+                    global: symbol.global,
+                });
+            }
+        }
+        symbols.sort_by(|a, b| (a.addr, &a.module, &a.name).cmp(&(b.addr, &b.module, &b.name)));
+
+        let mut code = Vec::new();
+        for ((module, object), section) in self.modules.iter().zip(&sections) {
+            let start = code.len();
+            code.extend(&object.code);
+
+            for relocation in &object.relocations {
+                let local = object
+                    .symbols
+                    .iter()
+                    .find(|s| s.name == relocation.symbol)
+                    .map(|s| section.addr.wrapping_add(s.value));
+                let addr = local
+                    .or_else(|| {
+                        globals
+                            .get(relocation.symbol.as_str())
+                            .map(|&(addr, _)| addr)
+                    })
+                    .ok_or_else(|| LinkError::UndefinedSymbol {
+                        symbol: relocation.symbol.clone(),
+                        module: module.clone(),
+                    })?;
+                code[start + relocation.offset as usize] = addr;
+            }
+        }
+
+        let entry = match &self.entry {
+            Some(symbol) => {
+                globals
+                    .get(symbol.as_str())
+                    .ok_or_else(|| LinkError::UndefinedSymbol {
+                        symbol: symbol.clone(),
+                        module: "entry point".to_string(),
+                    })?
+                    .0
+            }
+            None => self.base,
+        };
+
+        let executable = Executable {
+            load_address: self.base,
+            entry,
+            segments: vec![Segment {
+                bank: None,
+                addr: self.base,
+                bytes: code,
+            }],
+            symbols: symbols
+                .iter()
+                .map(|s| Symbol {
+                    name: match s.global {
+                        true => s.name.clone(),
+                        false => format!("{}.{}", s.module, s.name),
+                    },
+                    kind: SymbolKind::Label,
+                    value: s.addr,
+                })
+                .collect(),
+        };
+
+        Ok(Linked {
+            executable,
+            sections,
+            symbols,
+        })
+    }
+}
+
+impl Linked {
+    /// A text map of the load address, entry point, module placement and
+    /// symbol addresses.
+    pub fn map(&self) -> String {
+        let width = self
+            .sections
+            .iter()
+            .map(|s| s.module.len())
+            .max()
+            .unwrap_or(0)
+            .max("Module".len());
+
+        let mut out = String::new();
+        writeln!(out, "Load address: {:#04X}", self.executable.load_address).unwrap();
+        writeln!(out, "Entry point:  {:#04X}", self.executable.entry).unwrap();
+
+        writeln!(out, "\nSections").unwrap();
+        writeln!(out, "  {:<4}  {:>5}  Module", "Addr", "Size").unwrap();
+        for section in &self.sections {
+            writeln!(
+                out,
+                "  {:#04X}  {:>5}  {}",
+                section.addr, section.size, section.module
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "\nSymbols").unwrap();
+        writeln!(
+            out,
+            "  {:<4}  {:<width$}  {:<6}  Name",
+            "Addr", "Module", "Scope"
+        )
+        .unwrap();
+        for symbol in &self.symbols {
+            writeln!(
+                out,
+                "  {:#04X}  {:<width$}  {:<6}  {}",
+                symbol.addr,
+                symbol.module,
+                if symbol.global { "global" } else { "local" },
+                symbol.name
+            )
+            .unwrap();
+        }
+        out
+    }
+}
@@ -0,0 +1,411 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::{
+    FormatError, FormatResult,
+    assembler::Assembler,
+    banking::{BankWindow, BankedImage, Banks, map_banks},
+    codec::{Reader, check_crc32, crc32, write_bytes},
+    hexfile,
+    nanocore::NanoCore,
+    object::Object,
+};
+
+/// A run of bytes loaded at one address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// The bank the bytes belong to, or `None` for main memory. Banked
+    /// segments are addressed through the [`BankWindow`].
+    pub bank: Option<u8>,
+    pub addr: u8,
+    pub bytes: Vec<u8>,
+}
+
+impl Segment {
+    /// Address one past the last byte.
+    pub fn end(&self) -> usize {
+        self.addr as usize + self.bytes.len()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Label = 0,
+    Constant = 1,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub value: u8,
+}
+
+/// A program ready to load: where it goes, where it starts, and the names
+/// it was assembled with.
+///
+/// [`parse`](Self::parse) accepts every program file the tools have written:
+/// this container (`.ncb` version 2), banked `NCBK` images, Intel HEX and
+/// S-records, and legacy raw `.ncb` files, which hold just the bytes loaded
+/// at `0x00`.
+///
+/// # File format
+///
+/// All integers are little-endian.
+///
+/// | Field | Size |
+/// | :--- | :--- |
+/// | Magic `NCBX` | 4 |
+/// | Version | 1 |
+/// | Load address, entry point | 2 |
+/// | Segment count (`u16`) | 2 |
+/// | Per segment: banked flag (`0` main memory, `1` bank), bank, address, length (`u16`) and bytes | 5 + n |
+/// | Symbol count (`u16`) | 2 |
+/// | Per symbol: kind (`0` label, `1` constant), value, name (`u32` length and bytes) | 6 + n |
+/// | CRC-32 of everything before it | 4 |
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{assembler::Assembler, loader::Executable, nanocore::NanoCore};
+///
+/// let mut assembler = Assembler::default();
+/// assembler.assemble("start:\nLDI R0 7\nHLT").unwrap();
+///
+/// let bytes = Executable::from_assembler(&assembler).to_bytes();
+/// let executable = Executable::parse(&bytes).unwrap();
+/// assert_eq!(executable.labels()["start"], 0x00);
+///
+/// let mut nano = NanoCore::new();
+/// executable.load(&mut nano).unwrap();
+/// nano.run().unwrap();
+/// assert_eq!(nano.cpu.registers[0], 7);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Executable {
+    /// Start of the main program, which memory protection treats as code.
+    pub load_address: u8,
+    /// Initial PC.
+    pub entry: u8,
+    pub segments: Vec<Segment>,
+    pub symbols: Vec<Symbol>,
+}
+
+impl Executable {
+    pub const MAGIC: &'static [u8; 4] = b"NCBX";
+    pub const VERSION: u8 = 2;
+
+    /// Wraps a legacy raw binary, loaded and started at `0x00`.
+    pub fn from_raw(program: Vec<u8>) -> Self {
+        Self::from_banked(BankedImage {
+            main: program,
+            banks: Vec::new(),
+        })
+    }
+
+    /// Converts a banked image: the main program at `0x00` and each bank at
+    /// the start of the window.
+    pub fn from_banked(image: BankedImage) -> Self {
+        let mut segments = vec![Segment {
+            bank: None,
+            addr: 0x00,
+            bytes: image.main,
+        }];
+        segments.extend(
+            image
+                .banks
+                .into_iter()
+                .enumerate()
+                .map(|(i, bytes)| Segment {
+                    bank: Some(i as u8),
+                    addr: BankWindow::BASE,
+                    bytes,
+                }),
+        );
+
+        Self {
+            load_address: 0x00,
+            entry: 0x00,
+            segments,
+            symbols: Vec::new(),
+        }
+    }
+
+    /// Packages the output of `assembler`, with its labels and constants as
+    /// symbols.
+    pub fn from_assembler(assembler: &Assembler) -> Self {
+        let mut executable = Self::from_banked(BankedImage {
+            main: assembler.program.clone(),
+            banks: assembler.banks.clone(),
+        });
+
+        let labels = assembler.labels.iter().map(|l| (l, SymbolKind::Label));
+        let constants = assembler
+            .constants
+            .iter()
+            .map(|c| (c, SymbolKind::Constant));
+        executable.symbols = labels
+            .chain(constants)
+            .map(|((name, &value), kind)| Symbol {
+                name: name.clone(),
+                kind,
+                value,
+            })
+            .collect();
+        executable
+            .symbols
+            .sort_by(|a, b| (a.value, &a.name).cmp(&(b.value, &b.name)));
+        executable
+    }
+
+    /// Returns `true` if `bytes` starts with the container magic.
+    pub fn is_executable(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::MAGIC)
+    }
+
+    /// Decodes any program file, telling the formats apart by their magic or,
+    /// for Intel HEX and S-records, their text.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`from_bytes`](Self::from_bytes),
+    /// [`BankedImage::from_bytes`], [`hexfile::from_ihex`] or
+    /// [`hexfile::from_srec`], and [`FormatError::InvalidData`] for an
+    /// unlinked [`Object`].
+    pub fn parse(bytes: &[u8]) -> FormatResult<Self> {
+        if Self::is_executable(bytes) {
+            Self::from_bytes(bytes)
+        } else if BankedImage::is_banked(bytes) {
+            BankedImage::from_bytes(bytes).map(Self::from_banked)
+        } else if Object::is_object(bytes) {
+            Err(FormatError::InvalidData(
+                "object files must be linked with ncld before they can run".to_string(),
+            ))
+        } else if hexfile::is_ihex(bytes) {
+            hexfile::from_ihex(&String::from_utf8_lossy(bytes))
+        } else if hexfile::is_srec(bytes) {
+            hexfile::from_srec(&String::from_utf8_lossy(bytes))
+        } else {
+            Ok(Self::from_raw(bytes.to_vec()))
+        }
+    }
+
+    /// Reads and [`parse`](Self::parse)s a program file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::Io`] if the file cannot be read, or any error
+    /// from [`parse`](Self::parse).
+    pub fn read(path: impl AsRef<Path>) -> FormatResult<Self> {
+        Self::parse(&fs::read(path)?)
+    }
+
+    /// Writes the program in the container format.
+    pub fn write(&self, path: impl AsRef<Path>) -> FormatResult<()> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Main memory from `0x00` to the end of the last segment, with gaps
+    /// zeroed.
+    pub fn main_image(&self) -> Vec<u8> {
+        let main = self.segments.iter().filter(|s| s.bank.is_none());
+        let mut image = vec![0; main.clone().map(Segment::end).max().unwrap_or(0)];
+        for segment in main {
+            image[segment.addr as usize..segment.end()].copy_from_slice(&segment.bytes);
+        }
+        image
+    }
+
+    /// The contents of every bank up to the highest one used, each as large
+    /// as the window.
+    pub fn banks(&self) -> Vec<Vec<u8>> {
+        let mut banks = Vec::new();
+        for segment in &self.segments {
+            let Some(bank) = segment.bank else {
+                continue;
+            };
+            if banks.len() <= bank as usize {
+                banks.resize(bank as usize + 1, vec![0; BankWindow::SIZE]);
+            }
+            let offset = (segment.addr - BankWindow::BASE) as usize;
+            banks[bank as usize][offset..offset + segment.bytes.len()]
+                .copy_from_slice(&segment.bytes);
+        }
+        banks
+    }
+
+    pub fn has_banks(&self) -> bool {
+        self.segments.iter().any(|s| s.bank.is_some())
+    }
+
+    /// Total number of bytes in all segments.
+    pub fn size(&self) -> usize {
+        self.segments.iter().map(|s| s.bytes.len()).sum()
+    }
+
+    /// Labels by name, as in [`Assembler::labels`].
+    pub fn labels(&self) -> HashMap<String, u8> {
+        self.symbols
+            .iter()
+            .filter(|s| s.kind == SymbolKind::Label)
+            .map(|s| (s.name.clone(), s.value))
+            .collect()
+    }
+
+    /// Loads the segments, maps the banks if there are any, and sets the PC
+    /// to the entry point.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`NanoCore::load_program`] or [`map_banks`].
+    pub fn load(&self, nano: &mut NanoCore) -> crate::EmulatorResult<()> {
+        for segment in self.segments.iter().filter(|s| s.bank.is_none()) {
+            nano.load_program(&segment.bytes, segment.addr)?;
+        }
+
+        if self.has_banks() {
+            map_banks(
+                &mut nano.cpu.bus,
+                Banks::with_contents(&self.banks(), BankWindow::SIZE),
+            )?;
+        }
+
+        nano.cpu.pc = self.entry;
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend(Self::MAGIC);
+        out.push(Self::VERSION);
+        out.extend([self.load_address, self.entry]);
+
+        out.extend((self.segments.len() as u16).to_le_bytes());
+        for segment in &self.segments {
+            out.extend([
+                segment.bank.is_some() as u8,
+                segment.bank.unwrap_or(0),
+                segment.addr,
+            ]);
+            out.extend((segment.bytes.len() as u16).to_le_bytes());
+            out.extend(&segment.bytes);
+        }
+
+        out.extend((self.symbols.len() as u16).to_le_bytes());
+        for symbol in &self.symbols {
+            out.extend([symbol.kind as u8, symbol.value]);
+            write_bytes(&mut out, symbol.name.as_bytes());
+        }
+
+        out.extend(crc32(&out).to_le_bytes());
+        out
+    }
+
+    /// Decodes a container written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FormatError`] if the magic, version or checksum does not
+    /// match, the data is truncated, or a segment does not fit in memory or
+    /// the bank window.
+    pub fn from_bytes(bytes: &[u8]) -> FormatResult<Self> {
+        let (body, checksum) = bytes.split_at(bytes.len().saturating_sub(4));
+        let mut r = Reader::new(body);
+        r.magic(Self::MAGIC)?;
+
+        let version = r.u8()?;
+        if version != Self::VERSION {
+            return Err(FormatError::UnsupportedVersion { version });
+        }
+
+        check_crc32(body, checksum)?;
+
+        let [load_address, entry] = r.array()?;
+
+        let count = r.u16()?;
+        let mut segments = Vec::with_capacity(count as usize);
+        for i in 0..count {
+            let [banked, bank, addr] = r.array()?;
+            let len = r.u16()? as usize;
+            let segment = Segment {
+                bank: match banked {
+                    0 => None,
+                    1 => Some(bank),
+                    _ => {
+                        return Err(FormatError::InvalidData(format!(
+                            "segment {} has an invalid banked flag {}",
+                            i, banked
+                        )));
+                    }
+                },
+                addr,
+                bytes: r.take(len)?.to_vec(),
+            };
+
+            let fits = match segment.bank {
+                None => segment.end() <= 256,
+                Some(_) => {
+                    addr >= BankWindow::BASE && segment.end() <= BankWindow::END as usize + 1
+                }
+            };
+            if !fits {
+                return Err(FormatError::InvalidData(format!(
+                    "segment {} ({} bytes at {:#04X}) does not fit in {}",
+                    i,
+                    len,
+                    addr,
+                    if segment.bank.is_some() {
+                        "the bank window"
+                    } else {
+                        "memory"
+                    }
+                )));
+            }
+            segments.push(segment);
+        }
+
+        let count = r.u16()?;
+        let mut symbols = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let [kind, value] = r.array()?;
+            let kind = match kind {
+                0 => SymbolKind::Label,
+                1 => SymbolKind::Constant,
+                _ => {
+                    return Err(FormatError::InvalidData(format!(
+                        "unknown symbol kind {}",
+                        kind
+                    )));
+                }
+            };
+            symbols.push(Symbol {
+                name: r.string()?,
+                kind,
+                value,
+            });
+        }
+
+        Ok(Self {
+            load_address,
+            entry,
+            segments,
+            symbols,
+        })
+    }
+}
@@ -0,0 +1,228 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::{Op, cpu::CPU};
+
+/// An infinite loop found by [`LoopDetector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoopHit {
+    /// Lowest address executed in the loop.
+    pub start: u8,
+    /// Highest address executed in the loop.
+    pub end: u8,
+    /// Instructions per iteration.
+    pub period: u64,
+}
+
+impl fmt::Display for LoopHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:#04X}-{:#04X}, repeating every {} instruction{}",
+            self.start,
+            self.end,
+            self.period,
+            if self.period == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Detects hangs by remembering hashes of the machine state: registers,
+/// flags, PC, SP, RAM and the state of every device.
+///
+/// Execution is deterministic, so once a state repeats the CPU is stuck in a
+/// loop. Every loop contains a step that does not move the PC forward (a
+/// backward jump, a return, or wrapping past `0xFF`), so the state is only
+/// hashed after those. Two states can share a hash, so a repeated hash only
+/// keeps a full copy of the state, and the loop is reported when that exact
+/// state comes round again: one iteration after the first repeat. `IN`
+/// consumes outside input, so it forgets the history.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::nanocore::{NanoCore, StopReason};
+///
+/// let mut nano = NanoCore::new().with_loop_detection();
+/// // LDI R0 1; loop: JMP loop
+/// nano.load_program(&[0x02, 0x00, 0x01, 0x16, 0x03], 0x00).unwrap();
+///
+/// let StopReason::Loop(hit) = nano.run_until(|_| false) else {
+///     panic!("expected a loop");
+/// };
+/// assert_eq!((hit.start, hit.end, hit.period), (0x03, 0x03, 1));
+/// assert_eq!(nano.cycle, 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LoopDetector {
+    /// State hash -> value of `instructions` when it was seen.
+    seen: HashMap<u64, u64>,
+    /// Value of `instructions` when each address last ran, `0` if never.
+    last_run: Box<[u64; 256]>,
+    /// Hash and full state at the last repeated hash, waiting to recur.
+    candidate: Option<(u64, Vec<u8>)>,
+    instructions: u64,
+    /// Set by the instruction that closed a loop.
+    pub hit: Option<LoopHit>,
+}
+
+impl Default for LoopDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoopDetector {
+    /// States remembered before the history is dropped, bounding memory use
+    /// on long runs.
+    pub const CAPACITY: usize = 1 << 16;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            seen: HashMap::new(),
+            last_run: Box::new([0; 256]),
+            candidate: None,
+            instructions: 0,
+            hit: None,
+        }
+    }
+
+    /// Records the instruction at `pc`, with `cpu` in the state after it.
+    pub fn after_execute(&mut self, cpu: &CPU, pc: u8, op: Op) {
+        self.hit = None;
+        self.instructions += 1;
+        self.last_run[pc as usize] = self.instructions;
+
+        if op == Op::IN {
+            self.clear();
+            return;
+        }
+        if cpu.pc > pc {
+            return;
+        }
+
+        if self.seen.len() >= Self::CAPACITY {
+            self.seen.clear();
+        }
+        let hash = Self::hash(cpu);
+        let Some(then) = self.seen.insert(hash, self.instructions) else {
+            return;
+        };
+
+        let state = Self::state(cpu);
+        match &self.candidate {
+            Some((candidate, full)) if *candidate == hash && *full == state => {}
+            _ => {
+                self.candidate = Some((hash, state));
+                return;
+            }
+        }
+
+        // Everything that ran since the state was first seen is the loop.
+        let body = (0..=255u8).filter(|&addr| self.last_run[addr as usize] > then);
+        let (start, end) = body.fold((u8::MAX, u8::MIN), |(lo, hi), addr| {
+            (lo.min(addr), hi.max(addr))
+        });
+        self.hit = Some(LoopHit {
+            start,
+            end,
+            period: self.instructions - then,
+        });
+    }
+
+    /// Forgets the history, for when the state changes other than by
+    /// execution.
+    pub fn clear(&mut self) {
+        self.seen.clear();
+        self.candidate = None;
+        self.hit = None;
+    }
+
+    fn hash(cpu: &CPU) -> u64 {
+        let mut hasher = StateHasher(0);
+        Self::feed(cpu, &mut hasher);
+        hasher.finish()
+    }
+
+    /// The bytes [`hash`](Self::hash) covers.
+    fn state(cpu: &CPU) -> Vec<u8> {
+        let mut recorder = StateRecorder(Vec::new());
+        Self::feed(cpu, &mut recorder);
+        recorder.0
+    }
+
+    fn feed(cpu: &CPU, hasher: &mut impl Hasher) {
+        cpu.registers.hash(hasher);
+        cpu.flags.hash(hasher);
+        cpu.pc.hash(hasher);
+        cpu.sp.hash(hasher);
+        cpu.irq_pending.hash(hasher);
+        cpu.irq_vector.hash(hasher);
+        cpu.bus.ram().hash(hasher);
+        for m in cpu.bus.mappings() {
+            m.device.save_state().hash(hasher);
+        }
+    }
+}
+
+/// A fast non-cryptographic hasher (the `FxHash` mix), eight bytes at a
+/// time. The state hash runs on every backward jump, where SipHash is
+/// noticeably slower.
+struct StateHasher(u64);
+
+impl StateHasher {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    fn add(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+impl Hasher for StateHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        for &byte in chunks.remainder() {
+            self.add(byte as u64);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Collects the bytes written to it instead of hashing them.
+struct StateRecorder(Vec<u8>);
+
+impl Hasher for StateRecorder {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        0
+    }
+}
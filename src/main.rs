@@ -16,17 +16,43 @@
 //!
 
 use clap::Parser;
-use std::fs;
+use std::{collections::HashMap, fs};
 
-use nanocore::{assembler::Assembler, nanocore::NanoCore};
+use nanocore::{
+    assembler::Assembler,
+    banking::BankWindow,
+    coverage::Coverage,
+    loader::Executable,
+    nanocore::{NanoCore, StopReason},
+    profiler::Profiler,
+    protection::{MemoryMap, Permissions, Region},
+    snapshot::Snapshot,
+    system::{Schedule, System, SystemStop},
+    watch::Watchpoint,
+};
 
 #[derive(Parser, Debug)]
 #[command(name = "nanocore")]
 #[command(version, about = "Run NanoCore", long_about = None)]
 struct Args {
     /// Path to the source assembly file / compiled bin file
-    #[arg(index = 1)]
-    input: String,
+    #[arg(index = 1, required_unless_present_any = ["load_state", "cores"])]
+    input: Option<String>,
+
+    /// Run a multi-core system with one program per core (repeat for each core)
+    #[arg(
+        long = "core",
+        value_name = "FILE",
+        conflicts_with_all = [
+            "input", "protect", "load_state", "save_state", "profile", "collapsed",
+            "coverage", "coverage_data", "lcov"
+        ]
+    )]
+    cores: Vec<String>,
+
+    /// Switch cores every N instructions instead of running them in lock-step
+    #[arg(long, value_name = "N", requires = "cores")]
+    quantum: Option<u64>,
 
     /// Print output
     #[arg(short, long, default_value_t = true)]
@@ -39,35 +65,289 @@ struct Args {
     /// Print instructions
     #[arg(short = 'i', long, default_value_t = false)]
     print_instructions: bool,
+
+    /// Maximum number of cycles before the run is force-halted
+    #[arg(short = 'c', long, default_value_t = NanoCore::DEFAULT_MAX_CYCLES)]
+    max_cycles: u64,
+
+    /// Execute unknown opcodes as NOP instead of stopping with an error
+    #[arg(long, default_value_t = false)]
+    lenient: bool,
+
+    /// Enforce memory protection: code is read-execute, the stack is stack-only
+    #[arg(long, default_value_t = false, requires = "input")]
+    protect: bool,
+
+    /// Cache decoded instructions and skip the instruction trace
+    #[arg(long, default_value_t = false)]
+    fast: bool,
+
+    /// Keep running when the machine state repeats instead of stopping with
+    /// an infinite-loop report (multi-core runs never check)
+    #[arg(long, default_value_t = false)]
+    no_loop_detection: bool,
+
+    /// Throttle execution to this clock frequency (T-states per second)
+    #[arg(long, value_name = "HZ")]
+    clock_hz: Option<u64>,
+
+    /// Stop when a watchpoint triggers (e.g. `write:0x80`, `R3=0x10`, `flag:Z`);
+    /// may be repeated
+    #[arg(long = "watch", value_name = "SPEC")]
+    watchpoints: Vec<Watchpoint>,
+
+    /// Print execution counts per address, label, subroutine and basic block
+    /// after the run, and annotate the source of a .nca program
+    #[arg(long, default_value_t = false)]
+    profile: bool,
+
+    /// Write the profile as collapsed stacks for flame graph tools
+    #[arg(long, value_name = "FILE")]
+    collapsed: Option<String>,
+
+    /// Print the source annotated with line and branch coverage after the run
+    #[arg(long, default_value_t = false)]
+    coverage: bool,
+
+    /// Add this run's coverage to FILE (created if missing) and report the
+    /// combined coverage of every run recorded there
+    #[arg(long, value_name = "FILE")]
+    coverage_data: Option<String>,
+
+    /// Write coverage as an LCOV tracefile
+    #[arg(long, value_name = "FILE")]
+    lcov: Option<String>,
+
+    /// Resume from a save state instead of starting fresh
+    #[arg(long, value_name = "FILE")]
+    load_state: Option<String>,
+
+    /// Write a save state when the run stops
+    #[arg(long, value_name = "FILE")]
+    save_state: Option<String>,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
-    let bytes = if args.input.ends_with(".nca") {
-        let asm = fs::read_to_string(&args.input)
-            .map_err(|e| format!("Failed to read '{}': {}", args.input, e))?;
+    if !args.cores.is_empty() {
+        return run_system(args);
+    }
 
-        if args.print_state {
-            println!("Assembling {}", &args.input);
+    let mut nano = NanoCore::new().with_max_cycles(args.max_cycles);
+    if args.fast {
+        nano = nano.with_fast_mode();
+    }
+    if !args.no_loop_detection {
+        nano = nano.with_loop_detection();
+    }
+    nano.print = args.print;
+    nano.print_state = args.print_state;
+    nano.print_instructions = args.print_instructions;
+    nano.lenient_decoding = args.lenient;
+    nano.clock_hz = args.clock_hz;
+    nano.watchpoints = args.watchpoints.clone();
+
+    let mut source = None;
+    let mut program = Vec::new();
+    let mut labels = HashMap::new();
+    if let Some(input) = &args.input {
+        let (executable, assembler) = read_program(input, args.print_state)?;
+        executable.load(&mut nano)?;
+        source = assembler;
+        program = executable.main_image();
+        labels = executable.labels();
+
+        if args.protect {
+            let code = program
+                .len()
+                .saturating_sub(executable.load_address as usize);
+            let mut map = MemoryMap::standard(executable.load_address, code);
+            if executable.has_banks() {
+                map.regions.push(Region::new(
+                    "banks",
+                    BankWindow::BASE,
+                    BankWindow::END,
+                    Permissions::RW.union(Permissions::EXECUTE),
+                ));
+            }
+            nano.memory_map = Some(map);
         }
+    }
 
-        let mut c = Assembler::default();
-        c.assemble(&asm)
-            .map_err(|e| format!("Assembly failed: {}", e))?;
+    if let Some(path) = &args.load_state {
+        let snapshot =
+            Snapshot::load(path).map_err(|e| format!("Failed to load state '{}': {}", path, e))?;
+        nano.restore(&snapshot);
+        // The cycle budget applies to this run, not the whole history.
+        nano.max_cycles = nano.cycle.saturating_add(args.max_cycles);
+    }
 
-        c.program
-    } else {
-        fs::read(&args.input).map_err(|e| format!("Failed to read '{}': {}", args.input, e))?
+    let profiling = args.profile || args.collapsed.is_some();
+    if profiling {
+        nano.add_observer(Profiler::new().with_labels(&labels));
+    }
+
+    if args.coverage || args.coverage_data.is_some() || args.lcov.is_some() {
+        nano.add_observer(Coverage::new(&program));
+    }
+
+    let result = nano.run();
+
+    if let Some(profiler) = nano.observer::<Profiler>() {
+        if args.profile {
+            println!("\n{}", profiler.report());
+            if let Some(assembler) = &source {
+                println!("{}", profiler.annotate(&assembler.asm, &assembler.line_map));
+            }
+        }
+
+        if let Some(path) = &args.collapsed {
+            fs::write(path, profiler.collapsed())
+                .map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+        }
+    }
+
+    if let Some(coverage) = nano.observer::<Coverage>() {
+        report_coverage(&args, coverage, source.as_ref())?;
+    }
+
+    if let Some(path) = &args.save_state {
+        nano.snapshot()
+            .save(path)
+            .map_err(|e| format!("Failed to save state '{}': {}", path, e))?;
+    }
+
+    result?;
+
+    Ok(())
+}
+
+fn run_system(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    let schedule = match args.quantum {
+        Some(quantum) => Schedule::RoundRobin { quantum },
+        None => Schedule::Lockstep,
     };
+    let cores = args
+        .cores
+        .iter()
+        .map(|_| {
+            let mut nano = NanoCore::new().with_max_cycles(args.max_cycles);
+            if args.fast {
+                nano = nano.with_fast_mode();
+            }
+            nano.print = args.print;
+            nano.print_state = args.print_state;
+            nano.print_instructions = args.print_instructions;
+            nano.lenient_decoding = args.lenient;
+            nano.clock_hz = args.clock_hz;
+            nano.watchpoints = args.watchpoints.clone();
+            nano
+        })
+        .collect();
+    let mut system = System::from_cores(cores)?.with_schedule(schedule);
 
-    let mut nano = NanoCore::new();
-    nano.print = args.print;
-    nano.print_state = args.print_state;
-    nano.print_instructions = args.print_instructions;
+    for (core, input) in args.cores.iter().enumerate() {
+        let (executable, _) = read_program(input, args.print_state)?;
+        system
+            .load(core, &executable)
+            .map_err(|e| format!("Failed to load '{}': {}", input, e))?;
+    }
 
-    nano.load_program(&bytes, 0x00)?;
-    nano.run()?;
+    match system.run() {
+        SystemStop::Core {
+            core,
+            reason: StopReason::Error(e),
+        } => return Err(format!("Core {}: {}", core, e).into()),
+        SystemStop::Core {
+            core,
+            reason: StopReason::Watchpoint(hit),
+        } => println!("\n== WATCHPOINT - core {core}: {hit} ==\n"),
+        SystemStop::Core {
+            core,
+            reason: StopReason::CycleLimit,
+        } => println!("\n== FORCE HALT - max cycles (core {core}) ==\n"),
+        _ => {}
+    }
+
+    if args.print_state {
+        for (i, nano) in system.cores.iter().enumerate() {
+            println!("Core {i}");
+            nano.cpu.print_state(nano.cycle, nano.t_states);
+        }
+    }
 
     Ok(())
 }
+
+fn report_coverage(
+    args: &Args,
+    coverage: &Coverage,
+    source: Option<&Assembler>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut coverage = coverage.clone();
+
+    if let Some(path) = &args.coverage_data {
+        if fs::exists(path)? {
+            let previous = Coverage::load(path)
+                .map_err(|e| format!("Failed to load coverage '{}': {}", path, e))?;
+            if previous.program != coverage.program {
+                return Err(format!("Coverage data '{}' is for a different program", path).into());
+            }
+            coverage.merge(&previous);
+        }
+
+        coverage
+            .save(path)
+            .map_err(|e| format!("Failed to save coverage '{}': {}", path, e))?;
+    }
+
+    if !args.coverage && args.lcov.is_none() {
+        return Ok(());
+    }
+
+    let (Some(assembler), Some(input)) = (source, &args.input) else {
+        return Err("Coverage reports need the .nca source".into());
+    };
+
+    if args.coverage {
+        println!("\n{}", coverage.report(&assembler.asm, &assembler.line_map));
+    }
+
+    if let Some(path) = &args.lcov {
+        fs::write(
+            path,
+            coverage.lcov(input, &assembler.asm, &assembler.line_map),
+        )
+        .map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+    }
+
+    Ok(())
+}
+
+/// Reads a binary, or assembles a `.nca` file and also returns the assembler
+/// for its labels and source.
+fn read_program(
+    input: &str,
+    print_state: bool,
+) -> Result<(Executable, Option<Assembler>), Box<dyn std::error::Error>> {
+    if input.ends_with(".nca") {
+        let asm =
+            fs::read_to_string(input).map_err(|e| format!("Failed to read '{}': {}", input, e))?;
+
+        if print_state {
+            println!("Assembling {}", input);
+        }
+
+        let mut c = Assembler::default();
+        c.assemble(&asm)
+            .and_then(|_| c.check_linked())
+            .map_err(|e| format!("Assembly failed: {}", e))?;
+
+        Ok((Executable::from_assembler(&c), Some(c)))
+    } else {
+        let executable =
+            Executable::read(input).map_err(|e| format!("Failed to read '{}': {}", input, e))?;
+        Ok((executable, None))
+    }
+}
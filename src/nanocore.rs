@@ -14,27 +14,75 @@
 //! language programming.
 //!
 
-use std::{collections::VecDeque, io::Read};
+use std::{
+    any::Any,
+    collections::VecDeque,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Op,
+    cpu::CPU,
+    end_color,
+    icache::{Decoded, ICache},
+    io::{IoBackend, StdIo},
+    journal::{Journal, JournalEntry},
+    loop_detect::{LoopDetector, LoopHit},
+    observer::Observer,
+    protection::{AccessKind, MemoryMap},
+    snapshot::{DeviceState, Snapshot},
+    start_color,
+    timing::Timing,
+    trace::{MemRead, MemWrite, TraceEntry},
+    watch::{WatchHit, Watchpoint},
+};
 
-use crate::{Op, cpu::CPU, end_color, start_color};
-
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct NanoCore {
     pub cpu: CPU,
-    pub cycle: u16,
-    pub current_instruction: String,
-    pub current_instruction_bin: String,
-    pub current_skipped: bool,
-    pub instruction_log: VecDeque<String>,
+    pub cycle: u64,
+    pub max_cycles: u64,
+    /// Elapsed clock ticks, weighted by [`timing`](Self::timing).
+    pub t_states: u64,
+    pub timing: Timing,
+    /// Target clock frequency. `None` runs as fast as possible.
+    pub clock_hz: Option<u64>,
+    clock_origin: Option<(Instant, u64)>,
+    pub breakpoints: Vec<u8>,
+    /// Checked after every instruction by the run-control methods.
+    pub watchpoints: Vec<Watchpoint>,
+    pub lenient_decoding: bool,
+    pub memory_map: Option<MemoryMap>,
+    pub journal: Option<Journal>,
+    /// Predecoded instructions. Set by [`with_fast_mode`](Self::with_fast_mode).
+    pub icache: Option<ICache>,
+    /// Stops runs that repeat a machine state. Set by
+    /// [`with_loop_detection`](Self::with_loop_detection).
+    pub loop_detector: Option<LoopDetector>,
+    pub observers: Vec<Box<dyn Observer>>,
+    /// The most recently executed instruction.
+    pub current_instruction: Option<TraceEntry>,
+    /// Up to [`LOG_CAPACITY`](Self::LOG_CAPACITY) instructions before
+    /// [`current_instruction`](Self::current_instruction), oldest first.
+    pub instruction_log: VecDeque<TraceEntry>,
     pub output: String,
+    pub io: Box<dyn IoBackend>,
 
     pub print: bool,
     pub print_state: bool,
     pub print_instructions: bool,
 }
 
+impl Default for NanoCore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NanoCore {
-    pub const MAX_CYCLES: u16 = 1024;
+    pub const DEFAULT_MAX_CYCLES: u64 = 1024;
+    pub const LOG_CAPACITY: usize = 100;
 
     /// Creates a new NanoCore emulator instance.
     ///
@@ -55,11 +103,23 @@ impl NanoCore {
         NanoCore {
             cpu: CPU::new(),
             cycle: 0,
-            current_instruction: String::new(),
-            current_instruction_bin: String::new(),
-            current_skipped: false,
-            instruction_log: VecDeque::with_capacity(100),
+            max_cycles: Self::DEFAULT_MAX_CYCLES,
+            t_states: 0,
+            timing: Timing::default(),
+            clock_hz: None,
+            clock_origin: None,
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+            lenient_decoding: false,
+            memory_map: None,
+            journal: None,
+            icache: None,
+            loop_detector: None,
+            observers: Vec::new(),
+            current_instruction: None,
+            instruction_log: VecDeque::with_capacity(Self::LOG_CAPACITY),
             output: String::new(),
+            io: Box::new(StdIo),
 
             print: false,
             print_state: false,
@@ -67,6 +127,249 @@ impl NanoCore {
         }
     }
 
+    /// Replaces the I/O backend used by `IN` and `PRINT`. `PRINT` only
+    /// writes to it once [`print`](Self::print) is enabled, e.g. with
+    /// [`with_print`](Self::with_print).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanocore::{io::BufferIo, nanocore::NanoCore};
+    ///
+    /// let mut nano = NanoCore::new().with_io(BufferIo::new("A")).with_print();
+    /// let program = vec![0x24, 0x00, 0x19, 0x00, 0x00]; // IN R0; PRINT R0; HLT
+    /// nano.load_program(&program, 0x00).unwrap();
+    /// nano.run().unwrap();
+    ///
+    /// let io = nano.io_backend::<BufferIo>().unwrap();
+    /// assert_eq!(io.output_string(), "A");
+    /// ```
+    #[must_use]
+    pub fn with_io(mut self, io: impl IoBackend) -> Self {
+        self.io = Box::new(io);
+        self
+    }
+
+    /// Enables [`print`](Self::print), sending `PRINT` output to the I/O
+    /// backend as well as to [`output`](Self::output).
+    #[must_use]
+    pub fn with_print(mut self) -> Self {
+        self.print = true;
+        self
+    }
+
+    /// Sets the cycle budget after which [`run`](Self::run) and the other
+    /// run-control methods stop with [`StopReason::CycleLimit`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanocore::nanocore::{NanoCore, StopReason};
+    ///
+    /// let mut nano = NanoCore::new().with_max_cycles(10);
+    /// nano.load_program(&[0x16, 0x00], 0x00).unwrap(); // JMP 0x00
+    /// assert_eq!(nano.run_until(|_| false), StopReason::CycleLimit);
+    /// assert_eq!(nano.cycle, 10);
+    /// ```
+    #[must_use]
+    pub fn with_max_cycles(mut self, max_cycles: u64) -> Self {
+        self.max_cycles = max_cycles;
+        self
+    }
+
+    /// Enables compatibility mode, in which unknown opcodes execute as `NOP`
+    /// instead of failing with
+    /// [`EmulatorError::IllegalOpcode`](crate::EmulatorError::IllegalOpcode).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanocore::nanocore::NanoCore;
+    ///
+    /// let mut nano = NanoCore::new().with_lenient_decoding();
+    /// nano.load_program(&[0xFF, 0x00], 0x00).unwrap(); // <illegal>; HLT
+    /// nano.run().unwrap();
+    /// assert!(nano.cpu.is_halted);
+    /// ```
+    #[must_use]
+    pub fn with_lenient_decoding(mut self) -> Self {
+        self.lenient_decoding = true;
+        self
+    }
+
+    /// Throttles execution so that [`t_states`](Self::t_states) advance at
+    /// `hz` per second of wall-clock time.
+    #[must_use]
+    pub fn with_clock_hz(mut self, hz: u64) -> Self {
+        self.clock_hz = Some(hz);
+        self
+    }
+
+    /// Enables the undo journal, keeping up to `depth` cycles of history for
+    /// [`step_back`](Self::step_back) and [`rewind_to`](Self::rewind_to).
+    #[must_use]
+    pub fn with_journal(mut self, depth: usize) -> Self {
+        self.journal = Some(Journal::new(depth));
+        self
+    }
+
+    /// Enables fast mode for batch runs.
+    ///
+    /// Instructions are decoded once into an [`ICache`] and reused until one
+    /// of their bytes is written. No [`TraceEntry`] is recorded unless
+    /// watchpoints need it, so [`current_instruction`](Self::current_instruction)
+    /// stays `None` and [`print_instructions`](Self::print_instructions) has
+    /// no effect.
+    ///
+    /// The cache only sees writes made by executing instructions. Call
+    /// [`flush_icache`](Self::flush_icache) after writing code through
+    /// [`cpu.bus`](crate::cpu::CPU::bus) directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanocore::nanocore::NanoCore;
+    ///
+    /// let mut nano = NanoCore::new().with_fast_mode();
+    /// // loop: INC R0; JMP loop
+    /// nano.load_program(&[0x0D, 0x00, 0x16, 0x00], 0x00).unwrap();
+    /// nano.run_for(10);
+    /// assert_eq!(nano.cpu.registers[0], 5);
+    ///
+    /// let icache = nano.icache.as_ref().unwrap();
+    /// assert_eq!((icache.hits, icache.misses), (8, 2));
+    /// assert!(nano.current_instruction.is_none());
+    /// ```
+    #[must_use]
+    pub fn with_fast_mode(mut self) -> Self {
+        self.icache = Some(ICache::new());
+        self
+    }
+
+    /// Enables the [`LoopDetector`], so that the run-control methods stop
+    /// with [`StopReason::Loop`] as soon as the machine state repeats.
+    #[must_use]
+    pub fn with_loop_detection(mut self) -> Self {
+        self.loop_detector = Some(LoopDetector::new());
+        self
+    }
+
+    /// Empties the [`icache`](Self::icache), if fast mode is enabled.
+    pub fn flush_icache(&mut self) {
+        if let Some(icache) = &mut self.icache {
+            icache.clear();
+        }
+    }
+
+    /// Makes the [`loop_detector`](Self::loop_detector) forget the states
+    /// seen so far. Call it after changing registers or memory directly.
+    pub fn clear_loop_history(&mut self) {
+        if let Some(detector) = &mut self.loop_detector {
+            detector.clear();
+        }
+    }
+
+    /// Registers an [`Observer`], which is notified after those already
+    /// registered.
+    pub fn add_observer(&mut self, observer: impl Observer) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Returns the first registered observer of type `T`.
+    pub fn observer<T: Observer>(&self) -> Option<&T> {
+        self.observers
+            .iter()
+            .find_map(|o| (o.as_ref() as &dyn Any).downcast_ref::<T>())
+    }
+
+    /// Returns the first registered observer of type `T` mutably.
+    pub fn observer_mut<T: Observer>(&mut self) -> Option<&mut T> {
+        self.observers
+            .iter_mut()
+            .find_map(|o| (o.as_mut() as &mut dyn Any).downcast_mut::<T>())
+    }
+
+    /// Returns the I/O backend as its concrete type, if it is a `T`.
+    pub fn io_backend<T: IoBackend>(&self) -> Option<&T> {
+        (self.io.as_ref() as &dyn Any).downcast_ref::<T>()
+    }
+
+    /// Returns the I/O backend mutably as its concrete type, if it is a `T`.
+    pub fn io_backend_mut<T: IoBackend>(&mut self) -> Option<&mut T> {
+        (self.io.as_mut() as &mut dyn Any).downcast_mut::<T>()
+    }
+
+    /// Captures the current emulator state.
+    pub fn snapshot(&self) -> Snapshot {
+        let cpu = &self.cpu;
+
+        Snapshot {
+            registers: cpu.registers,
+            pc: cpu.pc,
+            sp: cpu.sp,
+            flags: cpu.flags,
+            is_halted: cpu.is_halted,
+            irq_pending: cpu.irq_pending,
+            irq_vector: cpu.irq_vector,
+            ram: *cpu.bus.ram(),
+            cycle: self.cycle,
+            t_states: self.t_states,
+            current_instruction: self.current_instruction,
+            output: self.output.clone(),
+            instruction_log: self.instruction_log.iter().copied().collect(),
+            devices: cpu
+                .bus
+                .mappings()
+                .iter()
+                .map(|m| DeviceState {
+                    start: m.start,
+                    name: m.device.name().to_owned(),
+                    state: m.device.save_state(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Restores state captured by [`snapshot`](Self::snapshot).
+    ///
+    /// Device state is only applied to a device mapped at the same address
+    /// with the same name; the bus layout itself is not changed.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        let cpu = &mut self.cpu;
+        cpu.registers = snapshot.registers;
+        cpu.pc = snapshot.pc;
+        cpu.sp = snapshot.sp;
+        cpu.flags = snapshot.flags;
+        cpu.is_halted = snapshot.is_halted;
+        cpu.irq_pending = snapshot.irq_pending;
+        cpu.irq_vector = snapshot.irq_vector;
+        *cpu.bus.ram_mut() = snapshot.ram;
+
+        for saved in &snapshot.devices {
+            if let Some(m) = cpu
+                .bus
+                .mappings_mut()
+                .iter_mut()
+                .find(|m| m.start == saved.start && m.device.name() == saved.name)
+            {
+                m.device.load_state(&saved.state);
+            }
+        }
+
+        self.cycle = snapshot.cycle;
+        self.t_states = snapshot.t_states;
+        self.clock_origin = None;
+        self.current_instruction = snapshot.current_instruction;
+        self.output = snapshot.output.clone();
+        self.instruction_log = snapshot.instruction_log.iter().copied().collect();
+
+        if let Some(journal) = &mut self.journal {
+            journal.clear();
+        }
+        self.flush_icache();
+        self.clear_loop_history();
+    }
+
     /// Loads a program into emulator memory at the specified address.
     ///
     /// # Arguments
@@ -99,8 +402,12 @@ impl NanoCore {
         }
 
         for (i, &byte) in program.iter().enumerate() {
-            self.cpu.memory[start_address.wrapping_add(i as u8) as usize] = byte;
+            self.cpu
+                .bus
+                .write(start_address.wrapping_add(i as u8), byte);
         }
+        self.flush_icache();
+        self.clear_loop_history();
 
         self.cpu.pc = start_address;
         Ok(())
@@ -109,8 +416,9 @@ impl NanoCore {
     /// Runs the emulator until the CPU halts or max cycles reached.
     ///
     /// Executes instructions in a loop, checking for halt condition or
-    /// [`MAX_CYCLES`](Self::MAX_CYCLES) limit. Optionally prints state and instructions
-    /// based on configuration flags.
+    /// [`max_cycles`](Self::max_cycles) limit. Optionally prints state and instructions
+    /// based on configuration flags. Breakpoints are ignored; use
+    /// [`run_until`](Self::run_until) to honor them.
     ///
     /// # Errors
     ///
@@ -137,15 +445,25 @@ impl NanoCore {
 
         while !self.cpu.is_halted {
             if self.print_state {
-                self.cpu.print_state(self.cycle);
+                self.cpu.print_state(self.cycle, self.t_states);
             }
 
-            if self.cycle >= Self::MAX_CYCLES {
+            if self.cycle >= self.max_cycles {
                 println!("\n== FORCE HALT - max cycles ==\n");
                 break;
             }
 
             self.cycle()?;
+
+            if let Some(hit) = self.check_watchpoints() {
+                println!("\n== WATCHPOINT - {hit} ==\n");
+                break;
+            }
+
+            if let Some(hit) = self.check_loop() {
+                println!("\n== INFINITE LOOP - {hit} ==\n");
+                break;
+            }
         }
 
         self.print_colored(&format!(
@@ -157,6 +475,118 @@ impl NanoCore {
         Ok(())
     }
 
+    /// Returns the first watchpoint triggered by the most recent instruction.
+    pub fn check_watchpoints(&self) -> Option<WatchHit> {
+        let entry = self.current_instruction.as_ref()?;
+        self.watchpoints.iter().find_map(|w| w.check(entry))
+    }
+
+    /// Returns the loop closed by the most recent instruction, if the
+    /// [`loop_detector`](Self::loop_detector) found one.
+    pub fn check_loop(&self) -> Option<LoopHit> {
+        self.loop_detector.as_ref()?.hit
+    }
+
+    /// Executes a single instruction.
+    ///
+    /// Breakpoints at the current PC are ignored, so stepping always makes
+    /// progress. Returns [`StopReason::Stepped`] unless the CPU halts, stops at
+    /// a breakpoint on the next instruction, triggers a watchpoint, or fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanocore::nanocore::{NanoCore, StopReason};
+    ///
+    /// let mut nano = NanoCore::new();
+    /// nano.load_program(&[0x0D, 0x00, 0x00], 0x00).unwrap(); // INC R0; HLT
+    /// assert_eq!(nano.step(), StopReason::Stepped);
+    /// assert_eq!(nano.step(), StopReason::Halted);
+    /// assert_eq!(nano.step(), StopReason::Halted);
+    /// ```
+    pub fn step(&mut self) -> StopReason {
+        self.run_for(1)
+    }
+
+    /// Executes up to `n` instructions.
+    ///
+    /// Stops early when the CPU halts, the cycle budget is exhausted, the PC
+    /// reaches a breakpoint, a watchpoint triggers, a loop is detected, or an
+    /// instruction fails. A breakpoint at the starting PC does not stop
+    /// execution, so calling this again resumes.
+    pub fn run_for(&mut self, n: u64) -> StopReason {
+        if n == 0 {
+            return StopReason::Stepped;
+        }
+
+        let mut executed = 0;
+        self.run_while(|_| {
+            executed += 1;
+            executed >= n
+        })
+        .unwrap_or(StopReason::Stepped)
+    }
+
+    /// Runs until `predicate` returns `true` after an instruction.
+    ///
+    /// Also stops when the CPU halts, the cycle budget is exhausted, the PC
+    /// reaches a breakpoint, a watchpoint triggers, a loop is detected, or an
+    /// instruction fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanocore::nanocore::{NanoCore, StopReason};
+    ///
+    /// let mut nano = NanoCore::new();
+    /// nano.load_program(&[0x0D, 0x00, 0x16, 0x00], 0x00).unwrap(); // INC R0; JMP 0x00
+    /// let reason = nano.run_until(|n| n.cpu.registers[0] == 5);
+    /// assert_eq!(reason, StopReason::Predicate);
+    /// assert_eq!(nano.cycle, 9);
+    /// ```
+    pub fn run_until(&mut self, predicate: impl FnMut(&NanoCore) -> bool) -> StopReason {
+        self.run_while(predicate).unwrap_or(StopReason::Predicate)
+    }
+
+    /// Executes instructions until one of the stop conditions holds or `done`
+    /// returns `true`, in which case `None` is returned and the caller decides
+    /// the reason.
+    fn run_while(&mut self, mut done: impl FnMut(&NanoCore) -> bool) -> Option<StopReason> {
+        loop {
+            if self.cpu.is_halted {
+                return Some(StopReason::Halted);
+            }
+
+            if self.cycle >= self.max_cycles {
+                return Some(StopReason::CycleLimit);
+            }
+
+            if let Err(e) = self.cycle() {
+                return Some(StopReason::Error(e));
+            }
+
+            if self.cpu.is_halted {
+                return Some(StopReason::Halted);
+            }
+
+            if let Some(hit) = self.check_watchpoints() {
+                return Some(StopReason::Watchpoint(hit));
+            }
+
+            if let Some(hit) = self.check_loop() {
+                return Some(StopReason::Loop(hit));
+            }
+
+            if self.breakpoints.contains(&self.cpu.pc) {
+                return Some(StopReason::Breakpoint { pc: self.cpu.pc });
+            }
+
+            if done(self) {
+                return None;
+            }
+        }
+    }
+
     pub fn print_colored(&self, s: &str) {
         if !self.print_state {
             return;
@@ -194,10 +624,72 @@ impl NanoCore {
     /// assert_eq!(nano.cycle, 1);
     /// ```
     pub fn cycle(&mut self) -> crate::EmulatorResult<()> {
-        let (op, operands) = self.fetch_decode();
+        if self.journal.is_none() {
+            let result = self.cycle_observed();
+            self.throttle();
+            return result;
+        }
+
+        let entry = self.journal_entry();
+        if let Some(journal) = &mut self.journal {
+            journal.begin(entry);
+        }
+
+        let result = self.cycle_observed();
+
+        if let Some(journal) = &mut self.journal {
+            journal.commit();
+        }
+        self.throttle();
+        result
+    }
+
+    /// Runs [`cycle_inner`](Self::cycle_inner), reporting errors to the
+    /// observers.
+    fn cycle_observed(&mut self) -> crate::EmulatorResult<()> {
+        let result = self.cycle_inner();
+        if let Err(e) = &result {
+            for o in &mut self.observers {
+                o.on_error(&self.cpu, e);
+            }
+        }
+        result
+    }
+
+    fn cycle_inner(&mut self) -> crate::EmulatorResult<()> {
+        if self.cpu.irq_pending && self.cpu.get_flag(CPU::FLAG_I) && !self.cpu.is_halted {
+            self.enter_interrupt()?;
+            self.cycle += 1;
+            self.t_states += self.timing.interrupt_cost;
+            return Ok(());
+        }
+
+        let pc = self.cpu.pc;
+        let (op, operands) = self.fetch_decode()?;
+
+        for o in &mut self.observers {
+            o.before_execute(&self.cpu, pc, op, operands);
+        }
 
         let pc_override = self.execute(op, operands)?;
 
+        let mut cost = self.timing.cost(op);
+        if pc_override && op.is_conditional_branch() {
+            cost += self.timing.branch_taken_penalty;
+        }
+        self.t_states += cost;
+
+        for o in &mut self.observers {
+            o.after_execute(&self.cpu, pc, op, cost);
+            if self.cpu.is_halted {
+                o.on_halt(&self.cpu);
+            }
+        }
+
+        if self.cpu.bus.tick() {
+            self.cpu.irq_pending = true;
+        }
+
         if self.cpu.is_halted {
             return Ok(());
         }
@@ -206,110 +698,515 @@ impl NanoCore {
             self.cpu.pc = self.cpu.pc.wrapping_add(op.instruction_len());
         }
 
+        if let Some(detector) = &mut self.loop_detector {
+            detector.after_execute(&self.cpu, pc, op);
+        }
+
         self.cycle += 1;
         Ok(())
     }
 
-    /// Executes binary arithmetic operation with division by zero check
-    fn execute_arithmetic(&self, op: Op, v1: u8, v2: u8) -> crate::EmulatorResult<(u8, bool)> {
+    /// Sleeps until wall-clock time catches up with [`t_states`](Self::t_states)
+    /// at [`clock_hz`](Self::clock_hz).
+    fn throttle(&mut self) {
+        let Some(hz) = self.clock_hz.filter(|&hz| hz > 0) else {
+            return;
+        };
+
+        let (origin, base) = *self
+            .clock_origin
+            .get_or_insert_with(|| (Instant::now(), self.t_states));
+        let elapsed = self.t_states.saturating_sub(base);
+        let target = origin + Duration::from_secs_f64(elapsed as f64 / hz as f64);
+
+        // Sleeping for tiny intervals is inaccurate; let the lead build up.
+        let ahead = target.saturating_duration_since(Instant::now());
+        if ahead >= Duration::from_millis(1) {
+            thread::sleep(ahead);
+        }
+    }
+
+    /// Requests an interrupt.
+    ///
+    /// The request stays pending until interrupts are enabled (`EI`), at which
+    /// point the CPU pushes PC and flags, disables interrupts and jumps to the
+    /// handler whose address is stored at [`CPU::irq_vector`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanocore::nanocore::NanoCore;
+    ///
+    /// let mut nano = NanoCore::new();
+    /// // EI; NOP...; handler at 0x10: LDI R0 1; RETI
+    /// nano.load_program(&[0x28, 0x01, 0x01, 0x01], 0x00).unwrap();
+    /// nano.load_program(&[0x02, 0x00, 0x01, 0x2A], 0x10).unwrap();
+    /// nano.cpu.bus.write(nano.cpu.irq_vector, 0x10);
+    /// nano.cpu.pc = 0x00;
+    ///
+    /// nano.cycle().unwrap(); // EI
+    /// nano.raise_irq();
+    /// nano.cycle().unwrap(); // interrupt entry
+    /// assert_eq!(nano.cpu.pc, 0x10);
+    /// nano.cycle().unwrap(); // LDI R0 1
+    /// nano.cycle().unwrap(); // RETI
+    /// assert_eq!(nano.cpu.registers[0], 1);
+    /// assert_eq!(nano.cpu.pc, 0x01);
+    /// ```
+    pub fn raise_irq(&mut self) {
+        self.cpu.irq_pending = true;
+    }
+
+    /// Withdraws a pending interrupt request.
+    pub fn clear_irq(&mut self) {
+        self.cpu.irq_pending = false;
+    }
+
+    /// Undoes the most recent cycle recorded in the [`journal`](Self::journal).
+    ///
+    /// Returns `false` if journaling is disabled or the history is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanocore::nanocore::NanoCore;
+    ///
+    /// let mut nano = NanoCore::new().with_journal(16);
+    /// nano.load_program(&[0x0D, 0x00, 0x0D, 0x00, 0x00], 0x00).unwrap(); // INC R0; INC R0; HLT
+    /// nano.run().unwrap();
+    /// assert_eq!(nano.cpu.registers[0], 2);
+    ///
+    /// assert!(nano.step_back()); // HLT
+    /// assert!(nano.step_back()); // INC R0
+    /// assert_eq!(nano.cpu.registers[0], 1);
+    /// assert_eq!(nano.cpu.pc, 0x02);
+    /// assert!(!nano.cpu.is_halted);
+    /// ```
+    pub fn step_back(&mut self) -> bool {
+        let Some(entry) = self.journal.as_mut().and_then(Journal::pop) else {
+            return false;
+        };
+
+        let cpu = &mut self.cpu;
+        cpu.registers = entry.registers;
+        cpu.pc = entry.pc;
+        cpu.sp = entry.sp;
+        cpu.flags = entry.flags;
+        cpu.is_halted = entry.is_halted;
+        cpu.irq_pending = entry.irq_pending;
+
+        for &(addr, old) in entry.memory.iter().rev() {
+            cpu.bus.ram_mut()[addr as usize] = old;
+            if let Some(icache) = &mut self.icache {
+                icache.invalidate(addr);
+            }
+        }
+        for (m, state) in cpu.bus.mappings_mut().iter_mut().zip(&entry.devices) {
+            m.device.load_state(state);
+        }
+
+        self.cycle = entry.cycle;
+        self.t_states = entry.t_states;
+        self.clock_origin = None;
+        self.clear_loop_history();
+        self.output.truncate(entry.output_len);
+        self.current_instruction = entry.current_instruction;
+
+        if let Some(evicted) = entry.log_evicted {
+            self.instruction_log.pop_back();
+            self.instruction_log.push_front(evicted);
+        } else {
+            self.instruction_log.truncate(entry.log_len);
+        }
+
+        true
+    }
+
+    /// Undoes cycles until [`cycle`](Self::cycle) equals `target`.
+    ///
+    /// Returns `false`, leaving the state untouched, if journaling is disabled
+    /// or `target` is older than the retained history or in the future.
+    pub fn rewind_to(&mut self, target: u64) -> bool {
+        let Some(oldest) = self.journal.as_ref().and_then(Journal::oldest_cycle) else {
+            return target == self.cycle && self.journal.is_some();
+        };
+
+        if target < oldest || target > self.cycle {
+            return false;
+        }
+
+        while self.cycle > target && self.step_back() {}
+        true
+    }
+
+    /// Captures the pre-cycle state for the journal.
+    fn journal_entry(&self) -> JournalEntry {
+        JournalEntry {
+            cycle: self.cycle,
+            t_states: self.t_states,
+            registers: self.cpu.registers,
+            pc: self.cpu.pc,
+            sp: self.cpu.sp,
+            flags: self.cpu.flags,
+            is_halted: self.cpu.is_halted,
+            irq_pending: self.cpu.irq_pending,
+            memory: Vec::new(),
+            devices: self
+                .cpu
+                .bus
+                .mappings()
+                .iter()
+                .map(|m| m.device.save_state())
+                .collect(),
+            output_len: self.output.len(),
+            current_instruction: self.current_instruction,
+            log_len: self.instruction_log.len(),
+            log_evicted: None,
+        }
+    }
+
+    /// Services a pending interrupt: pushes PC and flags, disables interrupts
+    /// and jumps to the handler.
+    fn enter_interrupt(&mut self) -> crate::EmulatorResult<()> {
+        self.log_instruction();
+        if self.tracing() {
+            self.current_instruction = Some(TraceEntry::new(
+                self.cycle,
+                &self.cpu,
+                0,
+                None,
+                Operands::None,
+            ));
+        }
+
+        let return_addr = self.cpu.pc;
+        self.push(return_addr)?;
+        self.push(self.cpu.flags)?;
+
+        self.cpu.irq_pending = false;
+        self.cpu.clear_flag(CPU::FLAG_I);
+
+        let handler = self.read_mem(self.cpu.irq_vector)?;
+        self.cpu.pc = handler;
+
+        for o in &mut self.observers {
+            o.on_interrupt(&self.cpu, return_addr);
+        }
+
+        self.finish_trace(true, handler);
+
+        Ok(())
+    }
+
+    /// Whether instructions are recorded as [`TraceEntry`] values. Fast mode
+    /// only records them for the watchpoints.
+    fn tracing(&self) -> bool {
+        self.icache.is_none() || !self.watchpoints.is_empty()
+    }
+
+    /// Moves the current instruction into the instruction log.
+    fn log_instruction(&mut self) {
+        let Some(entry) = self.current_instruction.take() else {
+            return;
+        };
+
+        if self.instruction_log.len() >= Self::LOG_CAPACITY
+            && let Some(evicted) = self.instruction_log.pop_front()
+            && let Some(journal) = &mut self.journal
+        {
+            journal.record_eviction(evicted);
+        }
+        self.instruction_log.push_back(entry);
+    }
+
+    /// Completes the current trace entry with the state after execution.
+    fn finish_trace(&mut self, branch_taken: bool, next_pc: u8) {
+        if let Some(entry) = &mut self.current_instruction {
+            entry.registers_after = self.cpu.registers;
+            entry.flags_after = self.cpu.flags;
+            entry.branch_taken = branch_taken;
+            entry.next_pc = next_pc;
+
+            if self.print_instructions {
+                println!("->{entry}");
+            }
+        }
+    }
+
+    /// Executes binary arithmetic operation with division by zero check.
+    ///
+    /// Returns the result, the carry (borrow for subtraction) and the signed
+    /// overflow. Among the divisions only `-128 / -1` overflows, for both
+    /// `IDIV` and `IMOD` (whose result is then `0`), and multiplication never
+    /// sets V.
+    fn execute_arithmetic(
+        &self,
+        op: Op,
+        v1: u8,
+        v2: u8,
+    ) -> crate::EmulatorResult<(u8, bool, bool)> {
+        let carry_in = match op {
+            Op::ADC | Op::ADCI | Op::SBC | Op::SBCI => self.cpu.get_flag(CPU::FLAG_C) as u8,
+            _ => 0,
+        };
+
+        if matches!(
+            op,
+            Op::DIV | Op::DIVI | Op::MOD | Op::MODI | Op::IDIV | Op::IMOD
+        ) && v2 == 0
+        {
+            return Err(crate::EmulatorError::DivisionByZero {
+                op: format!("{:?}", op),
+            });
+        }
+
         let result = match op {
-            Op::ADD | Op::ADDI => v1.overflowing_add(v2),
-            Op::SUB | Op::SUBI => v1.overflowing_sub(v2),
-            Op::MUL | Op::MULI => v1.overflowing_mul(v2),
-            Op::DIV | Op::DIVI => {
-                if v2 == 0 {
-                    return Err(crate::EmulatorError::DivisionByZero {
-                        op: format!("{:?}", op),
-                    });
-                }
-                v1.overflowing_div(v2)
+            Op::ADD | Op::ADDI | Op::ADC | Op::ADCI => {
+                let (partial, c1) = v1.overflowing_add(v2);
+                let (result, c2) = partial.overflowing_add(carry_in);
+                let overflow = (v1 ^ result) & (v2 ^ result) & 0x80 != 0;
+                (result, c1 || c2, overflow)
             }
-            Op::MOD | Op::MODI => {
-                if v2 == 0 {
-                    return Err(crate::EmulatorError::DivisionByZero {
-                        op: format!("{:?}", op),
-                    });
-                }
-                v1.overflowing_rem(v2)
+            Op::SUB | Op::SUBI | Op::SBC | Op::SBCI => {
+                let (partial, b1) = v1.overflowing_sub(v2);
+                let (result, b2) = partial.overflowing_sub(carry_in);
+                let overflow = (v1 ^ v2) & (v1 ^ result) & 0x80 != 0;
+                (result, b1 || b2, overflow)
+            }
+            Op::MUL | Op::MULI => {
+                let (result, carry) = v1.overflowing_mul(v2);
+                (result, carry, false)
+            }
+            Op::DIV | Op::DIVI => (v1 / v2, false, false),
+            Op::MOD | Op::MODI => (v1 % v2, false, false),
+            Op::IDIV => {
+                let (result, overflow) = (v1 as i8).overflowing_div(v2 as i8);
+                (result as u8, false, overflow)
+            }
+            Op::IMOD => {
+                let (result, overflow) = (v1 as i8).overflowing_rem(v2 as i8);
+                (result as u8, false, overflow)
             }
             _ => unreachable!(),
         };
         Ok(result)
     }
 
-    pub fn fetch_decode(&mut self) -> (Op, Operands) {
-        // FETCH
-        let opcode = self.cpu.memory[self.cpu.pc as usize];
-        let byte_2 = self.cpu.memory[self.cpu.pc.wrapping_add(1) as usize];
-        let byte_3 = self.cpu.memory[self.cpu.pc.wrapping_add(2) as usize];
+    /// Checks `addr` against the [`memory_map`](Self::memory_map), if any.
+    fn check_access(&self, addr: u8, kind: AccessKind) -> crate::EmulatorResult<()> {
+        match &self.memory_map {
+            Some(map) if !map.allows(addr, kind) => Err(crate::EmulatorError::AccessViolation {
+                addr,
+                pc: self.cpu.pc,
+                kind,
+            }),
+            _ => Ok(()),
+        }
+    }
 
-        self.current_instruction_bin = format!("{opcode:08b} │ {opcode:#04X} │ {opcode:03}");
+    /// Records the current value of `addr` before a write, if journaling.
+    fn journal_write(&mut self, addr: u8) {
+        if let Some(journal) = &mut self.journal
+            && self.cpu.bus.is_ram(addr)
+        {
+            journal.record_write(addr, self.cpu.bus.ram()[addr as usize]);
+        }
+    }
 
-        // DECODE
-        let op: Op = opcode.into();
+    /// Records a data read in the trace and notifies the observers.
+    fn trace_read(&mut self, addr: u8, value: u8) {
+        if let Some(entry) = &mut self.current_instruction {
+            entry.reads.push(MemRead { addr, value });
+        }
+        for o in &mut self.observers {
+            o.on_read(addr, value);
+        }
+    }
 
-        let operands = match op {
-            Op::HLT | Op::NOP | Op::RET => Operands::None,
-            Op::LDI | Op::ADDI | Op::SUBI | Op::MULI | Op::DIVI | Op::MODI => {
-                Operands::RegImm(byte_2, byte_3)
+    /// Records a write in the trace before the byte at `addr` is
+    /// overwritten, returning the old value.
+    fn trace_write(&mut self, addr: u8, new: u8) -> u8 {
+        let old = self.cpu.bus.peek(addr);
+        if let Some(entry) = &mut self.current_instruction {
+            entry.writes.push(MemWrite { addr, old, new });
+        }
+        old
+    }
+
+    /// Reads a data byte through the bus.
+    fn read_mem(&mut self, addr: u8) -> crate::EmulatorResult<u8> {
+        self.check_access(addr, AccessKind::Read)?;
+        let value = self.cpu.bus.read(addr);
+        self.trace_read(addr, value);
+        Ok(value)
+    }
+
+    /// Writes a data byte through the bus.
+    fn write_mem(&mut self, addr: u8, value: u8) -> crate::EmulatorResult<()> {
+        self.check_access(addr, AccessKind::Write)?;
+        self.journal_write(addr);
+        let old = self.trace_write(addr, value);
+        for o in &mut self.observers {
+            o.on_write(addr, old, value);
+        }
+        self.cpu.bus.write(addr, value);
+        self.invalidate_icache(addr);
+        Ok(())
+    }
+
+    /// Evicts cached instructions overlapping a written byte.
+    fn invalidate_icache(&mut self, addr: u8) {
+        if let Some(icache) = &mut self.icache {
+            icache.invalidate(addr);
+        }
+    }
+
+    /// Reads an instruction byte through the bus.
+    fn fetch_byte(&mut self, addr: u8) -> crate::EmulatorResult<u8> {
+        self.check_access(addr, AccessKind::Execute)?;
+        Ok(self.cpu.bus.read(addr))
+    }
+
+    /// Pushes a byte onto the stack.
+    fn push(&mut self, value: u8) -> crate::EmulatorResult<()> {
+        if self.cpu.sp == CPU::STACK_MIN {
+            return Err(crate::EmulatorError::StackOverflow { sp: self.cpu.sp });
+        }
+
+        self.check_access(self.cpu.sp, AccessKind::Stack)?;
+        self.journal_write(self.cpu.sp);
+        self.trace_write(self.cpu.sp, value);
+        self.cpu.bus.write(self.cpu.sp, value);
+        self.invalidate_icache(self.cpu.sp);
+        for o in &mut self.observers {
+            o.on_push(self.cpu.sp, value);
+        }
+        self.cpu.sp = self.cpu.sp.wrapping_sub(1);
+        Ok(())
+    }
+
+    /// Pops a byte from the stack.
+    fn pop(&mut self) -> crate::EmulatorResult<u8> {
+        if self.cpu.sp == CPU::STACK_MAX {
+            return Err(crate::EmulatorError::StackUnderflow { sp: self.cpu.sp });
+        }
+
+        self.check_access(self.cpu.sp.wrapping_add(1), AccessKind::Stack)?;
+        self.cpu.sp = self.cpu.sp.wrapping_add(1);
+        let value = self.cpu.bus.read(self.cpu.sp);
+        if let Some(entry) = &mut self.current_instruction {
+            entry.reads.push(MemRead {
+                addr: self.cpu.sp,
+                value,
+            });
+        }
+        for o in &mut self.observers {
+            o.on_pop(self.cpu.sp, value);
+        }
+        Ok(value)
+    }
+
+    /// Fetches and decodes the instruction at the PC.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EmulatorError::IllegalOpcode`](crate::EmulatorError::IllegalOpcode)
+    /// for an unknown opcode, unless [`lenient_decoding`](Self::lenient_decoding)
+    /// is set, or [`EmulatorError::AccessViolation`](crate::EmulatorError::AccessViolation)
+    /// if the [`memory_map`](Self::memory_map) does not allow execution there.
+    pub fn fetch_decode(&mut self) -> crate::EmulatorResult<(Op, Operands)> {
+        // The memory map is checked on every fetch, so it bypasses the cache.
+        let pc = self.cpu.pc;
+        let cached = match &mut self.icache {
+            Some(icache) if self.memory_map.is_none() => icache.get(pc),
+            _ => None,
+        };
+        if let Some(decoded) = cached {
+            for o in &mut self.observers {
+                o.on_fetch(pc, decoded.opcode);
+            }
+            return Ok((decoded.op, decoded.operands));
+        }
+
+        let (opcode, op, operands) = self.fetch_decode_uncached()?;
+
+        let in_ram = (0..op.instruction_len()).all(|i| self.cpu.bus.is_ram(pc.wrapping_add(i)));
+        if let Some(icache) = &mut self.icache
+            && self.memory_map.is_none()
+            && in_ram
+        {
+            icache.insert(
+                pc,
+                Decoded {
+                    opcode,
+                    op,
+                    operands,
+                },
+            );
+        }
+
+        Ok((op, operands))
+    }
+
+    /// Reads and decodes the instruction at the PC, returning the opcode byte
+    /// too.
+    fn fetch_decode_uncached(&mut self) -> crate::EmulatorResult<(u8, Op, Operands)> {
+        // FETCH
+        let opcode = self.fetch_byte(self.cpu.pc)?;
+        for o in &mut self.observers {
+            o.on_fetch(self.cpu.pc, opcode);
+        }
+
+        // DECODE
+        let op = match Op::try_from(opcode) {
+            Ok(op) => op,
+            Err(_) if self.lenient_decoding => Op::NOP,
+            Err(_) => {
+                return Err(crate::EmulatorError::IllegalOpcode {
+                    pc: self.cpu.pc,
+                    byte: opcode,
+                });
             }
-            Op::LDA | Op::STORE => Operands::RegAddr(byte_2, byte_3),
-            Op::PUSH
-            | Op::POP
-            | Op::INC
-            | Op::DEC
-            | Op::NOT
-            | Op::SHL
-            | Op::SHR
-            | Op::ROL
-            | Op::ROR
-            | Op::IN
-            | Op::JMPR
-            | Op::CALLR
-            | Op::PRINT => Operands::Reg(byte_2),
-            Op::LDR
-            | Op::MOV
-            | Op::ADD
-            | Op::SUB
-            | Op::AND
-            | Op::OR
-            | Op::XOR
-            | Op::CMP
-            | Op::MUL
-            | Op::DIV
-            | Op::MOD
-            | Op::STR => Operands::RegReg((byte_2 >> 4) & 0x0F, byte_2 & 0x0F),
-            Op::JMP | Op::JZ | Op::JNZ | Op::CALL => Operands::Addr(byte_2),
         };
 
-        (op, operands)
+        // Only fetch the operand bytes the instruction actually has, so that
+        // side-effecting devices are not read by accident.
+        let byte_2 = if op.instruction_len() > 1 {
+            self.fetch_byte(self.cpu.pc.wrapping_add(1))?
+        } else {
+            0
+        };
+        let byte_3 = if op.instruction_len() > 2 {
+            self.fetch_byte(self.cpu.pc.wrapping_add(2))?
+        } else {
+            0
+        };
+
+        let operands = Operands::decode(op, byte_2, byte_3);
+
+        Ok((opcode, op, operands))
     }
 
     pub fn execute(&mut self, op: Op, operands: Operands) -> crate::EmulatorResult<bool> {
         let mut pc_override = false;
 
-        if !self.current_instruction.is_empty() {
-            // Limit instruction log to 100 entries to prevent unbounded growth
-            if self.instruction_log.len() >= 100 {
-                self.instruction_log.pop_front();
-            }
-            self.instruction_log.push_back(format!(
-                "{} {}",
-                self.current_instruction,
-                if self.current_skipped { "(SKIP)" } else { "" }
+        self.log_instruction();
+        if self.tracing() {
+            let opcode = self.cpu.bus.peek(self.cpu.pc);
+            self.current_instruction = Some(TraceEntry::new(
+                self.cycle,
+                &self.cpu,
+                opcode,
+                Some(op),
+                operands,
             ));
         }
 
-        self.current_skipped = false;
-
         match op {
             Op::HLT => {
                 self.cpu.is_halted = true;
-                self.current_instruction = op.into();
-            }
-            Op::NOP => {
-                self.current_instruction = op.into();
             }
+            Op::NOP => {}
             Op::LDI => {
                 let Operands::RegImm(reg, value) = operands else {
                     return Err(crate::EmulatorError::InvalidOperand {
@@ -321,8 +1218,6 @@ impl NanoCore {
 
                 self.cpu.registers[reg as usize] = value;
                 self.cpu.update_zn_flags(value);
-
-                self.current_instruction = format!("{op}   R{reg} {value:#04X}| ({value:03})");
             }
             Op::LDA => {
                 let Operands::RegAddr(reg, addr) = operands else {
@@ -333,11 +1228,9 @@ impl NanoCore {
                     });
                 };
 
-                let value = self.cpu.memory[addr as usize];
+                let value = self.read_mem(addr)?;
                 self.cpu.registers[reg as usize] = value;
                 self.cpu.update_zn_flags(value);
-
-                self.current_instruction = format!("{op}   R{reg} {addr:#04X}| ({value:03})");
             }
             Op::STORE => {
                 let Operands::RegAddr(reg, addr) = operands else {
@@ -349,10 +1242,24 @@ impl NanoCore {
                 };
 
                 let value = self.cpu.registers[reg as usize];
-                self.cpu.memory[addr as usize] = value;
+                self.write_mem(addr, value)?;
                 self.cpu.update_zn_flags(value);
+            }
+            Op::TAS => {
+                let Operands::RegAddr(reg, addr) = operands else {
+                    return Err(crate::EmulatorError::InvalidOperand {
+                        op: "TAS".to_string(),
+                        expected: "RegAddr".to_string(),
+                        got: format!("{:?}", operands),
+                    });
+                };
 
-                self.current_instruction = format!("{op}   R{reg} {addr:#04X}| ({value:03})");
+                // A whole instruction runs before another core is scheduled,
+                // so the read and write cannot be interleaved.
+                let value = self.read_mem(addr)?;
+                self.write_mem(addr, 1)?;
+                self.cpu.registers[reg as usize] = value;
+                self.cpu.update_zn_flags(value);
             }
             Op::STR => {
                 let Operands::RegReg(rd, rs) = operands else {
@@ -365,10 +1272,7 @@ impl NanoCore {
 
                 let addr = self.cpu.registers[rs as usize];
                 let value = self.cpu.registers[rd as usize];
-                self.cpu.memory[addr as usize] = value;
-
-                self.current_instruction =
-                    format!("{op}   R{rd} [R{rs}]| ({value:03}) -> [{addr:#04X}]");
+                self.write_mem(addr, value)?;
             }
             Op::LDR => {
                 let Operands::RegReg(rd, rs) = operands else {
@@ -380,11 +1284,9 @@ impl NanoCore {
                 };
 
                 let addr = self.cpu.registers[rs as usize];
-                let value = self.cpu.memory[addr as usize];
+                let value = self.read_mem(addr)?;
                 self.cpu.registers[rd as usize] = value;
                 self.cpu.update_zn_flags(value);
-
-                self.current_instruction = format!("{op}   R{rd} R{rs}| {addr:#04X} ({value:03})");
             }
             Op::MOV => {
                 let Operands::RegReg(rd, rs) = operands else {
@@ -398,8 +1300,6 @@ impl NanoCore {
                 let value = self.cpu.registers[rs as usize];
                 self.cpu.registers[rd as usize] = value;
                 self.cpu.update_zn_flags(value);
-
-                self.current_instruction = format!("{op}   R{rd} R{rs}| ({value:03})");
             }
             Op::PUSH => {
                 let Operands::Reg(reg) = operands else {
@@ -410,18 +1310,10 @@ impl NanoCore {
                     });
                 };
 
-                if self.cpu.sp == CPU::STACK_MIN {
-                    return Err(crate::EmulatorError::StackOverflow { sp: self.cpu.sp });
-                }
-
                 let value = self.cpu.registers[reg as usize];
 
-                self.cpu.memory[self.cpu.sp as usize] = value;
-                self.cpu.sp = self.cpu.sp.wrapping_sub(1);
+                self.push(value)?;
                 self.cpu.update_zn_flags(value);
-
-                self.current_instruction =
-                    format!("PUSH  R{reg}| ({value:03}) (SP: {:#04X})", self.cpu.sp);
             }
             Op::POP => {
                 let Operands::Reg(reg) = operands else {
@@ -432,19 +1324,10 @@ impl NanoCore {
                     });
                 };
 
-                if self.cpu.sp == CPU::STACK_MAX {
-                    return Err(crate::EmulatorError::StackUnderflow { sp: self.cpu.sp });
-                }
-
-                self.cpu.sp = self.cpu.sp.wrapping_add(1);
-
-                let value = self.cpu.memory[self.cpu.sp as usize];
+                let value = self.pop()?;
 
                 self.cpu.registers[reg as usize] = value;
                 self.cpu.update_zn_flags(value);
-
-                self.current_instruction =
-                    format!("POP   R{reg}| ({value:03}) (SP: {:#04X})", self.cpu.sp);
             }
             Op::INC | Op::DEC => {
                 let Operands::Reg(reg) = operands else {
@@ -455,19 +1338,27 @@ impl NanoCore {
                     });
                 };
 
-                let value = if op == Op::INC {
-                    self.cpu.registers[reg as usize].wrapping_add(1)
+                let (value, overflow) = if op == Op::INC {
+                    let value = self.cpu.registers[reg as usize].wrapping_add(1);
+                    (value, value == 0x80)
                 } else {
-                    self.cpu.registers[reg as usize].wrapping_sub(1)
+                    let value = self.cpu.registers[reg as usize].wrapping_sub(1);
+                    (value, value == 0x7F)
                 };
 
                 self.cpu.registers[reg as usize] = value;
                 self.cpu.update_zn_flags(value);
-
-                self.current_instruction =
-                    format!("{op}   R{reg}| {:#04X}", self.cpu.registers[reg as usize]);
+                self.cpu.assign_flag(CPU::FLAG_V, overflow);
             }
-            Op::ADD | Op::SUB | Op::MUL | Op::DIV | Op::MOD => {
+            Op::ADD
+            | Op::SUB
+            | Op::MUL
+            | Op::DIV
+            | Op::MOD
+            | Op::ADC
+            | Op::SBC
+            | Op::IDIV
+            | Op::IMOD => {
                 let Operands::RegReg(rd, rs) = operands else {
                     return Err(crate::EmulatorError::InvalidOperand {
                         op: format!("{:?}", op),
@@ -479,7 +1370,7 @@ impl NanoCore {
                 let v1 = self.cpu.registers[rd as usize];
                 let v2 = self.cpu.registers[rs as usize];
 
-                let (result, carry) = self.execute_arithmetic(op, v1, v2)?;
+                let (result, carry, overflow) = self.execute_arithmetic(op, v1, v2)?;
 
                 self.cpu.registers[rd as usize] = result;
                 self.cpu.update_zn_flags(result);
@@ -489,20 +1380,9 @@ impl NanoCore {
                 } else {
                     self.cpu.clear_flag(CPU::FLAG_C);
                 }
-
-                self.current_instruction = format!(
-                    "{op}   R{rd} R{rs}| {v1:03} ({v1:#04X}) {} {v2:03} ({v2:#04X}) = {result:03} ({result:#04X})",
-                    match op {
-                        Op::ADD => "+",
-                        Op::SUB => "-",
-                        Op::MUL => "*",
-                        Op::DIV => "/",
-                        Op::MOD => "%",
-                        _ => unreachable!(),
-                    }
-                );
-            }
-            Op::ADDI | Op::SUBI | Op::MULI | Op::DIVI | Op::MODI => {
+                self.cpu.assign_flag(CPU::FLAG_V, overflow);
+            }
+            Op::ADDI | Op::SUBI | Op::MULI | Op::DIVI | Op::MODI | Op::ADCI | Op::SBCI => {
                 let Operands::RegImm(reg, v2) = operands else {
                     return Err(crate::EmulatorError::InvalidOperand {
                         op: format!("{:?}", op),
@@ -513,7 +1393,7 @@ impl NanoCore {
 
                 let v1 = self.cpu.registers[reg as usize];
 
-                let (result, carry) = self.execute_arithmetic(op, v1, v2)?;
+                let (result, carry, overflow) = self.execute_arithmetic(op, v1, v2)?;
 
                 self.cpu.registers[reg as usize] = result;
                 self.cpu.update_zn_flags(result);
@@ -523,18 +1403,7 @@ impl NanoCore {
                 } else {
                     self.cpu.clear_flag(CPU::FLAG_C);
                 }
-
-                self.current_instruction = format!(
-                    "{op}   R{reg} {v2:03}| {v1:03} ({v1:#04X}) {} {v2:03} ({v2:#04X}) = {result:03} ({result:#04X})",
-                    match op {
-                        Op::ADDI => "+",
-                        Op::SUBI => "-",
-                        Op::MULI => "*",
-                        Op::DIVI => "/",
-                        Op::MODI => "%",
-                        _ => unreachable!(),
-                    }
-                );
+                self.cpu.assign_flag(CPU::FLAG_V, overflow);
             }
             Op::AND | Op::OR | Op::XOR => {
                 let Operands::RegReg(rd, rs) = operands else {
@@ -557,16 +1426,6 @@ impl NanoCore {
 
                 self.cpu.registers[rd as usize] = result;
                 self.cpu.update_zn_flags(result);
-
-                self.current_instruction = format!(
-                    "{op}   R{rd} R{rs}| {v1:03} ({v1:#04X}) {} {v2:03} ({v2:#04X}) = {result:03} ({result:#04X})",
-                    match op {
-                        Op::AND => "&",
-                        Op::OR => "|",
-                        Op::XOR => "^",
-                        _ => unreachable!(),
-                    }
-                );
             }
             Op::CMP => {
                 let Operands::RegReg(rd, rs) = operands else {
@@ -580,6 +1439,7 @@ impl NanoCore {
                 let v1 = self.cpu.registers[rd as usize];
                 let v2 = self.cpu.registers[rs as usize];
                 let (result, borrow) = v1.overflowing_sub(v2);
+                let overflow = (v1 as i8).checked_sub(v2 as i8).is_none();
 
                 self.cpu.update_zn_flags(result);
 
@@ -589,9 +1449,7 @@ impl NanoCore {
                     self.cpu.clear_flag(CPU::FLAG_C);
                 }
 
-                self.current_instruction = format!(
-                    "{op}   R{rd} R{rs}| {v1:03} ({v1:#04X}) - {v2:03} ({v2:#04X}) = {result:03} ({result:#04X})",
-                );
+                self.cpu.assign_flag(CPU::FLAG_V, overflow);
             }
             Op::NOT => {
                 let Operands::Reg(reg) = operands else {
@@ -607,10 +1465,6 @@ impl NanoCore {
 
                 self.cpu.registers[reg as usize] = result;
                 self.cpu.update_zn_flags(result);
-
-                self.current_instruction = format!(
-                    "{op}   R{reg}| ! {value:03} ({value:#04X}) ({value:08b}) = {result:03} ({result:#04X}) ({result:08b})",
-                );
             }
             Op::JMP => {
                 let Operands::Addr(a) = operands else {
@@ -623,9 +1477,6 @@ impl NanoCore {
 
                 self.cpu.pc = a;
                 pc_override = true;
-
-                self.current_instruction =
-                    format!("JMP   {a:#04X}| Mem({:#04X})", self.cpu.memory[a as usize]);
             }
             Op::JMPR => {
                 let Operands::Reg(reg) = operands else {
@@ -639,10 +1490,23 @@ impl NanoCore {
                 let addr = self.cpu.registers[reg as usize];
                 self.cpu.pc = addr;
                 pc_override = true;
-
-                self.current_instruction = format!("{op}  R{reg} ({addr:#04X})");
             }
-            Op::JZ | Op::JNZ => {
+            Op::JZ
+            | Op::JNZ
+            | Op::JC
+            | Op::JNC
+            | Op::JN
+            | Op::JNN
+            | Op::JLT
+            | Op::JGE
+            | Op::JGT
+            | Op::JLE
+            | Op::JLTS
+            | Op::JGES
+            | Op::JGTS
+            | Op::JLES
+            | Op::JV
+            | Op::JNV => {
                 let Operands::Addr(a) = operands else {
                     return Err(crate::EmulatorError::InvalidOperand {
                         op: format!("{:?}", op),
@@ -651,22 +1515,9 @@ impl NanoCore {
                     });
                 };
 
-                self.current_instruction = format!(
-                    "{op}{}   {a:#04X}| Z({}) Mem({:#04X})",
-                    if op == Op::JZ { " " } else { "" },
-                    self.cpu.get_flag(CPU::FLAG_Z) as u8,
-                    self.cpu.memory[a as usize],
-                );
-
-                if self.cpu.get_flag(CPU::FLAG_Z) == (op == Op::JZ) {
+                if self.cpu.branch_condition(op) {
                     self.cpu.pc = a;
                     pc_override = true;
-                } else {
-                    self.current_skipped = true;
-
-                    if self.print_instructions {
-                        print!("(SKIP) ");
-                    }
                 }
             }
             Op::PRINT => {
@@ -679,15 +1530,10 @@ impl NanoCore {
                 };
 
                 let value = self.cpu.registers[reg as usize];
-
-                self.current_instruction = format!(
-                    "{op} R{reg}| '{}' ({value:03}) ({value:#04X})",
-                    value as char
-                );
                 self.output.push(value as char);
 
                 if self.print {
-                    print!("{}", value as char);
+                    self.io.write_byte(value)?;
                 }
             }
             Op::IN => {
@@ -699,21 +1545,12 @@ impl NanoCore {
                     });
                 };
 
-                let mut buffer = [0; 1];
-                std::io::stdin()
-                    .read_exact(&mut buffer)
-                    .map_err(|e| crate::EmulatorError::IoError(e.to_string()))?;
-                let value = buffer[0];
+                let value = self.io.read_byte()?;
 
                 self.cpu.registers[reg as usize] = value;
                 self.cpu.update_zn_flags(value);
-
-                self.current_instruction = format!(
-                    "{op}   R{reg}| '{0}' ({1:03}) ({1:#04X})",
-                    value as char, value
-                );
             }
-            Op::SHL | Op::SHR | Op::ROL | Op::ROR => {
+            Op::SHL | Op::SHR | Op::ROL | Op::ROR | Op::RCL | Op::RCR => {
                 let Operands::Reg(reg) = operands else {
                     return Err(crate::EmulatorError::InvalidOperand {
                         op: format!("{:?}", op),
@@ -723,12 +1560,15 @@ impl NanoCore {
                 };
 
                 let value = self.cpu.registers[reg as usize];
+                let carry_in = self.cpu.get_flag(CPU::FLAG_C) as u8;
 
                 let (result, carry) = match op {
                     Op::SHL => value.overflowing_shl(1),
                     Op::SHR => value.overflowing_shr(1),
                     Op::ROL => (value.rotate_left(1), (value & 0x80) != 0),
                     Op::ROR => (value.rotate_right(1), (value & 0x01) != 0),
+                    Op::RCL => ((value << 1) | carry_in, (value & 0x80) != 0),
+                    Op::RCR => ((value >> 1) | (carry_in << 7), (value & 0x01) != 0),
                     _ => unreachable!(),
                 };
 
@@ -740,17 +1580,6 @@ impl NanoCore {
                 } else {
                     self.cpu.clear_flag(CPU::FLAG_C);
                 }
-
-                self.current_instruction = format!(
-                    "{op}   R{reg}| {value:03} ({value:08b}) {} 1 = {result:03} ({result:08b})",
-                    match op {
-                        Op::SHL => "<<",
-                        Op::SHR => ">>",
-                        Op::ROL => "ROL",
-                        Op::ROR => "ROR",
-                        _ => unreachable!(),
-                    }
-                );
             }
             Op::CALL => {
                 let Operands::Addr(a) = operands else {
@@ -761,18 +1590,10 @@ impl NanoCore {
                     });
                 };
 
-                if self.cpu.sp == CPU::STACK_MIN {
-                    return Err(crate::EmulatorError::StackOverflow { sp: self.cpu.sp });
-                }
-
-                self.cpu.memory[self.cpu.sp as usize] = self.cpu.pc.wrapping_add(2);
-                self.cpu.sp = self.cpu.sp.wrapping_sub(1);
+                self.push(self.cpu.pc.wrapping_add(2))?;
 
                 self.cpu.pc = a;
                 pc_override = true;
-
-                self.current_instruction =
-                    format!("CALL  {a:#04X}| Mem({:#04X})", self.cpu.memory[a as usize]);
             }
             Op::CALLR => {
                 let Operands::Reg(reg) = operands else {
@@ -783,44 +1604,64 @@ impl NanoCore {
                     });
                 };
 
-                if self.cpu.sp == CPU::STACK_MIN {
-                    return Err(crate::EmulatorError::StackOverflow { sp: self.cpu.sp });
-                }
-
-                self.cpu.memory[self.cpu.sp as usize] = self.cpu.pc.wrapping_add(2);
-                self.cpu.sp = self.cpu.sp.wrapping_sub(1);
+                self.push(self.cpu.pc.wrapping_add(2))?;
 
                 let addr = self.cpu.registers[reg as usize];
                 self.cpu.pc = addr;
                 pc_override = true;
-
-                self.current_instruction = format!("CALLR R{reg} ({addr:#04X})");
             }
             Op::RET => {
-                if self.cpu.sp == CPU::STACK_MAX {
-                    return Err(crate::EmulatorError::StackUnderflow { sp: self.cpu.sp });
-                }
-
-                self.cpu.sp = self.cpu.sp.wrapping_add(1);
-                self.cpu.pc = self.cpu.memory[self.cpu.sp as usize];
+                self.cpu.pc = self.pop()?;
+                pc_override = true;
+            }
+            Op::EI => {
+                self.cpu.set_flag(CPU::FLAG_I);
+            }
+            Op::DI => {
+                self.cpu.clear_flag(CPU::FLAG_I);
+            }
+            Op::RETI => {
+                self.cpu.flags = self.pop()?;
+                self.cpu.pc = self.pop()?;
                 pc_override = true;
-
-                self.current_instruction = format!(
-                    "RET  | {:#04X} Mem({:#04X})",
-                    self.cpu.pc, self.cpu.memory[self.cpu.pc as usize]
-                );
             }
         }
 
-        if self.print_instructions {
-            println!("->{}", self.current_instruction);
-        }
+        let next_pc = if pc_override || self.cpu.is_halted {
+            self.cpu.pc
+        } else {
+            self.cpu.pc.wrapping_add(op.instruction_len())
+        };
+        self.finish_trace(pc_override, next_pc);
 
         Ok(pc_override)
     }
 }
 
-#[derive(Debug)]
+/// Why a run-control method ([`NanoCore::step`], [`NanoCore::run_for`],
+/// [`NanoCore::run_until`]) returned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopReason {
+    /// The CPU executed `HLT`.
+    Halted,
+    /// The cycle budget ([`NanoCore::max_cycles`]) is exhausted.
+    CycleLimit,
+    /// The requested number of instructions was executed.
+    Stepped,
+    /// The `run_until` predicate returned `true`.
+    Predicate,
+    /// The PC reached a breakpoint. The instruction at `pc` has not run yet.
+    Breakpoint { pc: u8 },
+    /// A watchpoint was triggered by the instruction that just ran.
+    Watchpoint(WatchHit),
+    /// The instruction that just ran repeated an earlier machine state, so
+    /// the program will never leave the loop.
+    Loop(LoopHit),
+    /// An instruction failed.
+    Error(crate::EmulatorError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Operands {
     None,
     Reg(u8),
@@ -829,3 +1670,72 @@ pub enum Operands {
     RegAddr(u8, u8),
     Addr(u8),
 }
+
+impl Operands {
+    /// Splits the operand bytes following an opcode. `byte_2` and `byte_3`
+    /// are ignored where [`Op::instruction_len`] says the instruction does
+    /// not have them.
+    pub fn decode(op: Op, byte_2: u8, byte_3: u8) -> Self {
+        match op {
+            Op::HLT | Op::NOP | Op::RET | Op::EI | Op::DI | Op::RETI => Operands::None,
+            Op::LDI
+            | Op::ADDI
+            | Op::SUBI
+            | Op::MULI
+            | Op::DIVI
+            | Op::MODI
+            | Op::ADCI
+            | Op::SBCI => Operands::RegImm(byte_2, byte_3),
+            Op::LDA | Op::STORE | Op::TAS => Operands::RegAddr(byte_2, byte_3),
+            Op::PUSH
+            | Op::POP
+            | Op::INC
+            | Op::DEC
+            | Op::NOT
+            | Op::SHL
+            | Op::SHR
+            | Op::ROL
+            | Op::ROR
+            | Op::RCL
+            | Op::RCR
+            | Op::IN
+            | Op::JMPR
+            | Op::CALLR
+            | Op::PRINT => Operands::Reg(byte_2),
+            Op::LDR
+            | Op::MOV
+            | Op::ADD
+            | Op::SUB
+            | Op::AND
+            | Op::OR
+            | Op::XOR
+            | Op::CMP
+            | Op::MUL
+            | Op::DIV
+            | Op::MOD
+            | Op::ADC
+            | Op::SBC
+            | Op::IDIV
+            | Op::IMOD
+            | Op::STR => Operands::RegReg((byte_2 >> 4) & 0x0F, byte_2 & 0x0F),
+            Op::JMP
+            | Op::CALL
+            | Op::JZ
+            | Op::JNZ
+            | Op::JC
+            | Op::JNC
+            | Op::JN
+            | Op::JNN
+            | Op::JLT
+            | Op::JGE
+            | Op::JGT
+            | Op::JLE
+            | Op::JLTS
+            | Op::JGES
+            | Op::JGTS
+            | Op::JLES
+            | Op::JV
+            | Op::JNV => Operands::Addr(byte_2),
+        }
+    }
+}
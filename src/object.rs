@@ -0,0 +1,258 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{fs, path::Path};
+
+use crate::{
+    FormatError, FormatResult,
+    assembler::Assembler,
+    codec::{Reader, check_crc32, crc32, write_bytes},
+};
+
+/// A label defined by a module, relative to the start of its code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectSymbol {
+    pub name: String,
+    pub value: u8,
+    /// Exported with `.GLOBAL`, so other modules can refer to it.
+    pub global: bool,
+}
+
+/// A code byte holding the address of `symbol`, which the linker rewrites
+/// once the symbol has been placed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocation {
+    pub offset: u8,
+    /// A label of the same module, or one of its externs.
+    pub symbol: String,
+}
+
+/// A separately assembled module (`.nco`), ready for the
+/// [`Linker`](crate::linker::Linker).
+///
+/// The code is assembled as if it were loaded at `0x00`. Banks are not
+/// supported, and constants are not exported.
+///
+/// # File format
+///
+/// All integers are little-endian; names are a `u32` length and UTF-8 bytes.
+///
+/// | Field | Size |
+/// | :--- | :--- |
+/// | Magic `NCBO` | 4 |
+/// | Version | 1 |
+/// | Code length (`u16`) and bytes | 2 + n |
+/// | Symbol count (`u16`) | 2 |
+/// | Per symbol: global flag, value, name | 6 + n |
+/// | Extern count (`u16`) | 2 |
+/// | Per extern: name | 4 + n |
+/// | Relocation count (`u16`) | 2 |
+/// | Per relocation: offset, symbol name | 5 + n |
+/// | CRC-32 of everything before it | 4 |
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{assembler::Assembler, object::Object};
+///
+/// let mut assembler = Assembler::default();
+/// assembler
+///     .assemble(".GLOBAL main\n.EXTERN print\nmain:\nCALL print\nJMP main")
+///     .unwrap();
+///
+/// let object = Object::from_assembler(&assembler).unwrap();
+/// assert_eq!(object.externs, ["print"]);
+/// assert_eq!(object.relocations[0].symbol, "print");
+/// assert_eq!(object.relocations[1].offset, 0x03);
+/// assert_eq!(Object::from_bytes(&object.to_bytes()).unwrap(), object);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Object {
+    pub code: Vec<u8>,
+    pub symbols: Vec<ObjectSymbol>,
+    pub externs: Vec<String>,
+    pub relocations: Vec<Relocation>,
+}
+
+impl Object {
+    pub const MAGIC: &'static [u8; 4] = b"NCBO";
+    pub const VERSION: u8 = 1;
+
+    /// Collects the code, labels, externs and label references of an
+    /// assembled module. Symbols are sorted by value, then name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::InvalidData`] if the module declares banks.
+    pub fn from_assembler(assembler: &Assembler) -> FormatResult<Self> {
+        if !assembler.banks.is_empty() {
+            return Err(FormatError::InvalidData(
+                "object files cannot contain banks".to_string(),
+            ));
+        }
+
+        let mut symbols: Vec<ObjectSymbol> = assembler
+            .labels
+            .iter()
+            .map(|(name, &value)| ObjectSymbol {
+                name: name.clone(),
+                value,
+                global: assembler.globals.contains(name),
+            })
+            .collect();
+        symbols.sort_by(|a, b| (a.value, &a.name).cmp(&(b.value, &b.name)));
+
+        Ok(Self {
+            code: assembler.program.clone(),
+            symbols,
+            externs: assembler.externs.keys().cloned().collect(),
+            relocations: assembler
+                .references
+                .iter()
+                .map(|(&offset, symbol)| Relocation {
+                    offset,
+                    symbol: symbol.clone(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Whether `bytes` start with the object file magic.
+    pub fn is_object(bytes: &[u8]) -> bool {
+        bytes.starts_with(Self::MAGIC)
+    }
+
+    /// The symbols exported with `.GLOBAL`.
+    pub fn globals(&self) -> impl Iterator<Item = &ObjectSymbol> {
+        self.symbols.iter().filter(|s| s.global)
+    }
+
+    /// Reads an object file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::Io`] if the file cannot be read, or any error
+    /// from [`from_bytes`](Self::from_bytes).
+    pub fn read(path: impl AsRef<Path>) -> FormatResult<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Writes the object file.
+    pub fn write(&self, path: impl AsRef<Path>) -> FormatResult<()> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Encodes the object file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend(Self::MAGIC);
+        out.push(Self::VERSION);
+
+        out.extend((self.code.len() as u16).to_le_bytes());
+        out.extend(&self.code);
+
+        out.extend((self.symbols.len() as u16).to_le_bytes());
+        for symbol in &self.symbols {
+            out.extend([symbol.global as u8, symbol.value]);
+            write_bytes(&mut out, symbol.name.as_bytes());
+        }
+
+        out.extend((self.externs.len() as u16).to_le_bytes());
+        for name in &self.externs {
+            write_bytes(&mut out, name.as_bytes());
+        }
+
+        out.extend((self.relocations.len() as u16).to_le_bytes());
+        for relocation in &self.relocations {
+            out.push(relocation.offset);
+            write_bytes(&mut out, relocation.symbol.as_bytes());
+        }
+
+        out.extend(crc32(&out).to_le_bytes());
+        out
+    }
+
+    /// Decodes an object file written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FormatError`] if the magic, version or checksum does not
+    /// match, the data is truncated, the code does not fit in memory, or a
+    /// relocation lies outside the code.
+    pub fn from_bytes(bytes: &[u8]) -> FormatResult<Self> {
+        let (body, checksum) = bytes.split_at(bytes.len().saturating_sub(4));
+        let mut r = Reader::new(body);
+        r.magic(Self::MAGIC)?;
+
+        let version = r.u8()?;
+        if version != Self::VERSION {
+            return Err(FormatError::UnsupportedVersion { version });
+        }
+
+        check_crc32(body, checksum)?;
+
+        let len = r.u16()? as usize;
+        if len > 256 {
+            return Err(FormatError::InvalidData(format!(
+                "{} bytes of code do not fit in memory",
+                len
+            )));
+        }
+        let code = r.take(len)?.to_vec();
+
+        let count = r.u16()?;
+        let mut symbols = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let [global, value] = r.array()?;
+            symbols.push(ObjectSymbol {
+                name: r.string()?,
+                value,
+                global: global != 0,
+            });
+        }
+
+        let count = r.u16()?;
+        let mut externs = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            externs.push(r.string()?);
+        }
+
+        let count = r.u16()?;
+        let mut relocations = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let offset = r.u8()?;
+            if offset as usize >= code.len() {
+                return Err(FormatError::InvalidData(format!(
+                    "relocation at {:#04X} is outside the {}-byte code",
+                    offset,
+                    code.len()
+                )));
+            }
+            relocations.push(Relocation {
+                offset,
+                symbol: r.string()?,
+            });
+        }
+
+        Ok(Self {
+            code,
+            symbols,
+            externs,
+            relocations,
+        })
+    }
+}
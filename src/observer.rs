@@ -0,0 +1,131 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use crate::{EmulatorError, Op, cpu::CPU, nanocore::Operands};
+
+/// Hooks called by [`NanoCore`](crate::nanocore::NanoCore) as it executes.
+///
+/// Every method has a no-op default, so an observer only implements the
+/// events it cares about. Observers are registered with
+/// [`NanoCore::add_observer`](crate::nanocore::NanoCore::add_observer); a core
+/// without observers skips the notifications entirely.
+///
+/// Observers are `Any` so callers can get the concrete type back with
+/// [`NanoCore::observer`](crate::nanocore::NanoCore::observer). To keep a
+/// handle on an observer while the core owns it, register an
+/// `Rc<RefCell<T>>`.
+///
+/// # Examples
+///
+/// ```
+/// use std::{cell::RefCell, rc::Rc};
+///
+/// use nanocore::{nanocore::NanoCore, observer::Observer};
+///
+/// #[derive(Debug, Default)]
+/// struct Writes(Vec<(u8, u8, u8)>);
+///
+/// impl Observer for Writes {
+///     fn on_write(&mut self, addr: u8, old: u8, new: u8) {
+///         self.0.push((addr, old, new));
+///     }
+/// }
+///
+/// let writes = Rc::new(RefCell::new(Writes::default()));
+/// let mut nano = NanoCore::new();
+/// nano.add_observer(writes.clone());
+///
+/// // LDI R0 7; STORE R0 0x80; HLT
+/// nano.load_program(&[0x02, 0x00, 0x07, 0x06, 0x00, 0x80, 0x00], 0x00).unwrap();
+/// nano.run().unwrap();
+/// assert_eq!(writes.borrow().0, [(0x80, 0, 7)]);
+/// ```
+#[allow(unused_variables)]
+pub trait Observer: Any + std::fmt::Debug {
+    /// An opcode byte was fetched from `pc`.
+    fn on_fetch(&mut self, pc: u8, opcode: u8) {}
+
+    /// The instruction at `pc` is about to execute.
+    fn before_execute(&mut self, cpu: &CPU, pc: u8, op: Op, operands: Operands) {}
+
+    /// The instruction at `pc` executed, taking `t_states` clock ticks.
+    fn after_execute(&mut self, cpu: &CPU, pc: u8, op: Op, t_states: u64) {}
+
+    /// A data byte was read (`LDA`, `LDR`, the interrupt vector).
+    fn on_read(&mut self, addr: u8, value: u8) {}
+
+    /// A data byte was written (`STORE`, `STR`).
+    fn on_write(&mut self, addr: u8, old: u8, new: u8) {}
+
+    /// `value` was pushed to `addr` on the stack.
+    fn on_push(&mut self, addr: u8, value: u8) {}
+
+    /// `value` was popped from `addr` on the stack.
+    fn on_pop(&mut self, addr: u8, value: u8) {}
+
+    /// An interrupt was taken; `return_addr` is the interrupted PC.
+    fn on_interrupt(&mut self, cpu: &CPU, return_addr: u8) {}
+
+    /// The CPU executed `HLT`.
+    fn on_halt(&mut self, cpu: &CPU) {}
+
+    /// A cycle failed with `error`.
+    fn on_error(&mut self, cpu: &CPU, error: &EmulatorError) {}
+}
+
+impl<T: Observer> Observer for Rc<RefCell<T>> {
+    fn on_fetch(&mut self, pc: u8, opcode: u8) {
+        self.borrow_mut().on_fetch(pc, opcode);
+    }
+
+    fn before_execute(&mut self, cpu: &CPU, pc: u8, op: Op, operands: Operands) {
+        self.borrow_mut().before_execute(cpu, pc, op, operands);
+    }
+
+    fn after_execute(&mut self, cpu: &CPU, pc: u8, op: Op, t_states: u64) {
+        self.borrow_mut().after_execute(cpu, pc, op, t_states);
+    }
+
+    fn on_read(&mut self, addr: u8, value: u8) {
+        self.borrow_mut().on_read(addr, value);
+    }
+
+    fn on_write(&mut self, addr: u8, old: u8, new: u8) {
+        self.borrow_mut().on_write(addr, old, new);
+    }
+
+    fn on_push(&mut self, addr: u8, value: u8) {
+        self.borrow_mut().on_push(addr, value);
+    }
+
+    fn on_pop(&mut self, addr: u8, value: u8) {
+        self.borrow_mut().on_pop(addr, value);
+    }
+
+    fn on_interrupt(&mut self, cpu: &CPU, return_addr: u8) {
+        self.borrow_mut().on_interrupt(cpu, return_addr);
+    }
+
+    fn on_halt(&mut self, cpu: &CPU) {
+        self.borrow_mut().on_halt(cpu);
+    }
+
+    fn on_error(&mut self, cpu: &CPU, error: &EmulatorError) {
+        self.borrow_mut().on_error(cpu, error);
+    }
+}
@@ -0,0 +1,370 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+use crate::{Op, cpu::CPU, observer::Observer};
+
+/// A straight run of instructions, entered at its start address and left by
+/// the control-flow instruction at `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Block {
+    pub end: u8,
+    /// Times execution entered the block.
+    pub entries: u64,
+    pub instructions: u64,
+    pub t_states: u64,
+}
+
+/// Totals for one label or subroutine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolStats {
+    pub name: String,
+    pub addr: u8,
+    /// Instructions executed for a label; calls for a subroutine.
+    pub count: u64,
+    pub t_states: u64,
+}
+
+/// An [`Observer`] that counts executions and T-states per address, label,
+/// subroutine and basic block.
+///
+/// Addresses are attributed to symbols with the assembler's
+/// [`labels`](crate::assembler::Assembler::labels). Calls and interrupts are
+/// tracked on a shadow call stack, whose frames are named after the entry
+/// address, so time can be exported as collapsed stacks for flame graphs.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{assembler::Assembler, nanocore::NanoCore, profiler::Profiler};
+///
+/// let mut assembler = Assembler::default();
+/// assembler
+///     .assemble("start:\nLDI R0 3\nloop:\nDEC R0\nJNZ loop\nCALL done\nHLT\ndone:\nRET")
+///     .unwrap();
+///
+/// let mut nano = NanoCore::new();
+/// nano.load_program(&assembler.program, 0x00).unwrap();
+/// nano.add_observer(Profiler::new().with_labels(&assembler.labels));
+/// nano.run().unwrap();
+///
+/// let profiler = nano.observer::<Profiler>().unwrap();
+/// assert_eq!(profiler.hits[0x03], 3); // DEC R0
+/// assert_eq!(profiler.symbol(0x05), "loop+2");
+/// assert_eq!(profiler.collapsed(), "start 21\nstart;done 4\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    /// Instructions executed at each address.
+    pub hits: [u64; 256],
+    /// T-states spent at each address.
+    pub t_states: [u64; 256],
+    /// The instruction last executed at each address.
+    pub ops: [Option<Op>; 256],
+    /// Blocks by start address.
+    pub blocks: BTreeMap<u8, Block>,
+    /// T-states per call stack. Each frame is the entry address of a
+    /// subroutine or interrupt handler, outermost first.
+    pub stacks: BTreeMap<Vec<u8>, u64>,
+    /// Calls (including interrupts) per entry address.
+    pub calls: BTreeMap<u8, u64>,
+    symbols: Vec<(u8, String)>,
+    call_stack: Vec<u8>,
+    block: Option<u8>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self {
+            hits: [0; 256],
+            t_states: [0; 256],
+            ops: [None; 256],
+            blocks: BTreeMap::new(),
+            stacks: BTreeMap::new(),
+            calls: BTreeMap::new(),
+            symbols: Vec::new(),
+            call_stack: Vec::new(),
+            block: None,
+        }
+    }
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names addresses after the labels at or before them.
+    pub fn with_labels(mut self, labels: &HashMap<String, u8>) -> Self {
+        self.symbols = labels
+            .iter()
+            .map(|(name, &addr)| (addr, name.clone()))
+            .collect();
+        self.symbols.sort();
+        self
+    }
+
+    /// Total instructions executed.
+    pub fn instructions(&self) -> u64 {
+        self.hits.iter().sum()
+    }
+
+    /// Total T-states spent.
+    pub fn total_t_states(&self) -> u64 {
+        self.t_states.iter().sum()
+    }
+
+    /// The closest label at or before `addr`.
+    fn label(&self, addr: u8) -> Option<&(u8, String)> {
+        let idx = self.symbols.partition_point(|&(a, _)| a <= addr);
+        idx.checked_sub(1).map(|i| &self.symbols[i])
+    }
+
+    /// Names `addr` as `label` or `label+offset`, or in hex before the first
+    /// label.
+    pub fn symbol(&self, addr: u8) -> String {
+        match self.label(addr) {
+            Some((base, name)) if *base == addr => name.clone(),
+            Some((base, name)) => format!("{name}+{}", addr - base),
+            None => format!("{addr:#04X}"),
+        }
+    }
+
+    /// Instructions and T-states per label, counting only the code between
+    /// the label and the next one. Sorted by T-states, most first.
+    pub fn label_stats(&self) -> Vec<SymbolStats> {
+        let mut stats: BTreeMap<u8, SymbolStats> = BTreeMap::new();
+        for addr in (0..=u8::MAX).filter(|&a| self.hits[a as usize] > 0) {
+            let (base, name) = match self.label(addr) {
+                Some((base, name)) => (*base, name.clone()),
+                None => (0, "(no label)".to_owned()),
+            };
+            let entry = stats.entry(base).or_insert(SymbolStats {
+                name,
+                addr: base,
+                count: 0,
+                t_states: 0,
+            });
+            entry.count += self.hits[addr as usize];
+            entry.t_states += self.t_states[addr as usize];
+        }
+
+        Self::sorted(stats.into_values())
+    }
+
+    /// Calls and inclusive T-states (including callees) per subroutine or
+    /// interrupt handler. Sorted by T-states, most first.
+    pub fn subroutine_stats(&self) -> Vec<SymbolStats> {
+        let mut inclusive: BTreeMap<u8, u64> = BTreeMap::new();
+        for (stack, &t_states) in &self.stacks {
+            let mut seen = Vec::new();
+            for &frame in &stack[1..] {
+                if !seen.contains(&frame) {
+                    seen.push(frame);
+                    *inclusive.entry(frame).or_default() += t_states;
+                }
+            }
+        }
+
+        Self::sorted(inclusive.into_iter().map(|(addr, t_states)| SymbolStats {
+            name: self.symbol(addr),
+            addr,
+            count: self.calls.get(&addr).copied().unwrap_or(0),
+            t_states,
+        }))
+    }
+
+    fn sorted(stats: impl Iterator<Item = SymbolStats>) -> Vec<SymbolStats> {
+        let mut stats: Vec<_> = stats.collect();
+        stats.sort_by(|a, b| b.t_states.cmp(&a.t_states).then(a.addr.cmp(&b.addr)));
+        stats
+    }
+
+    /// The `n` blocks that executed the most instructions, by start address.
+    pub fn hot_blocks(&self, n: usize) -> Vec<(u8, Block)> {
+        let mut blocks: Vec<_> = self.blocks.iter().map(|(&s, &b)| (s, b)).collect();
+        blocks.sort_by(|a, b| b.1.instructions.cmp(&a.1.instructions).then(a.0.cmp(&b.0)));
+        blocks.truncate(n);
+        blocks
+    }
+
+    /// T-states per call stack in the collapsed format read by flame graph
+    /// tools: one `frame;frame;frame count` line per stack.
+    pub fn collapsed(&self) -> String {
+        let mut out = String::new();
+        for (stack, t_states) in &self.stacks {
+            let frames: Vec<_> = stack.iter().map(|&addr| self.symbol(addr)).collect();
+            let _ = writeln!(out, "{} {}", frames.join(";"), t_states);
+        }
+        out
+    }
+
+    /// A plain-text report: per-address counts, labels, subroutines and the
+    /// ten hottest blocks.
+    pub fn report(&self) -> String {
+        let total = self.total_t_states().max(1);
+        let percent = |t: u64| t as f64 * 100.0 / total as f64;
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "== Profile: {} instructions, {} T-states ==",
+            self.instructions(),
+            self.total_t_states()
+        );
+
+        let _ = writeln!(out, "\nAddress  Symbol           Op         Hits  T-states");
+        for addr in (0..=u8::MAX).filter(|&a| self.hits[a as usize] > 0) {
+            let op = self.ops[addr as usize].map_or_else(String::new, |op| op.to_string());
+            let _ = writeln!(
+                out,
+                "{:#04X}     {:<16} {:<5} {:>9} {:>9}",
+                addr,
+                self.symbol(addr),
+                op,
+                self.hits[addr as usize],
+                self.t_states[addr as usize]
+            );
+        }
+
+        let _ = writeln!(out, "\nLabel                    Hits  T-states      %");
+        for s in self.label_stats() {
+            let _ = writeln!(
+                out,
+                "{:<20} {:>8} {:>9} {:>6.1}",
+                s.name,
+                s.count,
+                s.t_states,
+                percent(s.t_states)
+            );
+        }
+
+        let subroutines = self.subroutine_stats();
+        if !subroutines.is_empty() {
+            let _ = writeln!(out, "\nSubroutine (inclusive)  Calls  T-states      %");
+            for s in subroutines {
+                let _ = writeln!(
+                    out,
+                    "{:<20} {:>8} {:>9} {:>6.1}",
+                    s.name,
+                    s.count,
+                    s.t_states,
+                    percent(s.t_states)
+                );
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "\nHot blocks                      Entries  Instructions  T-states"
+        );
+        for (start, block) in self.hot_blocks(10) {
+            let _ = writeln!(
+                out,
+                "{:#04X}-{:#04X} {:<20} {:>8} {:>13} {:>9}",
+                start,
+                block.end,
+                self.symbol(start),
+                block.entries,
+                block.instructions,
+                block.t_states
+            );
+        }
+
+        out
+    }
+
+    /// Prefixes each line of `asm` with its hits and T-states, using the
+    /// assembler's [`line_map`](crate::assembler::Assembler::line_map).
+    pub fn annotate(&self, asm: &str, line_map: &BTreeMap<u8, usize>) -> String {
+        let addrs: HashMap<usize, u8> = line_map.iter().map(|(&a, &l)| (l, a)).collect();
+        let mut out = String::new();
+
+        for (idx, line) in asm.lines().enumerate() {
+            match addrs
+                .get(&(idx + 1))
+                .filter(|&&a| self.ops[a as usize].is_some())
+            {
+                Some(&addr) => {
+                    let _ = writeln!(
+                        out,
+                        "{:>8} {:>9} | {}",
+                        self.hits[addr as usize], self.t_states[addr as usize], line
+                    );
+                }
+                None => {
+                    let _ = writeln!(out, "{:>8} {:>9} | {}", "", "", line);
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl Observer for Profiler {
+    fn after_execute(&mut self, cpu: &CPU, pc: u8, op: Op, t_states: u64) {
+        self.hits[pc as usize] += 1;
+        self.t_states[pc as usize] += t_states;
+        self.ops[pc as usize] = Some(op);
+
+        if self.call_stack.is_empty() {
+            self.call_stack.push(pc);
+        }
+        match self.stacks.get_mut(self.call_stack.as_slice()) {
+            Some(total) => *total += t_states,
+            None => {
+                self.stacks.insert(self.call_stack.clone(), t_states);
+            }
+        }
+
+        let entered = self.block.is_none();
+        let start = *self.block.get_or_insert(pc);
+        let block = self.blocks.entry(start).or_default();
+        block.end = pc;
+        block.entries += entered as u64;
+        block.instructions += 1;
+        block.t_states += t_states;
+
+        if op.is_control_flow() {
+            self.block = None;
+        }
+
+        match op {
+            Op::CALL | Op::CALLR => {
+                self.call_stack.push(cpu.pc);
+                *self.calls.entry(cpu.pc).or_default() += 1;
+            }
+            Op::RET | Op::RETI if self.call_stack.len() > 1 => {
+                self.call_stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_interrupt(&mut self, cpu: &CPU, return_addr: u8) {
+        if self.call_stack.is_empty() {
+            self.call_stack.push(return_addr);
+        }
+        self.call_stack.push(cpu.pc);
+        *self.calls.entry(cpu.pc).or_default() += 1;
+        self.block = None;
+    }
+}
@@ -0,0 +1,207 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::fmt;
+
+use crate::{cpu::CPU, devices::Screen};
+
+/// The kind of memory access being checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    /// Data load (`LDA`, `LDR`, ...).
+    Read,
+    /// Data store (`STORE`, `STR`).
+    Write,
+    /// Instruction fetch.
+    Execute,
+    /// Stack access (`PUSH`, `POP`, `CALL`, `RET`, interrupts).
+    Stack,
+}
+
+impl fmt::Display for AccessKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Execute => "execute",
+            Self::Stack => "stack",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Set of allowed [`AccessKind`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions(u8);
+
+impl Permissions {
+    pub const NONE: Self = Self(0);
+    pub const READ: Self = Self(0b0001);
+    pub const WRITE: Self = Self(0b0010);
+    pub const EXECUTE: Self = Self(0b0100);
+    pub const STACK: Self = Self(0b1000);
+
+    pub const RW: Self = Self(Self::READ.0 | Self::WRITE.0);
+    pub const RX: Self = Self(Self::READ.0 | Self::EXECUTE.0);
+    pub const ALL: Self = Self(0b1111);
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn allows(self, kind: AccessKind) -> bool {
+        let bit = match kind {
+            AccessKind::Read => Self::READ,
+            AccessKind::Write => Self::WRITE,
+            AccessKind::Execute => Self::EXECUTE,
+            AccessKind::Stack => Self::STACK,
+        };
+        self.0 & bit.0 != 0
+    }
+}
+
+impl fmt::Display for Permissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            if self.allows(AccessKind::Read) {
+                'r'
+            } else {
+                '-'
+            },
+            if self.allows(AccessKind::Write) {
+                'w'
+            } else {
+                '-'
+            },
+            if self.allows(AccessKind::Execute) {
+                'x'
+            } else {
+                '-'
+            },
+            if self.allows(AccessKind::Stack) {
+                's'
+            } else {
+                '-'
+            },
+        )
+    }
+}
+
+/// A named address range with its permissions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub name: String,
+    pub start: u8,
+    pub end: u8,
+    pub permissions: Permissions,
+}
+
+impl Region {
+    pub fn new(name: &str, start: u8, end: u8, permissions: Permissions) -> Self {
+        Self {
+            name: name.to_owned(),
+            start,
+            end,
+            permissions,
+        }
+    }
+
+    pub fn contains(&self, addr: u8) -> bool {
+        (self.start..=self.end).contains(&addr)
+    }
+}
+
+/// Region permissions enforced by [`NanoCore`](crate::nanocore::NanoCore)
+/// when set as its [`memory_map`](crate::nanocore::NanoCore::memory_map).
+///
+/// The first region containing an address decides; addresses outside every
+/// region get the [`fallback`](Self::fallback) permissions.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::protection::{AccessKind, MemoryMap};
+///
+/// let map = MemoryMap::standard(0x00, 16);
+/// assert!(map.allows(0x04, AccessKind::Execute));
+/// assert!(!map.allows(0x04, AccessKind::Write));
+/// assert!(!map.allows(0x40, AccessKind::Execute));
+/// assert!(map.allows(0xF0, AccessKind::Stack));
+/// assert!(!map.allows(0xF0, AccessKind::Read));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryMap {
+    pub regions: Vec<Region>,
+    pub fallback: Permissions,
+}
+
+impl Default for MemoryMap {
+    fn default() -> Self {
+        Self {
+            regions: Vec::new(),
+            fallback: Permissions::ALL,
+        }
+    }
+}
+
+impl MemoryMap {
+    /// The default map for a program of `len` bytes loaded at `start`:
+    ///
+    /// - the program is read-execute,
+    /// - the screen is read-write,
+    /// - the stack is only reachable through stack operations,
+    /// - everything else is read-write data.
+    pub fn standard(start: u8, len: usize) -> Self {
+        let mut regions = Vec::new();
+
+        if len > 0 {
+            let end = (start as usize + len - 1).min(u8::MAX as usize) as u8;
+            regions.push(Region::new("code", start, end, Permissions::RX));
+        }
+
+        regions.push(Region::new(
+            "screen",
+            Screen::BASE,
+            Screen::END,
+            Permissions::RW,
+        ));
+        regions.push(Region::new(
+            "stack",
+            CPU::STACK_MIN,
+            CPU::STACK_MAX,
+            Permissions::STACK,
+        ));
+
+        Self {
+            regions,
+            fallback: Permissions::RW,
+        }
+    }
+
+    pub fn region(&self, addr: u8) -> Option<&Region> {
+        self.regions.iter().find(|r| r.contains(addr))
+    }
+
+    pub fn permissions(&self, addr: u8) -> Permissions {
+        self.region(addr).map_or(self.fallback, |r| r.permissions)
+    }
+
+    pub fn allows(&self, addr: u8, kind: AccessKind) -> bool {
+        self.permissions(addr).allows(kind)
+    }
+}
@@ -0,0 +1,217 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{fs, path::Path};
+
+use crate::{
+    FormatError, FormatResult,
+    codec::{Reader, write_bytes},
+    trace::TraceEntry,
+};
+
+/// Saved state of one mapped device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceState {
+    pub start: u8,
+    pub name: String,
+    pub state: Vec<u8>,
+}
+
+/// A complete copy of the emulator state.
+///
+/// Configuration such as the I/O backend, breakpoints, the memory map and the
+/// print flags is not part of a snapshot; restoring keeps the current values.
+///
+/// # File format
+///
+/// All integers are little-endian. Strings and byte blobs are prefixed with
+/// their length as a `u32`. Trace entries are stored as blobs; an empty blob
+/// means no current instruction.
+///
+/// | Field | Size |
+/// | :--- | :--- |
+/// | Magic `NCST` | 4 |
+/// | Version | 1 |
+/// | Registers R0–R15 | 16 |
+/// | PC, SP, flags, halted, IRQ pending, IRQ vector | 6 |
+/// | RAM | 256 |
+/// | Cycle | 8 |
+/// | T-states | 8 |
+/// | Current instruction, output | blob, string |
+/// | Instruction log | `u32` count, then blobs |
+/// | Devices | `u8` count, then start address, name and state blob |
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{nanocore::NanoCore, snapshot::Snapshot};
+///
+/// let mut nano = NanoCore::new();
+/// nano.load_program(&[0x0D, 0x00, 0x0D, 0x00, 0x00], 0x00).unwrap(); // INC R0; INC R0; HLT
+/// nano.cycle().unwrap();
+///
+/// let bytes = nano.snapshot().to_bytes();
+/// nano.run().unwrap();
+/// assert_eq!(nano.cpu.registers[0], 2);
+///
+/// nano.restore(&Snapshot::from_bytes(&bytes).unwrap());
+/// assert_eq!(nano.cpu.registers[0], 1);
+/// assert_eq!(nano.cpu.pc, 0x02);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub registers: [u8; 16],
+    pub pc: u8,
+    pub sp: u8,
+    pub flags: u8,
+    pub is_halted: bool,
+    pub irq_pending: bool,
+    pub irq_vector: u8,
+    pub ram: [u8; 256],
+    pub cycle: u64,
+    pub t_states: u64,
+    pub current_instruction: Option<TraceEntry>,
+    pub output: String,
+    pub instruction_log: Vec<TraceEntry>,
+    pub devices: Vec<DeviceState>,
+}
+
+impl Snapshot {
+    pub const MAGIC: &'static [u8; 4] = b"NCST";
+    pub const VERSION: u8 = 1;
+
+    /// Encodes the snapshot in the save-state file format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(512);
+        out.extend(Self::MAGIC);
+        out.push(Self::VERSION);
+
+        out.extend(self.registers);
+        out.extend([
+            self.pc,
+            self.sp,
+            self.flags,
+            self.is_halted as u8,
+            self.irq_pending as u8,
+            self.irq_vector,
+        ]);
+        out.extend(self.ram);
+        out.extend(self.cycle.to_le_bytes());
+        out.extend(self.t_states.to_le_bytes());
+
+        write_bytes(
+            &mut out,
+            &self
+                .current_instruction
+                .map_or_else(Vec::new, |entry| entry.to_bytes()),
+        );
+        write_bytes(&mut out, self.output.as_bytes());
+
+        out.extend((self.instruction_log.len() as u32).to_le_bytes());
+        for entry in &self.instruction_log {
+            write_bytes(&mut out, &entry.to_bytes());
+        }
+
+        out.push(self.devices.len() as u8);
+        for device in &self.devices {
+            out.push(device.start);
+            write_bytes(&mut out, device.name.as_bytes());
+            write_bytes(&mut out, &device.state);
+        }
+
+        out
+    }
+
+    /// Decodes a snapshot written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FormatError`] if the magic or version does not match, the
+    /// data is truncated, a string is not valid UTF-8, or a trace entry is
+    /// malformed.
+    pub fn from_bytes(bytes: &[u8]) -> FormatResult<Self> {
+        let mut r = Reader::new(bytes);
+        r.magic(Self::MAGIC)?;
+
+        let version = r.u8()?;
+        if version != Self::VERSION {
+            return Err(FormatError::UnsupportedVersion { version });
+        }
+
+        let registers = r.array()?;
+        let [pc, sp, flags, is_halted, irq_pending, irq_vector] = r.array()?;
+        let ram = r.array()?;
+        let cycle = u64::from_le_bytes(r.array()?);
+        let t_states = u64::from_le_bytes(r.array()?);
+        let current_instruction = match r.blob()? {
+            [] => None,
+            bytes => Some(TraceEntry::from_bytes(bytes)?),
+        };
+        let output = r.string()?;
+
+        let log_len = r.u32()?;
+        let instruction_log = (0..log_len)
+            .map(|_| TraceEntry::from_bytes(r.blob()?))
+            .collect::<FormatResult<_>>()?;
+
+        let device_count = r.u8()?;
+        let mut devices = Vec::with_capacity(device_count as usize);
+        for _ in 0..device_count {
+            devices.push(DeviceState {
+                start: r.u8()?,
+                name: r.string()?,
+                state: r.blob()?.to_vec(),
+            });
+        }
+
+        Ok(Self {
+            registers,
+            pc,
+            sp,
+            flags,
+            is_halted: is_halted != 0,
+            irq_pending: irq_pending != 0,
+            irq_vector,
+            ram,
+            cycle,
+            t_states,
+            current_instruction,
+            output,
+            instruction_log,
+            devices,
+        })
+    }
+
+    /// Writes the snapshot to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::Io`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> FormatResult<()> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Reads a snapshot from `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::Io`] if the file cannot be read, or any error
+    /// from [`from_bytes`](Self::from_bytes).
+    pub fn load(path: impl AsRef<Path>) -> FormatResult<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
@@ -0,0 +1,391 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use crate::{
+    bus::Device,
+    loader::Executable,
+    nanocore::{NanoCore, StopReason},
+};
+
+/// Memory that every core of a [`System`] sees at the same addresses.
+///
+/// All cores map a [`SharedMemory`] over one set of bytes, so a write by one
+/// core is visible to the others on their next instruction.
+#[derive(Debug, Clone, Default)]
+pub struct SharedMemory {
+    pub bytes: Rc<RefCell<[u8; Self::SIZE]>>,
+}
+
+impl SharedMemory {
+    pub const BASE: u8 = 0x50;
+    pub const END: u8 = 0x5F;
+    pub const SIZE: usize = (Self::END - Self::BASE) as usize + 1;
+}
+
+impl Device for SharedMemory {
+    fn name(&self) -> &str {
+        "Shared Memory"
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        self.peek(offset)
+    }
+
+    fn write(&mut self, offset: u8, value: u8) {
+        self.bytes.borrow_mut()[offset as usize] = value;
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        self.bytes.borrow()[offset as usize]
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        self.bytes.borrow().to_vec()
+    }
+
+    fn load_state(&mut self, state: &[u8]) {
+        let mut bytes = self.bytes.borrow_mut();
+        let len = state.len().min(Self::SIZE);
+        bytes[..len].copy_from_slice(&state[..len]);
+    }
+}
+
+/// The inboxes of every core in a [`System`].
+#[derive(Debug, Default)]
+pub struct PostOffice {
+    pub inboxes: Vec<VecDeque<u8>>,
+    /// Cores that received a message since their mailbox last ticked.
+    pub delivered: Vec<bool>,
+}
+
+impl PostOffice {
+    /// Messages an inbox holds before further sends to it are dropped.
+    pub const CAPACITY: usize = 16;
+
+    pub fn new(cores: usize) -> Self {
+        Self {
+            inboxes: vec![VecDeque::new(); cores],
+            delivered: vec![false; cores],
+        }
+    }
+
+    /// Queues `value` for core `to`. Returns `false` if the core does not
+    /// exist or its inbox is full.
+    pub fn send(&mut self, to: u8, value: u8) -> bool {
+        let to = to as usize;
+        match self.inboxes.get_mut(to) {
+            Some(inbox) if inbox.len() < Self::CAPACITY => {
+                inbox.push_back(value);
+                self.delivered[to] = true;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A core's connection to the [`PostOffice`].
+///
+/// | Offset | Read                                   | Write                          |
+/// | -----: | :------------------------------------- | :----------------------------- |
+/// |    `0` | Next message, or `0` if none           | Send to the selected core      |
+/// |    `1` | Number of pending messages             | Select the destination core    |
+/// |    `2` | This core's index                      | ignored                        |
+///
+/// Requests an interrupt on the cycle after a message arrives.
+#[derive(Debug, Clone)]
+pub struct Mailbox {
+    pub core: u8,
+    /// Destination of the next send.
+    pub to: u8,
+    pub post: Rc<RefCell<PostOffice>>,
+}
+
+impl Mailbox {
+    pub const BASE: u8 = 0x4D;
+    pub const END: u8 = 0x4F;
+}
+
+impl Device for Mailbox {
+    fn name(&self) -> &str {
+        "Mailbox"
+    }
+
+    fn read(&mut self, offset: u8) -> u8 {
+        match offset {
+            0 => self.post.borrow_mut().inboxes[self.core as usize]
+                .pop_front()
+                .unwrap_or(0),
+            _ => self.peek(offset),
+        }
+    }
+
+    fn write(&mut self, offset: u8, value: u8) {
+        match offset {
+            0 => {
+                self.post.borrow_mut().send(self.to, value);
+            }
+            1 => self.to = value,
+            _ => {}
+        }
+    }
+
+    fn peek(&self, offset: u8) -> u8 {
+        let post = self.post.borrow();
+        let inbox = &post.inboxes[self.core as usize];
+        match offset {
+            0 => inbox.front().copied().unwrap_or(0),
+            1 => inbox.len() as u8,
+            _ => self.core,
+        }
+    }
+
+    fn tick(&mut self) -> bool {
+        std::mem::take(&mut self.post.borrow_mut().delivered[self.core as usize])
+    }
+}
+
+/// How a [`System`] interleaves its cores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Schedule {
+    /// Every running core executes one instruction per
+    /// [`step`](System::step), in core order.
+    #[default]
+    Lockstep,
+    /// One core executes `quantum` instructions before the next takes over.
+    /// Each [`step`](System::step) executes a single instruction.
+    RoundRobin { quantum: u64 },
+}
+
+/// Why a [`System`] run-control method returned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemStop {
+    /// Every core has halted.
+    Halted,
+    /// The requested number of steps was executed.
+    Stepped,
+    /// A core stopped for a reason other than halting: a breakpoint,
+    /// watchpoint, its cycle budget or an error. The other cores keep their
+    /// state and the system can be resumed.
+    Core { core: usize, reason: StopReason },
+}
+
+/// Several [`NanoCore`]s sharing part of the address space.
+///
+/// Each core keeps its own registers, RAM and devices. On top of those, every
+/// core maps:
+///
+/// | Range | Device |
+/// | :--- | :--- |
+/// | `0x4D`–`0x4F` | [`Mailbox`] |
+/// | `0x50`–`0x5F` | [`SharedMemory`] |
+///
+/// Both sit at the top of program RAM, clear of every per-core device and of
+/// the [`BankWindow`](crate::banking::BankWindow), so a core's program must
+/// end by `0x4D`. Cores only switch between instructions, so `TAS` is atomic.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::system::{SharedMemory, System, SystemStop};
+///
+/// let mut system = System::new(2);
+/// // LDA R0 0x4F (core ID); STORE R0 0x50; HLT
+/// let program = [0x03, 0x00, 0x4F, 0x06, 0x00, 0x50, 0x00];
+/// for core in &mut system.cores {
+///     core.load_program(&program, 0x00).unwrap();
+/// }
+///
+/// assert_eq!(system.run(), SystemStop::Halted);
+/// // Core 1 wrote last.
+/// assert_eq!(system.shared.bytes.borrow()[0], 1);
+/// assert_eq!(system.cores[0].cpu.bus.read(SharedMemory::BASE), 1);
+/// ```
+#[derive(Debug)]
+pub struct System {
+    pub cores: Vec<NanoCore>,
+    pub shared: SharedMemory,
+    pub post: Rc<RefCell<PostOffice>>,
+    pub schedule: Schedule,
+    /// The core that executes next.
+    pub current: usize,
+    /// Instructions the current core has executed in its quantum.
+    slice: u64,
+}
+
+impl System {
+    /// Creates a system of `cores` fresh cores.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cores` is `0` or more than 256.
+    pub fn new(cores: usize) -> Self {
+        Self::from_cores((0..cores).map(|_| NanoCore::new()).collect())
+            .expect("fresh cores have free system ranges")
+    }
+
+    /// Connects existing cores, mapping the shared devices on each.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EmulatorError::BusConflict`](crate::EmulatorError::BusConflict)
+    /// if a core already has a device in one of the system ranges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cores` is empty or has more than 256 cores.
+    pub fn from_cores(mut cores: Vec<NanoCore>) -> crate::EmulatorResult<Self> {
+        assert!(
+            (1..=256).contains(&cores.len()),
+            "a system has 1 to 256 cores"
+        );
+
+        let shared = SharedMemory::default();
+        let post = Rc::new(RefCell::new(PostOffice::new(cores.len())));
+
+        for (id, core) in cores.iter_mut().enumerate() {
+            let bus = &mut core.cpu.bus;
+            bus.map(SharedMemory::BASE, SharedMemory::END, shared.clone())?;
+            bus.map(
+                Mailbox::BASE,
+                Mailbox::END,
+                Mailbox {
+                    core: id as u8,
+                    to: 0,
+                    post: post.clone(),
+                },
+            )?;
+        }
+
+        Ok(Self {
+            cores,
+            shared,
+            post,
+            schedule: Schedule::default(),
+            current: 0,
+            slice: 0,
+        })
+    }
+
+    /// Loads `executable` into core `core`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EmulatorError::ProgramTooLarge`](crate::EmulatorError::ProgramTooLarge)
+    /// if the main program reaches the [`Mailbox`], or any error from
+    /// [`Executable::load`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `core` is out of range.
+    pub fn load(&mut self, core: usize, executable: &Executable) -> crate::EmulatorResult<()> {
+        for segment in executable.segments.iter().filter(|s| s.bank.is_none()) {
+            if segment.addr as usize + segment.bytes.len() > Mailbox::BASE as usize {
+                return Err(crate::EmulatorError::ProgramTooLarge {
+                    size: segment.bytes.len(),
+                    start: segment.addr,
+                    max: Mailbox::BASE as usize,
+                });
+            }
+        }
+        executable.load(&mut self.cores[core])
+    }
+
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Returns `true` once every core has halted.
+    pub fn is_halted(&self) -> bool {
+        self.cores.iter().all(|core| core.cpu.is_halted)
+    }
+
+    /// Advances the system by one step of its [`schedule`](Self::schedule).
+    pub fn step(&mut self) -> SystemStop {
+        let instructions = match self.schedule {
+            Schedule::Lockstep => self.cores.iter().filter(|c| !c.cpu.is_halted).count(),
+            Schedule::RoundRobin { .. } => 1,
+        };
+
+        for _ in 0..instructions {
+            if let Some(stop) = self.step_core() {
+                return stop;
+            }
+        }
+
+        if self.is_halted() {
+            SystemStop::Halted
+        } else {
+            SystemStop::Stepped
+        }
+    }
+
+    /// Executes up to `n` steps.
+    pub fn run_for(&mut self, n: u64) -> SystemStop {
+        for _ in 0..n {
+            match self.step() {
+                SystemStop::Stepped => {}
+                stop => return stop,
+            }
+        }
+
+        SystemStop::Stepped
+    }
+
+    /// Runs until every core halts or one stops.
+    pub fn run(&mut self) -> SystemStop {
+        loop {
+            match self.step() {
+                SystemStop::Stepped => {}
+                stop => return stop,
+            }
+        }
+    }
+
+    /// Executes one instruction on the next running core.
+    fn step_core(&mut self) -> Option<SystemStop> {
+        if self.is_halted() {
+            return Some(SystemStop::Halted);
+        }
+
+        while self.cores[self.current].cpu.is_halted {
+            self.switch();
+        }
+
+        let core = self.current;
+        self.slice += 1;
+        let quantum = match self.schedule {
+            Schedule::Lockstep => 1,
+            Schedule::RoundRobin { quantum } => quantum.max(1),
+        };
+        if self.slice >= quantum {
+            self.switch();
+        }
+
+        match self.cores[core].step() {
+            StopReason::Stepped | StopReason::Halted => None,
+            reason => Some(SystemStop::Core { core, reason }),
+        }
+    }
+
+    fn switch(&mut self) {
+        self.current = (self.current + 1) % self.cores.len();
+        self.slice = 0;
+    }
+}
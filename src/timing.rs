@@ -0,0 +1,115 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use crate::Op;
+
+/// Instruction costs in T-states (clock ticks).
+///
+/// Every instruction costs its entry in [`costs`](Self::costs), a table
+/// indexed by opcode byte so that looking it up on each step is cheap.
+/// Opcodes that are not instructions cost 1. Conditional branches
+/// that are taken cost [`branch_taken_penalty`](Self::branch_taken_penalty)
+/// extra, and servicing an interrupt costs [`interrupt_cost`](Self::interrupt_cost).
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{Op, timing::Timing};
+///
+/// let mut timing = Timing::default();
+/// assert_eq!(timing.cost(Op::NOP), 1);
+/// assert!(timing.cost(Op::MUL) > timing.cost(Op::ADD));
+///
+/// timing.set_cost(Op::MUL, 2);
+/// assert_eq!(timing.cost(Op::MUL), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    /// T-states per opcode byte.
+    pub costs: [u64; 256],
+    pub branch_taken_penalty: u64,
+    pub interrupt_cost: u64,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        let mut costs = [1; 256];
+        for (byte, cost) in costs.iter_mut().enumerate() {
+            if let Ok(op) = Op::try_from(byte as u8) {
+                *cost = Self::default_cost(op);
+            }
+        }
+
+        Self {
+            costs,
+            branch_taken_penalty: 1,
+            interrupt_cost: 4,
+        }
+    }
+}
+
+impl Timing {
+    /// One T-state per instruction and no penalties, matching a plain
+    /// instruction count.
+    pub fn uniform() -> Self {
+        Self {
+            costs: [1; 256],
+            branch_taken_penalty: 0,
+            interrupt_cost: 1,
+        }
+    }
+
+    /// The built-in cost of `op`:
+    ///
+    /// | T-states | Instructions |
+    /// | -------: | :--- |
+    /// | 1 | Register-only ALU ops, `MOV`, `NOP`, `HLT`, `EI`, `DI` |
+    /// | 2 | Immediate ALU ops, `LDI`, `JMPR` |
+    /// | 3 | Memory and stack accesses, absolute and conditional jumps, `IN`, `PRINT` |
+    /// | 4 | Calls, returns and `TAS` |
+    /// | 8 | Multiply |
+    /// | 12 | Divide and modulus |
+    pub fn default_cost(op: Op) -> u64 {
+        if op.is_conditional_branch() {
+            return 3;
+        }
+
+        match op {
+            Op::LDI | Op::ADDI | Op::SUBI | Op::ADCI | Op::SBCI | Op::JMPR => 2,
+            Op::LDA
+            | Op::LDR
+            | Op::STORE
+            | Op::STR
+            | Op::PUSH
+            | Op::POP
+            | Op::JMP
+            | Op::IN
+            | Op::PRINT => 3,
+            Op::CALL | Op::CALLR | Op::RET | Op::RETI | Op::TAS => 4,
+            Op::MUL | Op::MULI => 8,
+            Op::DIV | Op::DIVI | Op::MOD | Op::MODI | Op::IDIV | Op::IMOD => 12,
+            _ => 1,
+        }
+    }
+
+    pub fn cost(&self, op: Op) -> u64 {
+        self.costs[u8::from(op) as usize]
+    }
+
+    pub fn set_cost(&mut self, op: Op, cost: u64) {
+        self.costs[u8::from(op) as usize] = cost;
+    }
+}
@@ -0,0 +1,424 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::fmt;
+
+use crate::{FormatError, FormatResult, Op, codec::Reader, cpu::CPU, nanocore::Operands};
+
+/// A data or stack read made by an instruction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemRead {
+    pub addr: u8,
+    pub value: u8,
+}
+
+/// A data or stack write made by an instruction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemWrite {
+    pub addr: u8,
+    pub old: u8,
+    pub new: u8,
+}
+
+/// The memory accesses of one instruction, stored inline.
+///
+/// No instruction makes more than [`CAPACITY`](Self::CAPACITY) reads or
+/// writes; further accesses are not recorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Accesses<T> {
+    items: [T; 2],
+    len: u8,
+}
+
+impl<T: Copy + Default> Accesses<T> {
+    pub const CAPACITY: usize = 2;
+
+    pub fn push(&mut self, item: T) {
+        if (self.len as usize) < Self::CAPACITY {
+            self.items[self.len as usize] = item;
+            self.len += 1;
+        }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items[..self.len as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.as_slice().first()
+    }
+}
+
+/// One executed instruction, or one interrupt entry.
+///
+/// Entries are recorded by [`NanoCore`](crate::nanocore::NanoCore) into
+/// [`current_instruction`](crate::nanocore::NanoCore::current_instruction)
+/// and [`instruction_log`](crate::nanocore::NanoCore::instruction_log)
+/// without allocating; the text shown by the TUI and `--print-instructions`
+/// is rendered from them on demand.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{Op, nanocore::NanoCore};
+///
+/// let mut nano = NanoCore::new();
+/// nano.load_program(&[0x02, 0x00, 0x05, 0x0D, 0x00], 0x00).unwrap(); // LDI R0 5; INC R0
+/// nano.cycle().unwrap();
+/// nano.cycle().unwrap();
+///
+/// let entry = nano.current_instruction.unwrap();
+/// assert_eq!(entry.op, Some(Op::INC));
+/// assert_eq!(entry.pc, 0x03);
+/// assert_eq!((entry.registers_before[0], entry.registers_after[0]), (5, 6));
+/// assert_eq!(entry.to_string(), "INC   R0      | 0x06");
+/// assert_eq!(nano.instruction_log[0].op, Some(Op::LDI));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// Value of [`NanoCore::cycle`](crate::nanocore::NanoCore::cycle) when
+    /// the instruction ran.
+    pub cycle: u64,
+    /// Address of the instruction, or the interrupted PC for interrupt entry.
+    pub pc: u8,
+    /// The opcode byte as fetched.
+    pub opcode: u8,
+    /// The decoded instruction. `None` for interrupt entry.
+    pub op: Option<Op>,
+    pub operands: Operands,
+    pub reads: Accesses<MemRead>,
+    pub writes: Accesses<MemWrite>,
+    pub registers_before: [u8; 16],
+    pub registers_after: [u8; 16],
+    pub flags_before: u8,
+    pub flags_after: u8,
+    /// The instruction changed the PC. Always `true` for jumps, calls,
+    /// returns and interrupt entry.
+    pub branch_taken: bool,
+    /// The PC after the instruction.
+    pub next_pc: u8,
+}
+
+impl TraceEntry {
+    /// Starts an entry for the instruction at `cpu.pc`.
+    pub fn new(cycle: u64, cpu: &CPU, opcode: u8, op: Option<Op>, operands: Operands) -> Self {
+        Self {
+            cycle,
+            pc: cpu.pc,
+            opcode,
+            op,
+            operands,
+            reads: Accesses::default(),
+            writes: Accesses::default(),
+            registers_before: cpu.registers,
+            registers_after: cpu.registers,
+            flags_before: cpu.flags,
+            flags_after: cpu.flags,
+            branch_taken: false,
+            next_pc: cpu.pc,
+        }
+    }
+
+    pub fn is_interrupt(&self) -> bool {
+        self.op.is_none()
+    }
+
+    /// A conditional branch that was not taken.
+    pub fn skipped(&self) -> bool {
+        self.op.is_some_and(|op| op.is_conditional_branch()) && !self.branch_taken
+    }
+
+    /// The instruction name, or `IRQ` for interrupt entry.
+    pub fn mnemonic(&self) -> &'static str {
+        self.op.map_or("IRQ", <&str>::from)
+    }
+
+    /// The operands as written in assembly.
+    pub fn args(&self) -> String {
+        if self.is_interrupt() {
+            return format!("{:#04X}", self.next_pc);
+        }
+
+        match self.operands {
+            Operands::None => String::new(),
+            Operands::Reg(r) => format!("R{r}"),
+            Operands::RegImm(r, imm) => format!("R{r} {imm:#04X}"),
+            Operands::RegReg(rd, rs) if self.op == Some(Op::STR) => format!("R{rd} [R{rs}]"),
+            Operands::RegReg(rd, rs) => format!("R{rd} R{rs}"),
+            Operands::RegAddr(r, addr) => format!("R{r} {addr:#04X}"),
+            Operands::Addr(addr) => format!("{addr:#04X}"),
+        }
+    }
+
+    /// What the instruction did: values, results and the flags a branch
+    /// tested.
+    pub fn detail(&self) -> String {
+        let Some(op) = self.op else {
+            return format!("from {:#04X}", self.pc);
+        };
+
+        let before = |r: u8| self.registers_before[r as usize];
+        let after = |r: u8| self.registers_after[r as usize];
+        let read = self.reads.first().copied().unwrap_or_default();
+        let write = self.writes.first().copied().unwrap_or_default();
+        let carry_in = (self.flags_before & CPU::FLAG_C != 0) as u8;
+
+        match (op, self.operands) {
+            (Op::LDI, Operands::RegImm(_, value)) => format!("({value:03})"),
+            (Op::LDA, Operands::RegAddr(r, _)) | (Op::MOV, Operands::RegReg(r, _)) => {
+                format!("({:03})", after(r))
+            }
+            (Op::STORE, Operands::RegAddr(r, _)) => format!("({:03})", before(r)),
+            (Op::STR, _) => format!("({:03}) -> [{:#04X}]", write.new, write.addr),
+            (Op::LDR | Op::TAS, _) => format!("{:#04X} ({:03})", read.addr, read.value),
+            (Op::PUSH, _) => format!(
+                "({:03}) (SP: {:#04X})",
+                write.new,
+                write.addr.wrapping_sub(1)
+            ),
+            (Op::POP, _) => format!("({:03}) (SP: {:#04X})", read.value, read.addr),
+            (Op::INC | Op::DEC, Operands::Reg(r)) => format!("{:#04X}", after(r)),
+            (Op::NOT, Operands::Reg(r)) => {
+                let (value, result) = (before(r), after(r));
+                format!(
+                    "! {value:03} ({value:#04X}) ({value:08b}) = {result:03} ({result:#04X}) ({result:08b})"
+                )
+            }
+            (Op::SHL | Op::SHR | Op::ROL | Op::ROR | Op::RCL | Op::RCR, Operands::Reg(r)) => {
+                let (value, result) = (before(r), after(r));
+                let symbol = match op {
+                    Op::SHL => "<<",
+                    Op::SHR => ">>",
+                    _ => op.into(),
+                };
+                format!("{value:03} ({value:08b}) {symbol} 1 = {result:03} ({result:08b})")
+            }
+            (
+                Op::ADD
+                | Op::ADDI
+                | Op::SUB
+                | Op::SUBI
+                | Op::MUL
+                | Op::MULI
+                | Op::DIV
+                | Op::DIVI
+                | Op::MOD
+                | Op::MODI
+                | Op::ADC
+                | Op::ADCI
+                | Op::SBC
+                | Op::SBCI
+                | Op::IDIV
+                | Op::IMOD
+                | Op::AND
+                | Op::OR
+                | Op::XOR
+                | Op::CMP,
+                Operands::RegReg(rd, _) | Operands::RegImm(rd, _),
+            ) => {
+                let v1 = before(rd);
+                let v2 = match self.operands {
+                    Operands::RegReg(_, rs) => before(rs),
+                    Operands::RegImm(_, imm) => imm,
+                    _ => unreachable!(),
+                };
+                let result = match op {
+                    Op::CMP => v1.wrapping_sub(v2),
+                    _ => after(rd),
+                };
+                let symbol = match op {
+                    Op::ADD | Op::ADDI => "+".to_string(),
+                    Op::SUB | Op::SUBI | Op::CMP => "-".to_string(),
+                    Op::MUL | Op::MULI => "*".to_string(),
+                    Op::DIV | Op::DIVI => "/".to_string(),
+                    Op::MOD | Op::MODI => "%".to_string(),
+                    Op::ADC | Op::ADCI => format!("+ {carry_in} +"),
+                    Op::SBC | Op::SBCI => format!("- {carry_in} -"),
+                    Op::IDIV => "/ (signed)".to_string(),
+                    Op::IMOD => "% (signed)".to_string(),
+                    Op::AND => "&".to_string(),
+                    Op::OR => "|".to_string(),
+                    _ => "^".to_string(),
+                };
+                format!(
+                    "{v1:03} ({v1:#04X}) {symbol} {v2:03} ({v2:#04X}) = {result:03} ({result:#04X})"
+                )
+            }
+            (Op::JMPR | Op::CALLR | Op::RET, _) => format!("({:#04X})", self.next_pc),
+            (Op::RETI, _) => format!("{:#04X} Flags({:08b})", self.next_pc, self.flags_after),
+            (Op::PRINT, Operands::Reg(r)) => {
+                let value = before(r);
+                format!("'{}' ({value:03}) ({value:#04X})", value as char)
+            }
+            (Op::IN, Operands::Reg(r)) => {
+                let value = after(r);
+                format!("'{}' ({value:03}) ({value:#04X})", value as char)
+            }
+            _ if op.is_conditional_branch() => self.branch_flags(op),
+            _ => String::new(),
+        }
+    }
+
+    /// The flags a conditional branch depends on, as they were when it ran.
+    fn branch_flags(&self, op: Op) -> String {
+        let flag =
+            |name: &str, bit: u8| format!("{name}({})", (self.flags_before & bit != 0) as u8);
+
+        match op {
+            Op::JZ | Op::JNZ => flag("Z", CPU::FLAG_Z),
+            Op::JC | Op::JNC | Op::JLT | Op::JGE => flag("C", CPU::FLAG_C),
+            Op::JN | Op::JNN => flag("N", CPU::FLAG_N),
+            Op::JV | Op::JNV => flag("V", CPU::FLAG_V),
+            Op::JGT | Op::JLE => format!("{} {}", flag("C", CPU::FLAG_C), flag("Z", CPU::FLAG_Z)),
+            _ => format!(
+                "{} {} {}",
+                flag("N", CPU::FLAG_N),
+                flag("V", CPU::FLAG_V),
+                flag("Z", CPU::FLAG_Z)
+            ),
+        }
+    }
+
+    /// Encodes the entry for a [`Snapshot`](crate::snapshot::Snapshot).
+    pub(crate) fn to_bytes(self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64);
+        out.extend(self.cycle.to_le_bytes());
+        out.extend([
+            self.pc,
+            self.opcode,
+            self.op.is_some() as u8,
+            self.op.map_or(0, u8::from),
+        ]);
+
+        out.extend(match self.operands {
+            Operands::None => [0, 0, 0],
+            Operands::Reg(r) => [1, r, 0],
+            Operands::RegImm(r, imm) => [2, r, imm],
+            Operands::RegReg(rd, rs) => [3, rd, rs],
+            Operands::RegAddr(r, addr) => [4, r, addr],
+            Operands::Addr(addr) => [5, addr, 0],
+        });
+
+        out.push(self.reads.len() as u8);
+        for r in self.reads.items {
+            out.extend([r.addr, r.value]);
+        }
+        out.push(self.writes.len() as u8);
+        for w in self.writes.items {
+            out.extend([w.addr, w.old, w.new]);
+        }
+
+        out.extend(self.registers_before);
+        out.extend(self.registers_after);
+        out.extend([
+            self.flags_before,
+            self.flags_after,
+            self.branch_taken as u8,
+            self.next_pc,
+        ]);
+        out
+    }
+
+    /// Decodes an entry written by [`to_bytes`](Self::to_bytes).
+    pub(crate) fn from_bytes(bytes: &[u8]) -> FormatResult<Self> {
+        let mut r = Reader::new(bytes);
+
+        let cycle = u64::from_le_bytes(r.array()?);
+        let [pc, opcode, has_op, op] = r.array()?;
+        let op = match has_op {
+            0 => None,
+            _ => Some(Op::try_from(op).map_err(|_| {
+                FormatError::InvalidData(format!("invalid opcode {op:#04X} in trace"))
+            })?),
+        };
+
+        let operands = match r.array()? {
+            [0, _, _] => Operands::None,
+            [1, r, _] => Operands::Reg(r),
+            [2, r, imm] => Operands::RegImm(r, imm),
+            [3, rd, rs] => Operands::RegReg(rd, rs),
+            [4, r, addr] => Operands::RegAddr(r, addr),
+            [5, addr, _] => Operands::Addr(addr),
+            [tag, _, _] => {
+                return Err(FormatError::InvalidData(format!(
+                    "invalid operand kind {tag} in trace"
+                )));
+            }
+        };
+
+        let mut reads = Accesses::default();
+        let read_count = r.u8()?;
+        for i in 0..Accesses::<MemRead>::CAPACITY {
+            let [addr, value] = r.array()?;
+            if i < read_count as usize {
+                reads.push(MemRead { addr, value });
+            }
+        }
+
+        let mut writes = Accesses::default();
+        let write_count = r.u8()?;
+        for i in 0..Accesses::<MemWrite>::CAPACITY {
+            let [addr, old, new] = r.array()?;
+            if i < write_count as usize {
+                writes.push(MemWrite { addr, old, new });
+            }
+        }
+
+        let registers_before = r.array()?;
+        let registers_after = r.array()?;
+        let [flags_before, flags_after, branch_taken, next_pc] = r.array()?;
+
+        Ok(Self {
+            cycle,
+            pc,
+            opcode,
+            op,
+            operands,
+            reads,
+            writes,
+            registers_before,
+            registers_after,
+            flags_before,
+            flags_after,
+            branch_taken: branch_taken != 0,
+            next_pc,
+        })
+    }
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<5} {:<8}| {}",
+            self.mnemonic(),
+            self.args(),
+            self.detail()
+        )?;
+        if self.skipped() {
+            write!(f, " (SKIP)")?;
+        }
+        Ok(())
+    }
+}
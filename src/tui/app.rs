@@ -18,10 +18,22 @@ use crossterm::event::KeyCode;
 use ratatui::DefaultTerminal;
 use std::{
     io,
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
 
-use crate::{cpu::CPU, nanocore::NanoCore};
+use crate::{
+    EmulatorResult,
+    cpu::CPU,
+    devices,
+    io::ScriptedIo,
+    journal::Journal,
+    loader::Executable,
+    nanocore::{NanoCore, StopReason},
+    snapshot::Snapshot,
+    system::{System, SystemStop},
+    watch::{WatchHit, Watchpoint},
+};
 
 use super::{events, ui};
 
@@ -30,51 +42,128 @@ pub struct App {
     pub exit: bool,
 
     pub nano_core: NanoCore,
+    /// Set when running one program per core; replaces `nano_core`.
+    pub system: Option<System>,
+    pub core_programs: Vec<Executable>,
+    /// The core shown in the CPU, memory and stack panels.
+    pub focus: usize,
 
     pub filename: String,
-    pub program: Vec<u8>,
+    pub program: Executable,
 
     pub running: bool,
     pub tick_rate: Duration,
     pub last_tick: Instant,
 
-    pub breakpoints: Vec<u8>,
     pub editing_breakpoint: Option<String>,
+    pub editing_watchpoint: Option<String>,
+    /// The watchpoint that stopped the last step, highlighted until the next.
+    pub watch_hit: Option<WatchHit>,
 
     pub mem_view_start: u8,
     pub mem_view_start_editing: Option<String>,
 
     pub stack_view_start: u8,
     pub stack_view_start_editing: Option<String>,
+
+    /// Result of the last save/load, shown in the output panel title.
+    pub status: Option<String>,
 }
 
 impl App {
-    pub fn new(filename: String, program: Vec<u8>) -> Self {
+    pub fn new(filename: String, program: Executable) -> Self {
         Self {
             exit: false,
-            nano_core: NanoCore::new(),
+            nano_core: Self::new_core(),
+            system: None,
+            core_programs: Vec::new(),
+            focus: 0,
             filename,
             program,
             running: false,
             tick_rate: Duration::from_millis(100),
             last_tick: Instant::now(),
-            breakpoints: vec![],
             editing_breakpoint: None,
+            editing_watchpoint: None,
+            watch_hit: None,
             mem_view_start: 0,
             mem_view_start_editing: None,
             stack_view_start: CPU::STACK_MAX,
             stack_view_start_editing: None,
+            status: None,
+        }
+    }
+
+    /// Creates an app that runs each program on its own core of a [`System`].
+    pub fn new_system(filename: String, programs: Vec<Executable>) -> Self {
+        Self {
+            core_programs: programs,
+            ..Self::new(filename, Executable::default())
+        }
+    }
+
+    /// The core shown in the panels.
+    pub fn core(&self) -> &NanoCore {
+        match &self.system {
+            Some(system) => &system.cores[self.focus],
+            None => &self.nano_core,
+        }
+    }
+
+    pub fn core_mut(&mut self) -> &mut NanoCore {
+        match &mut self.system {
+            Some(system) => &mut system.cores[self.focus],
+            None => &mut self.nano_core,
+        }
+    }
+
+    /// Every core: the system's, or just `nano_core`.
+    pub fn cores_mut(&mut self) -> &mut [NanoCore] {
+        match &mut self.system {
+            Some(system) => &mut system.cores,
+            None => std::slice::from_mut(&mut self.nano_core),
+        }
+    }
+
+    /// The program loaded on the focused core.
+    pub fn program(&self) -> &Executable {
+        self.core_programs.get(self.focus).unwrap_or(&self.program)
+    }
+
+    pub fn is_halted(&self) -> bool {
+        match &self.system {
+            Some(system) => system.is_halted(),
+            None => self.nano_core.cpu.is_halted,
         }
     }
 
-    pub fn run(&mut self, terminal: &mut DefaultTerminal, bytes: &[u8]) -> io::Result<()> {
-        self.program = bytes.to_vec();
-        self.nano_core
-            .load_program(&self.program, 0x00)
-            .unwrap_or_else(|e| {
-                eprintln!("Error loading program: {}", e);
-                std::process::exit(1);
-            });
+    /// Moves the focus to the next core.
+    pub fn next_core(&mut self) {
+        if let Some(system) = &self.system {
+            self.focus = (self.focus + 1) % system.cores.len();
+        }
+    }
+
+    /// Creates the emulator used by the TUI.
+    ///
+    /// `IN` reads from a script instead of the real stdin, which would block
+    /// the terminal, the standard devices (including the screen) are mapped,
+    /// and the journal is enabled for stepping back.
+    fn new_core() -> NanoCore {
+        let mut nano_core = NanoCore::new()
+            .with_io(ScriptedIo::default())
+            .with_print()
+            .with_journal(Journal::DEFAULT_DEPTH);
+        devices::map_standard(&mut nano_core.cpu.bus)
+            .expect("standard devices fit on an empty bus");
+        nano_core
+    }
+
+    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        self.load_program().unwrap_or_else(|e| {
+            eprintln!("Error loading program: {}", e);
+            std::process::exit(1);
+        });
 
         while !self.exit {
             terminal.draw(|frame| ui::draw(self, frame))?;
@@ -117,8 +206,47 @@ impl App {
         }
     }
 
+    /// Edits the watchpoint spec. Enter adds the watchpoint, or removes it if
+    /// it already exists; Enter on an empty spec clears all watchpoints.
+    pub fn handle_watch_input(&mut self, key_code: KeyCode) {
+        let Some(input) = &mut self.editing_watchpoint else {
+            return;
+        };
+
+        match key_code {
+            KeyCode::Esc => self.editing_watchpoint = None,
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let spec = std::mem::take(input);
+                self.editing_watchpoint = None;
+                if spec.trim().is_empty() {
+                    self.core_mut().watchpoints.clear();
+                    self.watch_hit = None;
+                    return;
+                }
+
+                match spec.parse::<Watchpoint>() {
+                    Ok(w) => {
+                        let watchpoints = &mut self.core_mut().watchpoints;
+                        if watchpoints.contains(&w) {
+                            watchpoints.retain(|x| *x != w);
+                        } else {
+                            watchpoints.push(w);
+                        }
+                    }
+                    Err(e) => self.status = Some(e),
+                }
+            }
+            _ => {}
+        }
+    }
+
     pub fn none_editing(&self) -> bool {
         self.editing_breakpoint.is_none()
+            && self.editing_watchpoint.is_none()
             && self.mem_view_start_editing.is_none()
             && self.stack_view_start_editing.is_none()
     }
@@ -127,41 +255,140 @@ impl App {
         self.exit = true;
     }
 
+    /// Steps the core, or the whole system, and records why it stopped. A
+    /// core that stops the system takes the focus.
+    fn step(&mut self) -> StopReason {
+        self.watch_hit = None;
+        let reason = match &mut self.system {
+            None => self.nano_core.step(),
+            Some(system) => match system.step() {
+                SystemStop::Halted => StopReason::Halted,
+                SystemStop::Stepped => StopReason::Stepped,
+                SystemStop::Core { core, reason } => {
+                    self.focus = core;
+                    reason
+                }
+            },
+        };
+
+        match &reason {
+            StopReason::Error(e) => {
+                eprintln!("Emulator error: {}", e);
+                self.core_mut().cpu.is_halted = true;
+            }
+            StopReason::Watchpoint(hit) => self.watch_hit = Some(*hit),
+            _ => {}
+        }
+        reason
+    }
+
     pub fn next(&mut self) {
-        if !self.nano_core.cpu.is_halted
-            && let Err(e) = self.nano_core.cycle()
-        {
-            eprintln!("Emulator error: {}", e);
-            self.nano_core.cpu.is_halted = true;
+        self.step();
+    }
+
+    pub fn prev(&mut self) {
+        self.running = false;
+        self.watch_hit = None;
+        if self.system.is_some() {
+            self.status = Some("Step back is not available with multiple cores".into());
+            return;
         }
+        self.nano_core.step_back();
     }
 
     pub fn run_full(&mut self) {
-        if self.nano_core.cpu.is_halted || self.breakpoints.contains(&self.nano_core.cpu.pc) {
+        if self.is_halted() {
             self.running = false;
             return;
         }
 
         if Instant::now().duration_since(self.last_tick) > self.tick_rate {
             self.last_tick = Instant::now();
-            if let Err(e) = self.nano_core.cycle() {
-                eprintln!("Emulator error: {}", e);
-                self.nano_core.cpu.is_halted = true;
+            if self.step() != StopReason::Stepped {
                 self.running = false;
             }
         }
     }
 
     pub fn reset(&mut self) {
-        self.nano_core = NanoCore::new();
-        self.nano_core
-            .load_program(&self.program, 0x00)
-            .unwrap_or_else(|e| {
-                eprintln!("Error reloading program: {}", e);
-            });
+        let debug: Vec<_> = self
+            .cores_mut()
+            .iter_mut()
+            .map(|core| {
+                (
+                    std::mem::take(&mut core.breakpoints),
+                    std::mem::take(&mut core.watchpoints),
+                )
+            })
+            .collect();
+        self.nano_core = Self::new_core();
+        self.watch_hit = None;
+        self.load_program().unwrap_or_else(|e| {
+            eprintln!("Error reloading program: {}", e);
+        });
+        for (core, (breakpoints, watchpoints)) in self.cores_mut().iter_mut().zip(debug) {
+            core.breakpoints = breakpoints;
+            core.watchpoints = watchpoints;
+        }
         self.running = false;
     }
 
+    /// Loads the program, and its banks if it has any, into the emulator. In
+    /// system mode, builds a fresh system with one program per core instead.
+    fn load_program(&mut self) -> EmulatorResult<()> {
+        if !self.core_programs.is_empty() {
+            let cores = self
+                .core_programs
+                .iter()
+                .map(|_| Self::new_core())
+                .collect();
+            let mut system = System::from_cores(cores)?;
+            for (core, program) in self.core_programs.iter().enumerate() {
+                system.load(core, program)?;
+            }
+            self.system = Some(system);
+            return Ok(());
+        }
+
+        self.program.load(&mut self.nano_core)
+    }
+
+    /// Save-state file used by the save and load keys: the program path with
+    /// an `.ncs` extension.
+    pub fn state_path(&self) -> PathBuf {
+        Path::new(&self.filename).with_extension("ncs")
+    }
+
+    pub fn save_state(&mut self) {
+        if self.system.is_some() {
+            self.status = Some("Save states are not available with multiple cores".into());
+            return;
+        }
+
+        let path = self.state_path();
+        self.status = Some(match self.nano_core.snapshot().save(&path) {
+            Ok(()) => format!("Saved {}", path.display()),
+            Err(e) => format!("Save failed: {}", e),
+        });
+    }
+
+    pub fn load_state(&mut self) {
+        if self.system.is_some() {
+            self.status = Some("Save states are not available with multiple cores".into());
+            return;
+        }
+
+        let path = self.state_path();
+        self.status = Some(match Snapshot::load(&path) {
+            Ok(snapshot) => {
+                self.nano_core.restore(&snapshot);
+                self.running = false;
+                format!("Loaded {}", path.display())
+            }
+            Err(e) => format!("Load failed: {}", e),
+        });
+    }
+
     fn parse_addr(s: &str) -> u8 {
         let addr = s.strip_prefix("0x").unwrap_or("0");
 
@@ -26,15 +26,25 @@ pub fn handle_events(app: &mut App) -> io::Result<()> {
         match event::read()? {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 match key_event.code {
+                    // Watchpoint specs contain letters that are also shortcuts.
+                    key_code if app.editing_watchpoint.is_some() => {
+                        app.handle_watch_input(key_code)
+                    }
                     KeyCode::Char('q') => app.exit(),
                     KeyCode::Char(' ') => app.next(),
+                    KeyCode::Left if app.none_editing() => app.prev(),
                     KeyCode::Enter if app.none_editing() => app.running = !app.running,
                     KeyCode::Up => app.tick_rate.add_assign(Duration::from_millis(50)),
                     KeyCode::Down => {
                         app.tick_rate = app.tick_rate.saturating_sub(Duration::from_millis(50))
                     }
                     KeyCode::Char('r') => app.reset(),
+                    KeyCode::Char('w') if app.none_editing() => app.save_state(),
+                    KeyCode::Char('l') if app.none_editing() => app.load_state(),
                     KeyCode::Char('b') => app.editing_breakpoint = Some("0x".into()),
+                    KeyCode::Char('a') if app.none_editing() => {
+                        app.editing_watchpoint = Some(String::new())
+                    }
                     KeyCode::Char('m') => {
                         app.mem_view_start_editing = Some(format!("{:#04X}", app.mem_view_start))
                     }
@@ -42,19 +52,23 @@ pub fn handle_events(app: &mut App) -> io::Result<()> {
                         app.stack_view_start_editing =
                             Some(format!("{:#04X}", app.stack_view_start))
                     }
+                    KeyCode::Tab if app.none_editing() => app.next_core(),
                     key_code => {
+                        let mut editing_breakpoint = app.editing_breakpoint.take();
                         App::handle_edit_input(
                             key_code,
-                            &mut app.editing_breakpoint,
+                            &mut editing_breakpoint,
                             |addr| {
-                                if app.breakpoints.contains(&addr) {
-                                    app.breakpoints.retain(|x| *x != addr);
+                                let breakpoints = &mut app.core_mut().breakpoints;
+                                if breakpoints.contains(&addr) {
+                                    breakpoints.retain(|x| *x != addr);
                                 } else {
-                                    app.breakpoints.push(addr);
+                                    breakpoints.push(addr);
                                 }
                             },
                             0,
                         );
+                        app.editing_breakpoint = editing_breakpoint;
                         App::handle_edit_input(
                             key_code,
                             &mut app.mem_view_start_editing,
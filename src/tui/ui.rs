@@ -14,7 +14,7 @@
 //! language programming.
 //!
 
-use crate::{Op, cpu::CPU};
+use crate::{Op, cpu::CPU, devices::Screen, system::System, trace::TraceEntry, watch::Watchpoint};
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
@@ -30,6 +30,8 @@ pub fn draw(app: &App, frame: &mut Frame) {
     let instructions = Line::from(vec![
         " Next Instruction ".into(),
         "<Space>".light_blue().bold(),
+        " | Step Back ".into(),
+        "<⬅>".light_blue().bold(),
         if app.running {
             " | Stop ".into()
         } else {
@@ -39,12 +41,21 @@ pub fn draw(app: &App, frame: &mut Frame) {
         " | ".into(),
         "●".red(),
         " Breakpoint ".into(),
-        (if app.breakpoints.is_empty() {
+        (if app.core().breakpoints.is_empty() {
             "<B>".light_blue()
         } else {
             "<B>".light_green()
         })
         .bold(),
+        " | ".into(),
+        "◆".yellow(),
+        " Watchpoint ".into(),
+        (if app.core().watchpoints.is_empty() {
+            "<A>".light_blue()
+        } else {
+            "<A>".light_green()
+        })
+        .bold(),
         " | Memory View ".into(),
         (if app.mem_view_start == 0 {
             "<M>".light_blue()
@@ -63,8 +74,27 @@ pub fn draw(app: &App, frame: &mut Frame) {
         "<⬆>".light_blue().bold(),
         " +50ms) (".into(),
         "<⬇>".light_blue().bold(),
-        " -50ms) | Reset ".into(),
+        " -50ms) | ".into(),
+        if app.system.is_some() {
+            "Core ".into()
+        } else {
+            "".into()
+        },
+        if app.system.is_some() {
+            "<Tab>".light_blue().bold()
+        } else {
+            "".into()
+        },
+        if app.system.is_some() {
+            " | Reset ".into()
+        } else {
+            "Reset ".into()
+        },
         "<R>".light_blue().bold(),
+        " | Save State ".into(),
+        "<W>".light_blue().bold(),
+        " | Load State ".into(),
+        "<L>".light_blue().bold(),
         " | Quit ".into(),
         "<Q> ".light_blue().bold(),
     ]);
@@ -97,16 +127,24 @@ pub fn draw(app: &App, frame: &mut Frame) {
     let inner =
         Layout::horizontal([Constraint::Percentage(75), Constraint::Fill(1)]).split(main[1]);
 
+    let cpu_title = match &app.system {
+        Some(system) => format!(" CPU (core {} of {}) ", app.focus, system.cores.len()),
+        None => " CPU ".to_owned(),
+    };
     let cpu_block = Block::default()
         .borders(Borders::TOP)
-        .title(Line::from(" CPU ").centered().bold());
+        .title(Line::from(cpu_title).centered().bold());
 
     let cpu_block_inner = cpu_block.inner(inner[0]);
     frame.render_widget(cpu_block, inner[0]);
 
     let cpu = Layout::vertical([
         Constraint::Percentage(5),
-        Constraint::Percentage(10),
+        match &app.system {
+            // A header and one line per core.
+            Some(system) => Constraint::Length(system.cores.len() as u16 + 3),
+            None => Constraint::Percentage(10),
+        },
         Constraint::Percentage(7),
         Constraint::Percentage(30),
         Constraint::Fill(1),
@@ -123,9 +161,11 @@ pub fn draw(app: &App, frame: &mut Frame) {
     ])
     .split(cpu[0]);
 
-    let state_line = if app.nano_core.cpu.is_halted {
+    let state_line = if app.core().cpu.is_halted {
         Line::from(" HLT ".white().on_red())
-    } else if app.breakpoints.contains(&app.nano_core.cpu.pc) {
+    } else if app.watch_hit.is_some() {
+        Line::from(" WCH ".black().on_yellow())
+    } else if app.core().breakpoints.contains(&app.core().cpu.pc) {
         Line::from(" BRK ".white().on_red())
     } else {
         Line::from(" RUN ".green().on_black())
@@ -140,8 +180,9 @@ pub fn draw(app: &App, frame: &mut Frame) {
     frame.render_widget(
         Paragraph::new(
             Line::from(format!(
-                " {:#04X} ({:03}) ",
-                app.nano_core.cycle, app.nano_core.cycle
+                " {:03} ({} T) ",
+                app.core().cycle,
+                app.core().t_states
             ))
             .centered(),
         )
@@ -152,7 +193,8 @@ pub fn draw(app: &App, frame: &mut Frame) {
         Paragraph::new(
             Line::from(format!(
                 " {:#04X} ({:03}) ",
-                app.nano_core.cpu.pc, app.nano_core.cpu.pc
+                app.core().cpu.pc,
+                app.core().cpu.pc
             ))
             .centered(),
         )
@@ -163,7 +205,8 @@ pub fn draw(app: &App, frame: &mut Frame) {
         Paragraph::new(
             Line::from(format!(
                 " {:#04X} ({:03}) ",
-                app.nano_core.cpu.sp, app.nano_core.cpu.sp
+                app.core().cpu.sp,
+                app.core().cpu.sp
             ))
             .centered(),
         )
@@ -171,15 +214,17 @@ pub fn draw(app: &App, frame: &mut Frame) {
         cpu_top[3],
     );
 
-    let flag_z = app.nano_core.cpu.get_flag(CPU::FLAG_Z);
-    let flag_c = app.nano_core.cpu.get_flag(CPU::FLAG_C);
-    let flag_n = app.nano_core.cpu.get_flag(CPU::FLAG_N);
-    let flag_y = app.nano_core.cpu.get_flag(CPU::FLAG_Y);
+    let flag_z = app.core().cpu.get_flag(CPU::FLAG_Z);
+    let flag_c = app.core().cpu.get_flag(CPU::FLAG_C);
+    let flag_n = app.core().cpu.get_flag(CPU::FLAG_N);
+    let flag_v = app.core().cpu.get_flag(CPU::FLAG_V);
+    let flag_i = app.core().cpu.get_flag(CPU::FLAG_I);
 
     let mut flag_line_z = Span::raw(format!("Z({:01}) ", flag_z as u8));
     let mut flag_line_c = Span::raw(format!("C({:01}) ", flag_c as u8));
     let mut flag_line_n = Span::raw(format!("N({:01}) ", flag_n as u8));
-    let mut flag_line_y = Span::raw(format!("Y({:01})", flag_y as u8));
+    let mut flag_line_v = Span::raw(format!("V({:01}) ", flag_v as u8));
+    let mut flag_line_i = Span::raw(format!("I({:01})", flag_i as u8));
 
     if !flag_z {
         flag_line_z = flag_line_z.dark_gray();
@@ -193,14 +238,37 @@ pub fn draw(app: &App, frame: &mut Frame) {
         flag_line_n = flag_line_n.dark_gray();
     }
 
-    if !flag_y {
-        flag_line_y = flag_line_y.dark_gray();
+    if !flag_v {
+        flag_line_v = flag_line_v.dark_gray();
+    }
+
+    if !flag_i {
+        flag_line_i = flag_line_i.dark_gray();
     }
 
-    let mut flag_line =
-        Line::from(vec![flag_line_z, flag_line_c, flag_line_n, flag_line_y]).centered();
+    let watch_hit = app.watch_hit.map(|hit| hit.watchpoint);
+    for (flag, span) in [
+        (CPU::FLAG_Z, &mut flag_line_z),
+        (CPU::FLAG_C, &mut flag_line_c),
+        (CPU::FLAG_N, &mut flag_line_n),
+        (CPU::FLAG_V, &mut flag_line_v),
+        (CPU::FLAG_I, &mut flag_line_i),
+    ] {
+        if watch_hit == Some(Watchpoint::Flag(flag)) {
+            *span = span.clone().black().on_yellow();
+        }
+    }
 
-    if app.nano_core.cpu.flags == 0 {
+    let mut flag_line = Line::from(vec![
+        flag_line_z,
+        flag_line_c,
+        flag_line_n,
+        flag_line_v,
+        flag_line_i,
+    ])
+    .centered();
+
+    if app.core().cpu.flags == 0 {
         flag_line = flag_line.dark_gray();
     }
 
@@ -218,8 +286,8 @@ pub fn draw(app: &App, frame: &mut Frame) {
                     .unwrap()
                     .to_str()
                     .unwrap(),
-                app.program.len(),
-                app.program.len()
+                app.program().size(),
+                app.program().size()
             ))
             .centered(),
         )
@@ -229,56 +297,77 @@ pub fn draw(app: &App, frame: &mut Frame) {
 
     // -- Registers
 
-    let register_block = Block::bordered()
-        .padding(Padding::left(1))
-        .title(Line::from(" Registers ").centered());
-    let register_block_inner = register_block.inner(cpu[1]);
-
-    let registers = Layout::horizontal([Constraint::Fill(1); 16])
-        .spacing(1)
-        .split(register_block_inner);
-
-    frame.render_widget(register_block, cpu[1]);
-
-    for i in 0..app.nano_core.cpu.registers.len() {
-        let mut dec_line = Line::from(format!("{:04}", app.nano_core.cpu.registers[i]));
-        let mut hex_line = Line::from(format!("{:#04X}", app.nano_core.cpu.registers[i]));
-
-        if app.nano_core.cpu.registers[i] == 0 {
-            dec_line = dec_line.dark_gray();
-            hex_line = hex_line.dark_gray();
-        }
-
-        let mut reg_block = Block::default();
-
-        if i < 15 {
-            reg_block = reg_block.borders(Borders::RIGHT)
-        }
-
+    if let Some(system) = &app.system {
         frame.render_widget(
-            Paragraph::new(Text::from(vec![dec_line, hex_line]))
-                .block(reg_block.title(Line::from(format!("R{i}")))),
-            registers[i],
+            Paragraph::new(get_core_lines(app, system))
+                .block(Block::bordered().title(Line::from(" Cores ").centered())),
+            cpu[1],
         );
+    } else {
+        let register_block = Block::bordered()
+            .padding(Padding::left(1))
+            .title(Line::from(" Registers ").centered());
+        let register_block_inner = register_block.inner(cpu[1]);
+
+        let registers = Layout::horizontal([Constraint::Fill(1); 16])
+            .spacing(1)
+            .split(register_block_inner);
+
+        frame.render_widget(register_block, cpu[1]);
+
+        for i in 0..app.core().cpu.registers.len() {
+            let mut dec_line = Line::from(format!("{:04}", app.core().cpu.registers[i]));
+            let mut hex_line = Line::from(format!("{:#04X}", app.core().cpu.registers[i]));
+
+            if app.core().cpu.registers[i] == 0 {
+                dec_line = dec_line.dark_gray();
+                hex_line = hex_line.dark_gray();
+            }
+
+            let mut reg_block = Block::default();
+
+            if i < 15 {
+                reg_block = reg_block.borders(Borders::RIGHT)
+            }
+
+            let mut reg_title = Line::from(format!("R{i}"));
+            if let Some(Watchpoint::Register(r) | Watchpoint::RegisterValue(r, _)) = watch_hit
+                && r as usize == i
+            {
+                reg_title = reg_title.black().on_yellow();
+                dec_line = dec_line.yellow();
+                hex_line = hex_line.yellow();
+            }
+
+            frame.render_widget(
+                Paragraph::new(Text::from(vec![dec_line, hex_line]))
+                    .block(reg_block.title(reg_title)),
+                registers[i],
+            );
+        }
     }
 
     // -- Current instruction
 
-    let (op, args, rest) = get_instruction_parts(&app.nano_core.current_instruction);
+    let current = app.core().current_instruction;
+    let (op, args, rest) =
+        current.map_or_else(Default::default, |e| (e.mnemonic(), e.args(), e.detail()));
+    let op_bin = current
+        .filter(|e| !e.is_interrupt())
+        .map_or_else(String::new, |e| {
+            format!("{0:08b} │ {0:#04X} │ {0:03}", e.opcode)
+        });
 
     let mut op_span = Span::raw(format!("{op:5}")).cyan();
-    let mut op_bin_span = Span::raw(&app.nano_core.current_instruction_bin).light_cyan();
+    let mut op_bin_span = Span::raw(op_bin).light_cyan();
 
-    if !op.is_empty() {
-        if matches!(
-            Op::try_from(op.as_str()).unwrap_or(Op::NOP),
-            Op::JMP | Op::JZ | Op::JNZ | Op::CALL | Op::RET
-        ) {
+    if let Some(op) = current.and_then(|e| e.op) {
+        if op.is_control_flow() {
             op_span = op_span.magenta();
             op_bin_span = op_bin_span.magenta();
         }
 
-        if Op::try_from(op.as_str()).unwrap_or(Op::NOP) == Op::HLT {
+        if op == Op::HLT {
             op_span = op_span.red();
             op_bin_span = op_bin_span.red();
         }
@@ -293,11 +382,11 @@ pub fn draw(app: &App, frame: &mut Frame) {
     frame.render_widget(ci_block, cpu[2]);
     frame.render_widget(
         Paragraph::new(Line::from(vec![
-            format!("{:03}", app.nano_core.instruction_log.len()).dim(),
+            format!("{:03}", app.core().instruction_log.len()).dim(),
             Span::raw(" "),
             op_span,
-            Span::raw(format!(" {:<8}", args.trim())).green(),
-            Span::raw(format!(" │{rest}")).dim(),
+            Span::raw(format!(" {args:<8}")).green(),
+            Span::raw(format!(" │ {rest}")).dim(),
         ])),
         ci_columns[0],
     );
@@ -305,7 +394,7 @@ pub fn draw(app: &App, frame: &mut Frame) {
         Paragraph::new(Line::from(vec![
             " ".white(),
             op_bin_span,
-            if app.nano_core.current_skipped {
+            if current.is_some_and(|e| e.skipped()) {
                 " │ (SKIP)".red()
             } else {
                 "".red()
@@ -337,25 +426,58 @@ pub fn draw(app: &App, frame: &mut Frame) {
     let bottom_block_inner = bottom_block.inner(cpu[4]);
 
     let bottom_columns = Layout::horizontal([
-        Constraint::Percentage(50),
+        Constraint::Percentage(35),
+        Constraint::Percentage(15),
         Constraint::Percentage(20),
         Constraint::Fill(1),
     ])
     .split(bottom_block_inner);
 
+    let output_title = match &app.status {
+        Some(status) => format!(" Output ({status}) "),
+        None => " Output ".to_owned(),
+    };
     let output =
-        Paragraph::new(app.nano_core.output.clone()).block(Block::bordered().title(" Output "));
+        Paragraph::new(app.core().output.clone()).block(Block::bordered().title(output_title));
 
     frame.render_widget(output, bottom_columns[0]);
 
+    // -- Watchpoints
+
+    let mut watch_lines: Vec<Line> = app
+        .nano_core
+        .watchpoints
+        .iter()
+        .map(|w| {
+            let line = Line::from(format!(" ◆ {w}"));
+            if watch_hit == Some(*w) {
+                line.black().on_yellow()
+            } else {
+                line.yellow()
+            }
+        })
+        .collect();
+
+    if let Some(hit) = app.watch_hit {
+        watch_lines.push("".into());
+        watch_lines.push(Line::from(format!(" at {:#04X}", hit.pc)).white());
+        watch_lines.push(Line::from(format!(" {:#04X} → {:#04X}", hit.old, hit.new)).white());
+    }
+
+    frame.render_widget(
+        Paragraph::new(watch_lines).block(Block::bordered().title(" Watchpoints ")),
+        bottom_columns[1],
+    );
+
     // -- Screen
 
+    let screen = app.core().cpu.bus.device::<Screen>();
+
     let mut screen_lines = Vec::new();
-    for y in 0..8 {
+    for y in 0..Screen::HEIGHT {
         let mut line_spans = Vec::new();
-        for x in 0..8 {
-            let addr = 0xAA + y * 8 + x;
-            let char_code = app.nano_core.cpu.memory[addr];
+        for x in 0..Screen::WIDTH {
+            let char_code = screen.map_or(0, |s| s.char_at(x, y));
             let char = if (32..=126).contains(&char_code) {
                 char_code as char
             } else {
@@ -367,9 +489,17 @@ pub fn draw(app: &App, frame: &mut Frame) {
     }
 
     frame.render_widget(
-        Paragraph::new(screen_lines)
-            .block(Block::bordered().title(Line::from(" Screen (0xAA-0xE9) ").centered())),
-        bottom_columns[1],
+        Paragraph::new(screen_lines).block(
+            Block::bordered().title(
+                Line::from(format!(
+                    " Screen ({:#04X}-{:#04X}) ",
+                    Screen::BASE,
+                    Screen::END
+                ))
+                .centered(),
+            ),
+        ),
+        bottom_columns[2],
     );
 
     // -- Stack
@@ -377,8 +507,8 @@ pub fn draw(app: &App, frame: &mut Frame) {
     let stack_block = Block::default()
         .borders(Borders::TOP)
         .title(Line::from(" Stack ").centered());
-    let stack_block_inner = stack_block.inner(bottom_columns[2]);
-    frame.render_widget(stack_block, bottom_columns[2]);
+    let stack_block_inner = stack_block.inner(bottom_columns[3]);
+    frame.render_widget(stack_block, bottom_columns[3]);
 
     let stack = Layout::horizontal([Constraint::Percentage(35), Constraint::Fill(1)])
         .split(stack_block_inner);
@@ -397,16 +527,14 @@ pub fn draw(app: &App, frame: &mut Frame) {
     }
 
     for i in ((sv_start - 32)..=sv_start).rev() {
-        let mut mem_line = Line::from(format!(
-            " {:08b}  {:#04X}  {:03}  ",
-            app.nano_core.cpu.memory[i], app.nano_core.cpu.memory[i], app.nano_core.cpu.memory[i],
-        ));
+        let byte = app.core().cpu.bus.peek(i as u8);
+        let mut mem_line = Line::from(format!(" {byte:08b}  {byte:#04X}  {byte:03}  "));
 
-        if app.nano_core.cpu.memory[i] == 0 {
+        if byte == 0 {
             mem_line = mem_line.dim();
         }
 
-        if i as u8 == app.nano_core.cpu.sp {
+        if i as u8 == app.core().cpu.sp {
             stack_addr_vec.push(Line::from(vec![
                 "► ".cyan(),
                 format!("{i:#04X} {i:03} ").white(),
@@ -451,9 +579,10 @@ pub fn draw(app: &App, frame: &mut Frame) {
     }
 
     let mut skip_bytes = 0;
-    for i in (app.mem_view_start as usize)..(app.nano_core.cpu.memory.len()) {
+    for i in (app.mem_view_start as usize)..=(u8::MAX as usize) {
+        let byte = app.core().cpu.bus.peek(i as u8);
         let op: Op = if skip_bytes == 0 {
-            let op: Op = app.nano_core.cpu.memory[i].into();
+            let op = Op::decode_or_nop(byte);
 
             skip_bytes = op.instruction_len() - 1;
 
@@ -472,34 +601,32 @@ pub fn draw(app: &App, frame: &mut Frame) {
 
         let mut op_span = Span::raw(format!("{op_str:5}"));
         op_span = match op {
-            _ if i as u8 == app.nano_core.cpu.pc => op_span.white(),
+            _ if i as u8 == app.core().cpu.pc => op_span.white(),
             Op::NOP => op_span.dim(),
             Op::HLT => op_span.red().dim(),
-            Op::JMP | Op::JZ | Op::JNZ | Op::CALL | Op::RET => op_span.magenta(),
+            op if op.is_control_flow() => op_span.magenta(),
             _ => op_span.cyan(),
         };
 
         let mut mem_line = Line::from(vec![
-            Span::raw(format!(
-                " {:08b} {:#04X} {:03} ",
-                app.nano_core.cpu.memory[i],
-                app.nano_core.cpu.memory[i],
-                app.nano_core.cpu.memory[i],
-            )),
+            Span::raw(format!(" {byte:08b} {byte:#04X} {byte:03} ")),
             op_span,
         ]);
 
-        if app.nano_core.cpu.memory[i] == 0 {
+        if byte == 0 {
             mem_line = mem_line.dark_gray();
         }
 
-        if i as u8 == app.nano_core.cpu.pc {
+        if i as u8 == app.core().cpu.pc {
             addr_vec.push(Line::from(vec![
                 "► ".magenta(),
                 format!("{i:#04X} {i:03} ").white(),
             ]));
             mem_line = mem_line.white().on_magenta();
-        } else if app.breakpoints.contains(&(i as u8)) {
+        } else if watch_hit.and_then(|w| w.addr()) == Some(i as u8) {
+            addr_vec.push(Line::from(format!("◆ {i:#04X} {i:03} ").yellow()));
+            mem_line = mem_line.black().on_yellow();
+        } else if app.core().breakpoints.contains(&(i as u8)) {
             addr_vec.push(Line::from(format!("● {i:#04X} {i:03} ").red()));
             mem_line = mem_line.white().on_red();
         } else {
@@ -535,7 +662,7 @@ pub fn draw(app: &App, frame: &mut Frame) {
 
         let mut bp_y = 8;
 
-        if !app.breakpoints.is_empty() {
+        if !app.core().breakpoints.is_empty() {
             bp_modal_lines.push(Line::from(vec![
                 "<K>   ".bold(),
                 "Clear all breakpoints".into(),
@@ -555,6 +682,45 @@ pub fn draw(app: &App, frame: &mut Frame) {
         );
     }
 
+    if let Some(watchpoint) = &app.editing_watchpoint {
+        let mut wp_modal_lines = vec![
+            Line::from(vec![
+                "Spec: ".into(),
+                format!(" {:20} ", watchpoint.as_str())
+                    .black()
+                    .on_white()
+                    .bold(),
+                " ↵".bold(),
+            ]),
+            "".into(),
+            "read:ADDR  write:ADDR  access:ADDR".dark_gray().into(),
+            "R3  R3=0x10  flag:Z".dark_gray().into(),
+            "".into(),
+            Line::from(vec!["<Esc> ".bold(), "Close".into()]),
+        ];
+
+        let mut wp_y = 11;
+
+        if !app.core().watchpoints.is_empty() {
+            wp_modal_lines.push(Line::from(vec![
+                "<↵>   ".bold(),
+                "Clear all (empty spec)".into(),
+            ]));
+
+            wp_y = 12;
+        }
+
+        frame.render_widget(
+            Paragraph::new(Text::from(wp_modal_lines)).block(
+                Block::bordered()
+                    .title(Line::from(" Add / Remove Watchpoint "))
+                    .black()
+                    .on_yellow(),
+            ),
+            centered_rect(25, wp_y, frame.area()),
+        );
+    }
+
     if let Some(mem_view_start) = &app.mem_view_start_editing {
         let mut mv_modal_lines = vec![
             Line::from(vec![
@@ -638,9 +804,67 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     .split(popup_layout[1])[1]
 }
 
+fn get_core_lines<'a>(app: &App, system: &System) -> Text<'a> {
+    let mut lines = vec![
+        Line::from(format!(
+            "   Core State PC   SP   Flags │{}",
+            (0..16)
+                .map(|i| format!(" {:>4}", format!("R{i}")))
+                .collect::<String>()
+        ))
+        .light_blue(),
+    ];
+
+    for (i, core) in system.cores.iter().enumerate() {
+        let focused = i == app.focus;
+        let state = if core.cpu.is_halted {
+            " HLT ".white().on_red()
+        } else if focused && app.watch_hit.is_some() {
+            " WCH ".black().on_yellow()
+        } else {
+            " RUN ".green()
+        };
+
+        let mut spans = vec![
+            if focused {
+                " ► ".magenta()
+            } else {
+                "   ".into()
+            },
+            format!("{i:>4} ").into(),
+            state,
+            format!(
+                " {:#04X} {:#04X} {} │",
+                core.cpu.pc,
+                core.cpu.sp,
+                core.cpu.flag_names()
+            )
+            .into(),
+        ];
+
+        let watched = match app.watch_hit.map(|hit| hit.watchpoint) {
+            Some(Watchpoint::Register(r) | Watchpoint::RegisterValue(r, _)) if focused => Some(r),
+            _ => None,
+        };
+        for (r, &value) in core.cpu.registers.iter().enumerate() {
+            let span = Span::raw(format!(" {value:#04X}"));
+            spans.push(match value {
+                _ if watched == Some(r as u8) => span.black().on_yellow(),
+                0 => span.dark_gray(),
+                _ => span,
+            });
+        }
+
+        let line = Line::from(spans);
+        lines.push(if focused { line.bold() } else { line });
+    }
+
+    Text::from(lines)
+}
+
 fn get_instruction_list(app: &App, skip: usize, take: usize) -> List<'_> {
     List::new(
-        app.nano_core
+        app.core()
             .instruction_log
             .iter()
             .enumerate()
@@ -652,28 +876,25 @@ fn get_instruction_list(app: &App, skip: usize, take: usize) -> List<'_> {
     )
 }
 
-fn get_instruction_line(i: usize, l: &str) -> Line<'static> {
-    let (op, args, rest) = get_instruction_parts(l);
+fn get_instruction_line(i: usize, entry: &TraceEntry) -> Line<'static> {
+    let mut op_span = Span::raw(format!("{:5}", entry.mnemonic())).cyan();
+    let mut args_span = Span::raw(format!(" {:<8}", entry.args()));
+    let mut rest_span = Span::raw(format!(" │ {}", entry.detail())).dim();
 
-    let mut op_span = Span::raw(format!("{op:5}")).cyan();
-    let mut args_span = Span::raw(format!(" {:<8}", args.trim()));
-    let mut rest_span = Span::raw(format!(" │{}", rest.clone())).dim();
-
-    if matches!(
-        Op::try_from(op.as_str()).unwrap_or(Op::NOP),
-        Op::JMP | Op::JZ | Op::JNZ | Op::CALL | Op::RET
-    ) {
-        op_span = op_span.magenta();
-    }
+    if let Some(op) = entry.op {
+        if op.is_control_flow() {
+            op_span = op_span.magenta();
+        }
 
-    if Op::try_from(op.as_str()).unwrap_or(Op::NOP) == Op::HLT {
-        op_span = op_span.red();
+        if op == Op::HLT {
+            op_span = op_span.red();
+        }
     }
 
-    if rest.contains("(SKIP)") {
+    if entry.skipped() {
         op_span = op_span.dim();
         args_span = args_span.dim();
-        rest_span = rest_span.yellow();
+        rest_span = Span::raw(format!(" │ {} (SKIP)", entry.detail())).yellow();
     }
 
     Line::from(vec![
@@ -684,20 +905,3 @@ fn get_instruction_line(i: usize, l: &str) -> Line<'static> {
         rest_span,
     ])
 }
-
-fn get_instruction_parts(l: &str) -> (String, String, String) {
-    let op = l.split_whitespace().next().unwrap_or("").to_owned();
-    let args = l
-        .strip_prefix(&op)
-        .unwrap()
-        .split('|')
-        .next()
-        .unwrap_or("")
-        .to_owned();
-    let rest = l
-        .strip_prefix(&format!("{op}{args}"))
-        .unwrap()
-        .replace('|', "");
-
-    (op, args, rest)
-}
@@ -0,0 +1,214 @@
+//! # `NanoCore`
+//!
+//! (c) 2025 Afaan Bilal <https://afaan.dev>
+//!
+//! `NanoCore` is a meticulously crafted emulator for a custom, true 8-bit CPU.
+//!
+//! Designed with extreme minimalism in mind, this CPU operates within a strict
+//! 256-byte memory space, with all registers, the Program Counter (PC), and
+//! the Stack Pointer (SP) being 8-bit.
+//!
+//! This project serves as an educational exercise in understanding the
+//! fundamental principles of computer architecture, low-level instruction
+//! set design, memory management under severe constraints, and assembly
+//! language programming.
+//!
+
+use std::{fmt, str::FromStr};
+
+use crate::{cpu::CPU, trace::TraceEntry};
+
+/// A data condition that stops execution after the instruction that meets it.
+///
+/// Watchpoints are written as:
+///
+/// | Spec | Triggers when |
+/// | :--- | :--- |
+/// | `read:0x80` | the address is read |
+/// | `write:0x80` | the address is written |
+/// | `access:0x80` | the address is read or written |
+/// | `R3` | R3 changes |
+/// | `R3=0x10` | R3 changes to `0x10` |
+/// | `flag:Z` | the flag toggles (`Z`, `C`, `N`, `V` or `I`) |
+///
+/// Stack pushes and pops count as writes and reads; instruction fetches do
+/// not.
+///
+/// # Examples
+///
+/// ```
+/// use nanocore::{
+///     nanocore::{NanoCore, StopReason},
+///     watch::Watchpoint,
+/// };
+///
+/// let mut nano = NanoCore::new();
+/// // LDI R0 7; STORE R0 0x80; HLT
+/// nano.load_program(&[0x02, 0x00, 0x07, 0x06, 0x00, 0x80, 0x00], 0x00).unwrap();
+/// nano.watchpoints.push("write:0x80".parse().unwrap());
+///
+/// let StopReason::Watchpoint(hit) = nano.run_until(|_| false) else {
+///     panic!("expected a watchpoint");
+/// };
+/// assert_eq!(hit.watchpoint, Watchpoint::Write(0x80));
+/// assert_eq!((hit.pc, hit.old, hit.new), (0x03, 0, 7));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Watchpoint {
+    Read(u8),
+    Write(u8),
+    Access(u8),
+    /// The register changes.
+    Register(u8),
+    /// The register changes to the value.
+    RegisterValue(u8, u8),
+    /// The flag bit (one of the `CPU::FLAG_*` constants) toggles.
+    Flag(u8),
+}
+
+/// A triggered [`Watchpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchHit {
+    pub watchpoint: Watchpoint,
+    /// Address of the instruction that triggered it.
+    pub pc: u8,
+    /// The value before the instruction: the byte for memory accesses (the
+    /// value read, for reads), the register, or the flag as `0`/`1`.
+    pub old: u8,
+    /// The value after the instruction, in the same terms as `old`.
+    pub new: u8,
+}
+
+impl Watchpoint {
+    /// Checks the instruction recorded in `entry`.
+    pub fn check(&self, entry: &TraceEntry) -> Option<WatchHit> {
+        let hit = |old, new| WatchHit {
+            watchpoint: *self,
+            pc: entry.pc,
+            old,
+            new,
+        };
+        let read = |addr| {
+            entry
+                .reads
+                .as_slice()
+                .iter()
+                .find(|r| r.addr == addr)
+                .map(|r| hit(r.value, r.value))
+        };
+        let write = |addr| {
+            entry
+                .writes
+                .as_slice()
+                .iter()
+                .find(|w| w.addr == addr)
+                .map(|w| hit(w.old, w.new))
+        };
+
+        match *self {
+            Self::Read(addr) => read(addr),
+            Self::Write(addr) => write(addr),
+            Self::Access(addr) => write(addr).or_else(|| read(addr)),
+            Self::Register(r) | Self::RegisterValue(r, _) => {
+                let old = *entry.registers_before.get(r as usize)?;
+                let new = entry.registers_after[r as usize];
+                match *self {
+                    Self::RegisterValue(_, value) if new != value => None,
+                    _ if old == new => None,
+                    _ => Some(hit(old, new)),
+                }
+            }
+            Self::Flag(bit) => {
+                let old = (entry.flags_before & bit != 0) as u8;
+                let new = (entry.flags_after & bit != 0) as u8;
+                (old != new).then(|| hit(old, new))
+            }
+        }
+    }
+
+    /// The memory address watched, if any.
+    pub fn addr(&self) -> Option<u8> {
+        match *self {
+            Self::Read(addr) | Self::Write(addr) | Self::Access(addr) => Some(addr),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Watchpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Read(addr) => write!(f, "read:{addr:#04X}"),
+            Self::Write(addr) => write!(f, "write:{addr:#04X}"),
+            Self::Access(addr) => write!(f, "access:{addr:#04X}"),
+            Self::Register(r) => write!(f, "R{r}"),
+            Self::RegisterValue(r, value) => write!(f, "R{r}={value:#04X}"),
+            Self::Flag(bit) => {
+                let name = CPU::FLAG_NAMES
+                    .iter()
+                    .find(|&&(_, b)| b == bit)
+                    .map_or('?', |&(name, _)| name);
+                write!(f, "flag:{name}")
+            }
+        }
+    }
+}
+
+impl FromStr for Watchpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let number = |n: &str| {
+            let n = n.trim();
+            match n.strip_prefix("0x").or_else(|| n.strip_prefix("0X")) {
+                Some(hex) => u8::from_str_radix(hex, 16),
+                None => n.parse(),
+            }
+            .map_err(|_| format!("invalid number '{n}' in watchpoint '{s}'"))
+        };
+
+        if let Some((kind, arg)) = s.split_once(':') {
+            return match kind.to_ascii_lowercase().as_str() {
+                "read" => Ok(Self::Read(number(arg)?)),
+                "write" => Ok(Self::Write(number(arg)?)),
+                "access" => Ok(Self::Access(number(arg)?)),
+                "flag" => {
+                    let arg = arg.trim().to_ascii_uppercase();
+                    CPU::FLAG_NAMES
+                        .iter()
+                        .find(|&&(name, _)| arg.len() == 1 && arg.starts_with(name))
+                        .map(|&(_, bit)| Self::Flag(bit))
+                        .ok_or_else(|| format!("unknown flag '{arg}' in watchpoint '{s}'"))
+                }
+                _ => Err(format!("unknown watchpoint kind '{kind}' in '{s}'")),
+            };
+        }
+
+        let (reg, value) = match s.split_once('=') {
+            Some((reg, value)) => (reg, Some(number(value)?)),
+            None => (s, None),
+        };
+        let reg = reg
+            .trim()
+            .strip_prefix(['R', 'r'])
+            .and_then(|n| n.parse::<u8>().ok())
+            .filter(|&n| n < 16)
+            .ok_or_else(|| format!("invalid watchpoint '{s}'"))?;
+
+        Ok(match value {
+            Some(value) => Self::RegisterValue(reg, value),
+            None => Self::Register(reg),
+        })
+    }
+}
+
+impl fmt::Display for WatchHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {:#04X}: {:#04X} -> {:#04X}",
+            self.watchpoint, self.pc, self.old, self.new
+        )
+    }
+}
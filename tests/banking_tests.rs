@@ -0,0 +1,112 @@
+use nanocore::{
+    FormatError,
+    assembler::Assembler,
+    banking::{BankSelect, BankWindow, BankedImage},
+    io::BufferIo,
+    nanocore::NanoCore,
+};
+
+fn image(asm: &str) -> BankedImage {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+    BankedImage {
+        main: assembler.program,
+        banks: assembler.banks,
+    }
+}
+
+const PROGRAM: &str = ".CONST BANK_SELECT 0xA8
+                       LDI R0 1
+                       STORE R0 BANK_SELECT
+                       CALL get
+                       MOV R2 R1
+                       LDI R0 0
+                       STORE R0 BANK_SELECT
+                       CALL get
+                       LDA R3 0x61
+                       HLT
+                       .BANK 0
+                       get:
+                       LDI R1 10
+                       RET
+                       .BANK 1
+                       .DB 0x02 0x01 20 0x21";
+
+#[test]
+fn test_code_runs_from_selected_bank() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    image(PROGRAM).load(&mut nano)?;
+    nano.run()?;
+
+    assert_eq!(nano.cpu.registers[2], 20);
+    assert_eq!(nano.cpu.registers[1], 10);
+    assert_eq!(nano.cpu.registers[3], 1); // register byte of LDI R1 10 in bank 0
+    assert_eq!(nano.cpu.bus.peek(BankSelect::BASE), 0);
+    Ok(())
+}
+
+#[test]
+fn test_image_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let original = image(PROGRAM);
+    let bytes = original.to_bytes();
+
+    assert!(BankedImage::is_banked(&bytes));
+    assert_eq!(BankedImage::from_bytes(&bytes)?, original);
+
+    let mut bad = bytes.clone();
+    bad[5] = 0x40;
+    assert!(matches!(
+        BankedImage::from_bytes(&bad),
+        Err(FormatError::InvalidData(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_restores_banks() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new().with_io(BufferIo::default());
+    image(PROGRAM).load(&mut nano)?;
+    nano.run_for(2);
+    nano.cpu.bus.write(BankWindow::END, 0x77);
+    let snapshot = nano.snapshot();
+
+    nano.run()?;
+    nano.cpu.bus.write(BankWindow::END, 0);
+    assert_eq!(nano.cpu.bus.peek(BankSelect::BASE), 0);
+
+    nano.restore(&snapshot);
+    assert_eq!(nano.cpu.bus.peek(BankSelect::BASE), 1);
+    assert_eq!(nano.cpu.bus.peek(BankWindow::END), 0x77);
+    Ok(())
+}
+
+#[test]
+fn test_programs_without_banks_map_nothing() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    image("HLT").load(&mut nano)?;
+    assert!(nano.cpu.bus.mappings().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_last_bank_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let image = image(
+        "LDI R0 255
+         STORE R0 0xA8
+         CALL 0x60
+         HLT
+         .BANK 255
+         LDI R1 9
+         RET",
+    );
+    assert_eq!(image.banks.len(), 256);
+
+    let parsed = BankedImage::from_bytes(&image.to_bytes())?;
+    assert_eq!(parsed, image);
+
+    let mut nano = NanoCore::new();
+    parsed.load(&mut nano)?;
+    nano.run()?;
+    assert_eq!(nano.cpu.registers[1], 9);
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use nanocore::{Op, assembler::Assembler, nanocore::NanoCore};
+
+/// Runs `CMP a b` followed by `op`, returning whether the branch was taken.
+fn branch_after_cmp(op: Op, a: u8, b: u8) -> bool {
+    let mut assembler = Assembler::default();
+    assembler
+        .assemble(&format!(
+            "LDI R0 {a}
+             LDI R1 {b}
+             CMP R0 R1
+             {op} taken
+             HLT
+             taken:
+             LDI R2 1
+             HLT"
+        ))
+        .unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano.run().unwrap();
+    nano.cpu.registers[2] == 1
+}
+
+const VALUES: [u8; 8] = [0, 1, 2, 0x7F, 0x80, 0x81, 0xFE, 0xFF];
+
+#[test]
+fn test_unsigned_comparisons() {
+    for a in VALUES {
+        for b in VALUES {
+            assert_eq!(branch_after_cmp(Op::JLT, a, b), a < b, "JLT {a} {b}");
+            assert_eq!(branch_after_cmp(Op::JGE, a, b), a >= b, "JGE {a} {b}");
+            assert_eq!(branch_after_cmp(Op::JGT, a, b), a > b, "JGT {a} {b}");
+            assert_eq!(branch_after_cmp(Op::JLE, a, b), a <= b, "JLE {a} {b}");
+        }
+    }
+}
+
+#[test]
+fn test_signed_comparisons() {
+    for a in VALUES {
+        for b in VALUES {
+            let (sa, sb) = (a as i8, b as i8);
+            assert_eq!(branch_after_cmp(Op::JLTS, a, b), sa < sb, "JLTS {sa} {sb}");
+            assert_eq!(branch_after_cmp(Op::JGES, a, b), sa >= sb, "JGES {sa} {sb}");
+            assert_eq!(branch_after_cmp(Op::JGTS, a, b), sa > sb, "JGTS {sa} {sb}");
+            assert_eq!(branch_after_cmp(Op::JLES, a, b), sa <= sb, "JLES {sa} {sb}");
+        }
+    }
+}
+
+#[test]
+fn test_flag_branches() {
+    // 1 - 2 borrows and is negative; 2 - 1 does neither.
+    assert!(branch_after_cmp(Op::JC, 1, 2));
+    assert!(!branch_after_cmp(Op::JNC, 1, 2));
+    assert!(branch_after_cmp(Op::JN, 1, 2));
+    assert!(!branch_after_cmp(Op::JNN, 1, 2));
+
+    assert!(!branch_after_cmp(Op::JC, 2, 1));
+    assert!(branch_after_cmp(Op::JNC, 2, 1));
+    assert!(!branch_after_cmp(Op::JN, 2, 1));
+    assert!(branch_after_cmp(Op::JNN, 2, 1));
+}
+
+#[test]
+fn test_branch_opcodes_round_trip() {
+    for byte in 0x2B..=0x36 {
+        let op = Op::try_from(byte).unwrap();
+        assert!(op.is_conditional_branch());
+        assert_eq!(op.instruction_len(), 2);
+        assert_eq!(Op::try_from(op.to_string().as_str()).unwrap(), op);
+        assert_eq!(u8::from(op), byte);
+    }
+}
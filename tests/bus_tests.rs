@@ -0,0 +1,120 @@
+use nanocore::{
+    EmulatorError,
+    assembler::Assembler,
+    bus::Bus,
+    devices::{self, Keyboard, Rng, Screen, Timer},
+    nanocore::NanoCore,
+};
+
+fn assemble(asm: &str) -> Vec<u8> {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+    assembler.program
+}
+
+#[test]
+fn test_screen_device() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    devices::map_standard(&mut nano.cpu.bus)?;
+
+    nano.load_program(
+        &assemble(
+            "LDI R0 72
+             STORE R0 0xAA
+             LDI R0 73
+             STORE R0 0xAB
+             HLT",
+        ),
+        0,
+    )?;
+    nano.run()?;
+
+    let screen = nano.cpu.bus.device::<Screen>().unwrap();
+    assert_eq!(screen.char_at(0, 0), b'H');
+    assert_eq!(screen.char_at(1, 0), b'I');
+    assert_eq!(nano.cpu.bus.ram()[0xAA], 0);
+    Ok(())
+}
+
+#[test]
+fn test_keyboard_device() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    devices::map_standard(&mut nano.cpu.bus)?;
+    nano.cpu
+        .bus
+        .device_mut::<Keyboard>()
+        .unwrap()
+        .push_key(b'x');
+
+    nano.load_program(
+        &assemble(
+            "LDA R0 0xA1
+             LDA R1 0xA2
+             LDA R2 0xA1
+             HLT",
+        ),
+        0,
+    )?;
+    nano.run()?;
+
+    assert_eq!(nano.cpu.registers[0], 1);
+    assert_eq!(nano.cpu.registers[1], b'x');
+    assert_eq!(nano.cpu.registers[2], 0);
+    Ok(())
+}
+
+#[test]
+fn test_timer_device_ticks() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    nano.cpu
+        .bus
+        .map(Timer::BASE, Timer::END, Timer::default())?;
+
+    nano.load_program(
+        &assemble(
+            "LDI R0 3
+             STORE R0 0xA4
+             INC R1
+             INC R1
+             INC R1
+             LDA R2 0xA5
+             LDA R3 0xA5
+             HLT",
+        ),
+        0,
+    )?;
+    nano.run()?;
+
+    assert_eq!(nano.cpu.registers[2], 1);
+    assert_eq!(nano.cpu.registers[3], 0);
+    Ok(())
+}
+
+#[test]
+fn test_rng_is_reproducible() {
+    let mut a = Bus::new();
+    let mut b = Bus::new();
+    a.map(Rng::BASE, Rng::END, Rng::default()).unwrap();
+    b.map(Rng::BASE, Rng::END, Rng::default()).unwrap();
+
+    let xs: Vec<u8> = (0..8).map(|_| a.read(Rng::BASE)).collect();
+    let ys: Vec<u8> = (0..8).map(|_| b.read(Rng::BASE)).collect();
+
+    assert_eq!(xs, ys);
+    assert!(xs.windows(2).any(|w| w[0] != w[1]));
+}
+
+#[test]
+fn test_bus_conflict() {
+    let mut bus = Bus::new();
+    bus.map(Screen::BASE, Screen::END, Screen::default())
+        .unwrap();
+
+    assert!(matches!(
+        bus.map(0xE0, 0xF0, Screen::default()),
+        Err(EmulatorError::BusConflict { .. })
+    ));
+
+    assert!(bus.unmap(Screen::BASE).is_some());
+    assert!(bus.is_ram(Screen::BASE));
+}
@@ -0,0 +1,113 @@
+use nanocore::{assembler::Assembler, cpu::CPU, nanocore::NanoCore};
+
+/// Runs `asm` with R0:R1 = `a` and R2:R3 = `b` (high byte first) and returns
+/// R0:R1 as a 16-bit value.
+fn run16(asm: &str, a: u16, b: u16) -> u16 {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0).unwrap();
+    let [a_hi, a_lo] = a.to_be_bytes();
+    let [b_hi, b_lo] = b.to_be_bytes();
+    nano.cpu.registers[..4].copy_from_slice(&[a_hi, a_lo, b_hi, b_lo]);
+    nano.run().unwrap();
+
+    u16::from_be_bytes([nano.cpu.registers[0], nano.cpu.registers[1]])
+}
+
+const VALUES: [u16; 7] = [0, 1, 0x00FF, 0x0100, 0x7FFF, 0x8001, 0xFFFF];
+
+#[test]
+fn test_16_bit_addition() {
+    for a in VALUES {
+        for b in VALUES {
+            let sum = run16(
+                "ADD R1 R3
+                 ADC R0 R2
+                 HLT",
+                a,
+                b,
+            );
+            assert_eq!(sum, a.wrapping_add(b), "{a:#06X} + {b:#06X}");
+        }
+    }
+}
+
+#[test]
+fn test_16_bit_subtraction() {
+    for a in VALUES {
+        for b in VALUES {
+            let diff = run16(
+                "SUB R1 R3
+                 SBC R0 R2
+                 HLT",
+                a,
+                b,
+            );
+            assert_eq!(diff, a.wrapping_sub(b), "{a:#06X} - {b:#06X}");
+        }
+    }
+}
+
+#[test]
+fn test_16_bit_immediate() {
+    for a in VALUES {
+        let sum = run16(
+            "ADDI R1 0x34
+             ADCI R0 0x12
+             HLT",
+            a,
+            0,
+        );
+        assert_eq!(sum, a.wrapping_add(0x1234), "{a:#06X} + 0x1234");
+
+        let diff = run16(
+            "SUBI R1 0x34
+             SBCI R0 0x12
+             HLT",
+            a,
+            0,
+        );
+        assert_eq!(diff, a.wrapping_sub(0x1234), "{a:#06X} - 0x1234");
+    }
+}
+
+#[test]
+fn test_16_bit_rotate_through_carry() {
+    for a in VALUES {
+        // ADDI R15 0 clears the carry first.
+        let left = run16(
+            "ADDI R15 0
+             RCL R1
+             RCL R0
+             HLT",
+            a,
+            0,
+        );
+        assert_eq!(left, a << 1, "{a:#06X} << 1");
+
+        let right = run16(
+            "ADDI R15 0
+             RCR R0
+             RCR R1
+             HLT",
+            a,
+            0,
+        );
+        assert_eq!(right, a >> 1, "{a:#06X} >> 1");
+    }
+}
+
+#[test]
+fn test_rcl_moves_bit_7_into_carry() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    nano.load_program(&[0x3B, 0x00, 0x00], 0)?; // RCL R0; HLT
+    nano.cpu.registers[0] = 0x81;
+    nano.cpu.set_flag(CPU::FLAG_C);
+    nano.run()?;
+
+    assert_eq!(nano.cpu.registers[0], 0x03);
+    assert!(nano.cpu.get_flag(CPU::FLAG_C));
+    Ok(())
+}
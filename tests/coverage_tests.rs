@@ -0,0 +1,128 @@
+use nanocore::{
+    FormatError,
+    assembler::Assembler,
+    coverage::{BranchCounts, Coverage},
+    nanocore::NanoCore,
+};
+
+const ABS: &str = "; absolute value of R0
+    LDI R1 0x80
+    AND R1 R0
+    JZ done
+    NOT R0
+    INC R0
+done:
+    HLT
+.DB 1 2 3";
+
+fn run(asm: &str, r0: u8) -> (Coverage, Assembler) {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano.cpu.registers[0] = r0;
+    nano.add_observer(Coverage::new(&assembler.program));
+    nano.run().unwrap();
+    (nano.observer::<Coverage>().unwrap().clone(), assembler)
+}
+
+#[test]
+fn test_uncovered_lines_and_branches() {
+    let (coverage, assembler) = run(ABS, 5);
+    let lines = coverage.lines(ABS, &assembler.line_map);
+
+    // Comments, labels and data are not coverable.
+    let numbers: Vec<_> = lines.iter().map(|l| l.line).collect();
+    assert_eq!(numbers, [2, 3, 4, 5, 6, 8]);
+    let hit: Vec<_> = lines.iter().map(|l| l.hits).collect();
+    assert_eq!(hit, [1, 1, 1, 0, 0, 1]);
+
+    let branch = lines[2].branch.unwrap();
+    assert_eq!((branch.taken, branch.not_taken), (1, 0));
+    assert_eq!(branch.covered(), 1);
+    assert!(
+        lines
+            .iter()
+            .filter(|l| l.line != 4)
+            .all(|l| l.branch.is_none())
+    );
+
+    let report = coverage.report(ABS, &assembler.line_map);
+    assert!(report.starts_with("== Coverage: 4/6 lines (66.7%), 1/2 branch outcomes (50.0%) =="));
+    assert!(report.contains("    #####:    5:    NOT R0\n"));
+    assert!(report.contains("        -:    9:.DB 1 2 3\n"));
+    assert!(report.contains("branch taken 1, not taken 0\n"));
+}
+
+#[test]
+fn test_merging_runs() {
+    let (mut coverage, assembler) = run(ABS, 5);
+    let (negative, _) = run(ABS, 0xFB);
+    coverage.merge(&negative);
+
+    let lines = coverage.lines(ABS, &assembler.line_map);
+    assert!(lines.iter().all(|l| l.hits > 0));
+    assert_eq!(
+        coverage.branches[&0x05],
+        BranchCounts {
+            taken: 1,
+            not_taken: 1
+        }
+    );
+    assert_eq!(coverage.hits[0x00], 2);
+}
+
+#[test]
+fn test_branch_to_self() {
+    let asm = "LDI R0 1
+               wait:
+               JZ wait
+               HLT";
+    let (coverage, _) = run(asm, 0);
+    assert_eq!(coverage.branches[&0x03].not_taken, 1);
+    assert_eq!(coverage.branches[&0x03].taken, 0);
+}
+
+#[test]
+fn test_lcov() {
+    let (coverage, assembler) = run(ABS, 5);
+    assert_eq!(
+        coverage.lcov("abs.nca", ABS, &assembler.line_map),
+        "TN:
+SF:abs.nca
+BRDA:4,0,0,1
+BRDA:4,0,1,0
+BRF:2
+BRH:1
+DA:2,1
+DA:3,1
+DA:4,1
+DA:5,0
+DA:6,0
+DA:8,1
+LF:6
+LH:4
+end_of_record
+"
+    );
+
+    // Branches that never ran have unknown outcomes.
+    let unreached = Coverage::new(&assembler.program);
+    assert!(
+        unreached
+            .lcov("abs.nca", ABS, &assembler.line_map)
+            .contains("BRDA:4,0,0,-\n")
+    );
+}
+
+#[test]
+fn test_data_file_round_trip() {
+    let (coverage, _) = run(ABS, 0xFB);
+    assert_eq!(Coverage::from_bytes(&coverage.to_bytes()), Ok(coverage));
+
+    assert!(matches!(
+        Coverage::from_bytes(b"NCST\x01"),
+        Err(FormatError::BadMagic { .. })
+    ));
+}
@@ -0,0 +1,128 @@
+use std::fs;
+
+use nanocore::{assembler::Assembler, disassembler, loader::Executable};
+
+fn assemble(source: &str) -> Assembler {
+    let mut assembler = Assembler::default();
+    assembler.assemble(source).unwrap();
+    assembler
+}
+
+fn assert_round_trip(assembler: &Assembler, executable: &Executable) -> String {
+    let asm = disassembler::disassemble(executable);
+    let reassembled = assemble(&asm);
+    assert_eq!(reassembled.program, assembler.program, "{asm}");
+    assert_eq!(reassembled.banks, assembler.banks, "{asm}");
+    asm
+}
+
+#[test]
+fn test_programs_round_trip() {
+    for entry in fs::read_dir("programs").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|e| e != "nca") {
+            continue;
+        }
+
+        let assembler = assemble(&fs::read_to_string(&path).unwrap());
+        let mut executable = Executable::from_assembler(&assembler);
+        assert_round_trip(&assembler, &executable);
+
+        executable.symbols.clear();
+        assert_round_trip(&assembler, &executable);
+    }
+}
+
+#[test]
+fn test_separates_code_and_data() {
+    let assembler = assemble(
+        "JMP main
+         msg:
+         .STRING \"Hello\"
+         .DB 0 0xFF 7
+         main:
+         LDI R0 0x02
+         LDA R1 0x05
+         HLT
+         .DB 0x0D 0x10",
+    );
+    let mut executable = Executable::from_assembler(&assembler);
+    executable.symbols.clear();
+
+    let asm = assert_round_trip(&assembler, &executable);
+    assert!(asm.contains("    JMP lbl_0a "));
+    assert!(asm.contains("    .STRING \"Hello\" "));
+    assert!(asm.contains("    .DB 0x00 0xFF 0x07 "));
+    assert!(asm.contains("lbl_0a:\n    LDI R0 2 "));
+    assert!(asm.contains("    LDA R1 0x05 "));
+    // Unreachable, and INC R16 would not assemble anyway.
+    assert!(asm.contains("    .DB 0x0D 0x10 "));
+}
+
+#[test]
+fn test_labels() {
+    let source = "CALL print
+                  loop:
+                  DEC R0
+                  JNZ loop
+                  HLT
+                  print:
+                  PRINT R0
+                  RET";
+    let assembler = assemble(source);
+    let mut executable = Executable::from_assembler(&assembler);
+
+    let asm = assert_round_trip(&assembler, &executable);
+    assert!(asm.contains("    CALL print "));
+    assert!(asm.contains("loop:\n    DEC R0 "));
+    assert!(asm.contains("    JNZ loop "));
+
+    executable.symbols.clear();
+    let asm = assert_round_trip(&assembler, &executable);
+    assert!(asm.contains("start:\n    CALL sub_07 "));
+    assert!(asm.contains("sub_07:\n    PRINT R0 "));
+    assert!(asm.contains("    JNZ lbl_02 "));
+}
+
+#[test]
+fn test_nop_written_as_data() {
+    let assembler = assemble(
+        ".DB 0x01
+         INC R0
+         HLT",
+    );
+    let executable = Executable::from_raw(assembler.program.clone());
+
+    let asm = assert_round_trip(&assembler, &executable);
+    assert!(asm.contains("    .DB 0x01                ; 0x00 NOP\n"));
+    assert!(asm.contains("    INC R0                  ; 0x01\n"));
+}
+
+#[test]
+fn test_banks_and_entry_point() {
+    let assembler = assemble(
+        ".DB 0x2A 0x2A
+         LDI R0 1
+         STORE R0 0xA8
+         CALL 0x60
+         HLT
+         .BANK 1
+         LDI R1 3
+         spin:
+         DEC R1
+         JNZ spin
+         RET",
+    );
+    let mut executable = Executable::from_assembler(&assembler);
+    executable.entry = 0x02;
+    executable.symbols.clear();
+
+    let asm = assert_round_trip(&assembler, &executable);
+    assert!(asm.contains("entry point: 0x02"));
+    assert!(asm.contains("    .DB 0x2A 0x2A "));
+    assert!(asm.contains("start:\n    LDI R0 1 "));
+    assert!(asm.contains("    CALL 0x60 "));
+    assert!(asm.contains(".BANK 1\n    LDI R1 3 "));
+    assert!(asm.contains("b1_lbl_63:\n    DEC R1 "));
+    assert!(asm.contains("    JNZ b1_lbl_63 "));
+}
@@ -228,3 +228,43 @@ fn test_normal_stack_operations() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(nano.cpu.registers[1], 42);
     Ok(())
 }
+
+#[test]
+fn test_illegal_opcode() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    nano.load_program(&[0x0D, 0x00, 0xEE, 0x00], 0)?; // INC R0; <0xEE>; HLT
+
+    nano.cycle()?;
+    let result = nano.cycle();
+
+    assert_eq!(
+        result,
+        Err(EmulatorError::IllegalOpcode {
+            pc: 0x02,
+            byte: 0xEE
+        })
+    );
+    assert_eq!(nano.cpu.pc, 0x02);
+    Ok(())
+}
+
+#[test]
+fn test_illegal_opcode_lenient_mode() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new().with_lenient_decoding();
+    nano.load_program(&[0xEE, 0x0D, 0x00, 0x00], 0)?; // <0xEE>; INC R0; HLT
+    nano.run()?;
+
+    assert_eq!(nano.cpu.registers[0], 1);
+    assert!(nano.cpu.is_halted);
+    Ok(())
+}
+
+#[test]
+fn test_opcode_round_trip() {
+    for byte in 0..=u8::MAX {
+        match nanocore::Op::try_from(byte) {
+            Ok(op) => assert_eq!(u8::from(op), byte),
+            Err(e) => assert_eq!(e, EmulatorError::IllegalOpcode { pc: 0, byte }),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use nanocore::{
+    assembler::Assembler,
+    nanocore::{NanoCore, StopReason},
+};
+
+fn assemble(asm: &str) -> Assembler {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+    assembler
+}
+
+fn load(program: &[u8], fast: bool) -> NanoCore {
+    let mut nano = NanoCore::new().with_max_cycles(10_000);
+    if fast {
+        nano = nano.with_fast_mode();
+    }
+    nano.load_program(program, 0).unwrap();
+    nano
+}
+
+/// Adds the immediate of `LDI R0` to R2 twice, patching the immediate to
+/// 0x2A after the first pass.
+fn self_modifying() -> Vec<u8> {
+    let labels = assemble(
+        "LDI R1 0x2A
+         LDI R3 2
+         patch:
+         LDI R0 1",
+    )
+    .labels;
+
+    assemble(&format!(
+        "LDI R1 0x2A
+         LDI R3 2
+         patch:
+         LDI R0 1
+         ADD R2 R0
+         STORE R1 {}
+         DEC R3
+         JNZ patch
+         HLT",
+        labels["patch"] + 2
+    ))
+    .program
+}
+
+#[test]
+fn test_fast_mode_matches_normal_mode() {
+    let source = std::fs::read_to_string("programs/fib_extended.nca").unwrap();
+    let program = assemble(&source).program;
+
+    let mut normal = load(&program, false);
+    let mut fast = load(&program, true);
+    normal.run().unwrap();
+    fast.run().unwrap();
+
+    assert!(fast.cpu.is_halted);
+    assert_eq!(fast.output, normal.output);
+    assert_eq!(fast.cpu.registers, normal.cpu.registers);
+    assert_eq!(fast.cpu.bus.ram(), normal.cpu.bus.ram());
+    assert_eq!((fast.cycle, fast.t_states), (normal.cycle, normal.t_states));
+
+    assert!(fast.current_instruction.is_none());
+    assert!(fast.instruction_log.is_empty());
+    assert!(fast.icache.as_ref().unwrap().hits > 0);
+}
+
+#[test]
+fn test_self_modifying_code() {
+    let program = self_modifying();
+
+    for fast in [false, true] {
+        let mut nano = load(&program, fast);
+        nano.run().unwrap();
+        assert_eq!(nano.cpu.registers[2], 1 + 0x2A, "fast: {fast}");
+    }
+}
+
+#[test]
+fn test_step_back_restores_patched_code() {
+    let program = self_modifying();
+    let mut nano = load(&program, true).with_journal(64);
+    nano.run().unwrap();
+    assert_eq!(nano.cpu.registers[2], 1 + 0x2A);
+
+    assert!(nano.rewind_to(0));
+    nano.cpu.registers[2] = 0;
+    nano.run().unwrap();
+    assert_eq!(nano.cpu.registers[2], 1 + 0x2A);
+}
+
+#[test]
+fn test_flush_after_direct_write() {
+    let program = assemble(
+        "loop:
+         INC R0
+         JMP loop",
+    )
+    .program;
+    let mut nano = load(&program, true);
+    nano.run_for(4);
+    assert_eq!(nano.cpu.registers[0], 2);
+
+    // INC R0 -> INC R1
+    nano.cpu.bus.write(0x01, 0x01);
+    nano.flush_icache();
+    nano.run_for(4);
+    assert_eq!(nano.cpu.registers[0], 2);
+    assert_eq!(nano.cpu.registers[1], 2);
+}
+
+#[test]
+fn test_watchpoints_in_fast_mode() {
+    let program = assemble(
+        "LDI R0 7
+         STORE R0 0x80
+         HLT",
+    )
+    .program;
+    let mut nano = load(&program, true);
+    nano.watchpoints = vec!["write:0x80".parse().unwrap()];
+
+    let StopReason::Watchpoint(hit) = nano.run_until(|_| false) else {
+        panic!("expected a watchpoint");
+    };
+    assert_eq!((hit.pc, hit.new), (0x03, 7));
+    assert!(nano.current_instruction.is_some());
+}
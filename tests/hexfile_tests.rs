@@ -0,0 +1,149 @@
+use nanocore::{
+    FormatError, Op,
+    assembler::Assembler,
+    hexfile,
+    loader::{Executable, Segment},
+};
+
+fn banked() -> Executable {
+    let mut assembler = Assembler::default();
+    assembler
+        .assemble(
+            "LDI R0 1
+             STORE R0 0xA8
+             CALL 0x60
+             HLT
+             .BANK 0
+             RET
+             .BANK 1
+             RET",
+        )
+        .unwrap();
+
+    let mut executable = Executable::from_assembler(&assembler);
+    executable.entry = 0x03;
+    executable.symbols.clear();
+    executable
+}
+
+#[test]
+fn test_ihex_round_trip() {
+    let executable = banked();
+    let text = hexfile::to_ihex(&executable);
+
+    // Bank 1 lives at 0x0260.
+    assert!(text.contains(":01026000"));
+    assert!(text.ends_with(":0400000500000003F4\n:00000001FF\n"));
+    assert_eq!(hexfile::from_ihex(&text).unwrap(), executable);
+}
+
+#[test]
+fn test_srec_round_trip() {
+    let executable = banked();
+    let text = hexfile::to_srec(&executable);
+
+    let lines: Vec<_> = text.lines().collect();
+    assert!(lines[0].starts_with("S0"));
+    assert!(lines.contains(&"S10401602179"));
+    assert_eq!(lines[lines.len() - 2], "S5030003F9");
+    assert_eq!(lines[lines.len() - 1], "S9030003F9");
+    assert_eq!(hexfile::from_srec(&text).unwrap(), executable);
+}
+
+#[test]
+fn test_checksum_errors_report_the_line() {
+    let ihex = ":0400000002000700F3\n:0400000002000700F4\n";
+    assert_eq!(
+        hexfile::from_ihex(ihex),
+        Err(FormatError::ChecksumMismatch {
+            line: Some(2),
+            expected: 0xF3,
+            found: 0xF4,
+        })
+    );
+
+    let srec = "S107000002000700EE\n";
+    let err = hexfile::from_srec(srec).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Line 1: Checksum mismatch: expected 0xEF, found 0xEE"
+    );
+}
+
+#[test]
+fn test_foreign_records() {
+    // Extended linear address 0, start segment address 0000:0002.
+    let ihex = ":020000040000FA\n:0400000002000700F3\n:0400000300000002F7\n:00000001FF\n";
+    let executable = hexfile::from_ihex(ihex).unwrap();
+    assert_eq!(executable.entry, 0x02);
+    assert_eq!(executable.main_image(), [0x02, 0x00, 0x07, 0x00]);
+
+    // 24-bit addresses.
+    let srec = "S20800001002000700DE\nS804000010EB\n";
+    let executable = hexfile::from_srec(srec).unwrap();
+    assert_eq!((executable.load_address, executable.entry), (0x10, 0x10));
+
+    // Outside the bank window of bank 0.
+    assert!(matches!(
+        hexfile::from_ihex(":01010000FFFF\n"),
+        Err(FormatError::InvalidData(_))
+    ));
+}
+
+#[test]
+fn test_loader_detects_text_formats() {
+    let executable = Executable::from_raw(vec![0x02, 0x00, 0x07, 0x00]);
+    let ihex = hexfile::to_ihex(&executable);
+    let srec = hexfile::to_srec(&executable);
+    assert_eq!(Executable::parse(ihex.as_bytes()).unwrap(), executable);
+    assert_eq!(Executable::parse(srec.as_bytes()).unwrap(), executable);
+
+    // A raw program that happens to start with ':'.
+    let raw = vec![u8::from(Op::SBCI), 0x00, 0x00];
+    assert_eq!(raw[0], b':');
+    assert_eq!(
+        Executable::parse(&raw).unwrap().segments,
+        [Segment {
+            bank: None,
+            addr: 0x00,
+            bytes: raw.clone()
+        }]
+    );
+}
+
+#[test]
+fn test_short_records_are_rejected() {
+    // A lone checksum byte, then a count and checksum with no address.
+    for srec in ["S1FF", "S101FE"] {
+        assert_eq!(
+            hexfile::from_srec(srec),
+            Err(FormatError::InvalidData(
+                "line 1: record is too short".to_string()
+            ))
+        );
+    }
+    assert!(matches!(
+        hexfile::from_ihex(":"),
+        Err(FormatError::InvalidData(_))
+    ));
+}
+
+#[test]
+fn test_last_bank_round_trip() {
+    let mut executable = banked();
+    executable.segments.push(Segment {
+        bank: Some(255),
+        addr: 0x60,
+        bytes: vec![u8::from(Op::RET)],
+    });
+
+    // Bank 255 lives at 0x10060, past 16-bit addresses.
+    let ihex = hexfile::to_ihex(&executable);
+    assert!(ihex.contains(":020000040001F9\n:01006000"));
+    assert_eq!(hexfile::from_ihex(&ihex).unwrap(), executable);
+
+    let srec = hexfile::to_srec(&executable);
+    assert!(srec.contains("\nS20501006021"));
+    assert!(srec.contains("\nS804000003F8\n"));
+    assert_eq!(hexfile::from_srec(&srec).unwrap(), executable);
+}
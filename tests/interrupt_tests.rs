@@ -0,0 +1,108 @@
+use nanocore::{assembler::Assembler, cpu::CPU, devices::Timer, nanocore::NanoCore};
+
+fn assemble(asm: &str) -> Vec<u8> {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+    assembler.program
+}
+
+#[test]
+fn test_interrupt_pending_until_enabled() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    nano.load_program(
+        &assemble(
+            "INC R0
+             EI
+             INC R0
+             HLT
+             handler:
+             LDI R1 42
+             RETI",
+        ),
+        0,
+    )?;
+    nano.cpu.bus.write(CPU::IRQ_VECTOR, 0x06);
+
+    nano.raise_irq();
+    nano.cycle()?; // INC R0 (interrupts disabled)
+    assert_eq!(nano.cpu.registers[0], 1);
+    assert!(nano.cpu.irq_pending);
+
+    nano.cycle()?; // EI
+    nano.cycle()?; // interrupt entry
+    assert_eq!(nano.cpu.pc, 0x06);
+    assert!(!nano.cpu.get_flag(CPU::FLAG_I));
+    assert_eq!(nano.cpu.sp, CPU::STACK_MAX - 2);
+
+    nano.run()?;
+    assert_eq!(nano.cpu.registers[0], 2);
+    assert_eq!(nano.cpu.registers[1], 42);
+    assert!(nano.cpu.get_flag(CPU::FLAG_I));
+    assert_eq!(nano.cpu.sp, CPU::STACK_MAX);
+    Ok(())
+}
+
+#[test]
+fn test_reti_restores_flags() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    nano.load_program(
+        &assemble(
+            "EI
+             LDI R0 0
+             HLT
+             handler:
+             LDI R1 1
+             RETI",
+        ),
+        0,
+    )?;
+    nano.cpu.bus.write(CPU::IRQ_VECTOR, 0x05);
+
+    nano.cycle()?; // EI
+    nano.cycle()?; // LDI R0 0 sets Z
+    nano.raise_irq();
+    nano.cycle()?; // interrupt entry
+    nano.cycle()?; // LDI R1 1 clears Z
+    assert!(!nano.cpu.get_flag(CPU::FLAG_Z));
+    nano.cycle()?; // RETI
+
+    assert!(nano.cpu.get_flag(CPU::FLAG_Z));
+    assert_eq!(nano.cpu.pc, 0x04);
+    Ok(())
+}
+
+#[test]
+fn test_timer_interrupt() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    nano.cpu
+        .bus
+        .map(Timer::BASE, Timer::END, Timer::default())?;
+    nano.load_program(
+        &assemble(
+            ".CONST TIMER_RELOAD 0xA4
+             start:
+                 LDI R2 3
+                 LDI R0 5
+                 STORE R0 TIMER_RELOAD
+                 EI
+             wait:
+                 INC R3
+                 JMP wait
+             handler:
+                 INC R1
+                 CMP R1 R2
+                 JZ done
+                 RETI
+             done:
+                 HLT",
+        ),
+        0,
+    )?;
+    nano.cpu.bus.write(CPU::IRQ_VECTOR, 0x0E);
+
+    nano.run()?;
+
+    assert_eq!(nano.cpu.registers[1], 3);
+    assert!(nano.cpu.registers[3] > 0);
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use nanocore::{
+    EmulatorError,
+    assembler::Assembler,
+    io::{BufferIo, ScriptedIo},
+    nanocore::NanoCore,
+};
+
+fn assemble(asm: &str) -> Vec<u8> {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+    assembler.program
+}
+
+#[test]
+fn test_buffer_io_echo() -> Result<(), Box<dyn std::error::Error>> {
+    let program = assemble(
+        "IN R0
+         PRINT R0
+         IN R0
+         PRINT R0
+         HLT",
+    );
+
+    let mut nano = NanoCore::new().with_io(BufferIo::new("hi")).with_print();
+    nano.load_program(&program, 0)?;
+    nano.run()?;
+
+    assert_eq!(nano.io_backend::<BufferIo>().unwrap().output_string(), "hi");
+    assert_eq!(nano.output, "hi");
+
+    // Without `print`, output is only collected.
+    let mut nano = NanoCore::new().with_io(BufferIo::new("hi"));
+    nano.load_program(&program, 0)?;
+    nano.run()?;
+
+    assert_eq!(nano.io_backend::<BufferIo>().unwrap().output_string(), "");
+    assert_eq!(nano.output, "hi");
+    Ok(())
+}
+
+#[test]
+fn test_buffer_io_exhausted() -> Result<(), Box<dyn std::error::Error>> {
+    let program = assemble(
+        "IN R0
+         HLT",
+    );
+
+    let mut nano = NanoCore::new().with_io(BufferIo::default());
+    nano.load_program(&program, 0)?;
+
+    assert!(matches!(nano.run(), Err(EmulatorError::IoError(_))));
+    Ok(())
+}
+
+#[test]
+fn test_scripted_io_fallback() -> Result<(), Box<dyn std::error::Error>> {
+    let program = assemble(
+        "IN R0
+         IN R1
+         HLT",
+    );
+
+    let mut nano = NanoCore::new().with_io(ScriptedIo::new([7]).with_fallback(9));
+    nano.load_program(&program, 0)?;
+    nano.run()?;
+
+    assert_eq!(nano.cpu.registers[0], 7);
+    assert_eq!(nano.cpu.registers[1], 9);
+    assert_eq!(nano.io_backend::<ScriptedIo>().unwrap().reads, 2);
+    assert!(nano.io_backend::<BufferIo>().is_none());
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use nanocore::{
+    assembler::Assembler,
+    devices::{self, Screen},
+    io::BufferIo,
+    nanocore::{NanoCore, StopReason},
+};
+
+const PROGRAM: &str = "LDI R0 65
+                       LDI R1 0xAA
+                       LDI R2 0x80
+                       loop:
+                       PRINT R0
+                       STR R0 R1
+                       STR R0 R2
+                       PUSH R0
+                       POP R3
+                       INC R0
+                       INC R1
+                       INC R2
+                       CMP R0 R4
+                       JNZ loop
+                       HLT";
+
+fn journaled(depth: usize) -> NanoCore {
+    let mut assembler = Assembler::default();
+    assembler.assemble(PROGRAM).unwrap();
+
+    let mut nano = NanoCore::new()
+        .with_io(BufferIo::default())
+        .with_journal(depth);
+    devices::map_standard(&mut nano.cpu.bus).unwrap();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano.cpu.registers[4] = 70;
+    nano
+}
+
+#[test]
+fn test_step_back_restores_every_cycle() {
+    let mut nano = journaled(1000);
+
+    let mut history = vec![nano.snapshot()];
+    while nano.step() == StopReason::Stepped {
+        history.push(nano.snapshot());
+    }
+    assert!(nano.cpu.is_halted);
+    assert_eq!(nano.output, "ABCDE");
+
+    while let Some(expected) = history.pop() {
+        assert!(nano.step_back());
+        assert_eq!(nano.snapshot(), expected);
+    }
+    assert!(!nano.step_back());
+    assert_eq!(nano.output, "");
+}
+
+#[test]
+fn test_rewind_to_cycle() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = journaled(1000);
+    nano.run_for(20);
+    let at_20 = nano.snapshot();
+    nano.run()?;
+
+    assert!(nano.rewind_to(20));
+    assert_eq!(nano.cycle, 20);
+    assert_eq!(nano.snapshot(), at_20);
+
+    let screen = nano.cpu.bus.device::<Screen>().unwrap();
+    assert_eq!(screen.char_at(1, 0), b'B');
+    assert_eq!(screen.char_at(2, 0), 0);
+
+    assert!(!nano.rewind_to(30));
+    Ok(())
+}
+
+#[test]
+fn test_history_depth_is_bounded() {
+    let mut nano = journaled(3);
+    nano.run_for(10);
+    assert_eq!(nano.journal.as_ref().unwrap().len(), 3);
+
+    assert!(!nano.rewind_to(6));
+    assert!(nano.rewind_to(8));
+    assert!(nano.step_back());
+    assert!(!nano.step_back());
+    assert_eq!(nano.cycle, 7);
+}
+
+#[test]
+fn test_disabled_by_default() {
+    let mut nano = NanoCore::new();
+    nano.load_program(&[0x0D, 0x00, 0x00], 0x00).unwrap();
+    nano.step();
+    assert!(!nano.step_back());
+    assert_eq!(nano.cpu.registers[0], 1);
+}
@@ -0,0 +1,174 @@
+use std::fs;
+
+use nanocore::{
+    AssemblerError, FormatError, LinkError, Op, assembler::Assembler, io::BufferIo, linker::Linker,
+    loader::Executable, nanocore::NanoCore, object::Object,
+};
+
+fn object(asm: &str) -> Object {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+    Object::from_assembler(&assembler).unwrap()
+}
+
+fn program(name: &str) -> Object {
+    object(&fs::read_to_string(format!("programs/{name}.nca")).unwrap())
+}
+
+#[test]
+fn test_links_shared_routine() -> Result<(), Box<dyn std::error::Error>> {
+    let mut linker = Linker::new();
+    linker.add("fib_linked", program("fib_linked"));
+    linker.add("print_digits", program("print_digits"));
+    let linked = linker.link()?;
+
+    assert_eq!(linked.sections[1].addr, 0x1B);
+    assert_eq!(linked.executable.labels()["print_digits"], 0x1B);
+    assert_eq!(linked.executable.labels()["print_digits.unit_digit"], 0x2B);
+
+    let mut nano = NanoCore::new().with_io(BufferIo::default()).with_print();
+    linked.executable.load(&mut nano)?;
+    nano.run()?;
+    assert_eq!(
+        nano.io_backend::<BufferIo>().unwrap().output_string(),
+        "0 1 1 2 3 5 8 13 21 34 55 89 "
+    );
+    Ok(())
+}
+
+#[test]
+fn test_object_records_symbols_and_relocations() -> Result<(), Box<dyn std::error::Error>> {
+    let object = program("fib_linked");
+    assert_eq!(object.externs, ["print_digits"]);
+    assert!(object.globals().next().is_none());
+    let relocations: Vec<_> = object
+        .relocations
+        .iter()
+        .map(|r| (r.offset, r.symbol.as_str()))
+        .collect();
+    assert_eq!(relocations, [(0x0D, "print_digits"), (0x19, "loop")]);
+    assert_eq!(object.code[0x0D], 0x00);
+
+    let bytes = object.to_bytes();
+    assert_eq!(Object::from_bytes(&bytes)?, object);
+
+    let mut corrupted = bytes.clone();
+    corrupted[8] ^= 0xFF;
+    assert!(matches!(
+        Object::from_bytes(&corrupted),
+        Err(FormatError::ChecksumMismatch { .. })
+    ));
+    assert!(matches!(
+        Executable::parse(&bytes),
+        Err(FormatError::InvalidData(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_assembler_checks_linkage() {
+    let mut assembler = Assembler::default();
+    assembler
+        .assemble("; main\n.EXTERN print\nCALL print\nHLT")
+        .unwrap();
+    assert_eq!(
+        assembler.check_linked(),
+        Err(AssemblerError::UnresolvedExtern {
+            name: "print".to_string(),
+            line: 2,
+        })
+    );
+
+    let mut assembler = Assembler::default();
+    assert!(matches!(
+        assembler.assemble(".GLOBAL missing\nHLT"),
+        Err(AssemblerError::UndefinedLabel { line: 1, .. })
+    ));
+
+    let mut assembler = Assembler::default();
+    assert!(matches!(
+        assembler.assemble(".EXTERN done\ndone:\nHLT"),
+        Err(AssemblerError::SyntaxError { line: 1, .. })
+    ));
+
+    let mut assembler = Assembler::default();
+    assembler.assemble("HLT\n.BANK 0\nRET").unwrap();
+    assert!(matches!(
+        Object::from_assembler(&assembler),
+        Err(FormatError::InvalidData(_))
+    ));
+}
+
+#[test]
+fn test_link_errors() {
+    let mut linker = Linker::new();
+    linker.add("main", object(".EXTERN print\nCALL print\nHLT"));
+    assert_eq!(
+        linker.link().unwrap_err(),
+        LinkError::UndefinedSymbol {
+            symbol: "print".to_string(),
+            module: "main".to_string(),
+        }
+    );
+
+    linker.add("a", object(".GLOBAL print\nprint:\nRET"));
+    linker.add("b", object(".GLOBAL print\nprint:\nRET"));
+    assert_eq!(
+        linker.link().unwrap_err(),
+        LinkError::DuplicateSymbol {
+            symbol: "print".to_string(),
+            first: "a".to_string(),
+            second: "b".to_string(),
+        }
+    );
+
+    let mut linker = Linker::new().with_base(0xF0);
+    linker.add(
+        "big",
+        object(".DB 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17"),
+    );
+    assert_eq!(
+        linker.link().unwrap_err(),
+        LinkError::OutOfMemory {
+            module: "big".to_string(),
+            end: 0x101,
+        }
+    );
+
+    let linker = Linker::new().with_entry("main");
+    assert!(matches!(
+        linker.link(),
+        Err(LinkError::UndefinedSymbol { .. })
+    ));
+}
+
+#[test]
+fn test_base_entry_and_map() -> Result<(), Box<dyn std::error::Error>> {
+    let mut linker = Linker::new().with_base(0x10).with_entry("main");
+    linker.add("lib", object(".GLOBAL seven\nseven:\nLDI R0 7\nRET"));
+    linker.add(
+        "main",
+        object(".GLOBAL main\n.EXTERN seven\nmain:\nCALL seven\nback:\nJMP done\ndone:\nHLT"),
+    );
+    let linked = linker.link()?;
+
+    let executable = &linked.executable;
+    assert_eq!(executable.load_address, 0x10);
+    assert_eq!(executable.entry, 0x14);
+    assert_eq!(
+        executable.main_image()[0x14..],
+        [Op::CALL.into(), 0x10, Op::JMP.into(), 0x18, Op::HLT.into()]
+    );
+
+    let map = linked.map();
+    assert!(map.contains("Entry point:  0x14\n"));
+    assert!(map.contains("  0x14      5  main\n"));
+    assert!(map.contains("  0x14  main    global  main\n"));
+    assert!(map.contains("  0x16  main    local   back\n"));
+
+    let mut nano = NanoCore::new();
+    executable.load(&mut nano)?;
+    nano.run()?;
+    assert_eq!(nano.cpu.registers[0], 7);
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use nanocore::{
+    FormatError, Op,
+    assembler::Assembler,
+    banking::BankedImage,
+    loader::{Executable, Segment, SymbolKind},
+    nanocore::NanoCore,
+};
+
+fn assemble(asm: &str) -> Assembler {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+    assembler
+}
+
+#[test]
+fn test_round_trip_with_banks_and_symbols() {
+    let assembler = assemble(
+        ".CONST ANSWER 42
+         start:
+         LDI R0 ANSWER
+         HLT
+         .BANK 1
+         routine:
+         RET",
+    );
+    let executable = Executable::from_assembler(&assembler);
+    let bytes = executable.to_bytes();
+    assert!(bytes.starts_with(b"NCBX\x02"));
+
+    let parsed = Executable::parse(&bytes).unwrap();
+    assert_eq!(parsed, executable);
+    assert_eq!(parsed.main_image(), assembler.program);
+    assert_eq!(parsed.banks()[1][0], u8::from(Op::RET));
+    assert_eq!(parsed.labels()["routine"], 0x60);
+
+    let answer = parsed.symbols.iter().find(|s| s.name == "ANSWER").unwrap();
+    assert_eq!((answer.kind, answer.value), (SymbolKind::Constant, 42));
+}
+
+#[test]
+fn test_legacy_formats_still_load() {
+    let raw = vec![0x02, 0x00, 0x07, 0x00]; // LDI R0 7; HLT
+    let executable = Executable::parse(&raw).unwrap();
+    assert_eq!(executable, Executable::from_raw(raw.clone()));
+    assert_eq!(executable.main_image(), raw);
+    assert!(executable.symbols.is_empty());
+
+    let image = BankedImage {
+        main: raw.clone(),
+        banks: vec![vec![0x00]],
+    };
+    let executable = Executable::parse(&image.to_bytes()).unwrap();
+    assert!(executable.has_banks());
+    assert_eq!(executable.main_image(), raw);
+}
+
+#[test]
+fn test_load_address_and_entry_point() {
+    let executable = Executable {
+        load_address: 0x10,
+        entry: 0x13,
+        segments: vec![
+            Segment {
+                bank: None,
+                addr: 0x10,
+                // LDI R0 1; LDI R1 2; HLT
+                bytes: vec![0x02, 0x00, 0x01, 0x02, 0x01, 0x02, 0x00],
+            },
+            Segment {
+                bank: None,
+                addr: 0x30,
+                bytes: vec![0xAB],
+            },
+        ],
+        symbols: Vec::new(),
+    };
+    let executable = Executable::parse(&executable.to_bytes()).unwrap();
+    assert_eq!(executable.main_image().len(), 0x31);
+
+    let mut nano = NanoCore::new();
+    executable.load(&mut nano).unwrap();
+    assert_eq!(nano.cpu.pc, 0x13);
+    nano.run().unwrap();
+    assert_eq!(nano.cpu.registers[..2], [0, 2]);
+    assert_eq!(nano.cpu.bus.peek(0x30), 0xAB);
+}
+
+#[test]
+fn test_corruption_is_detected() {
+    let mut bytes = Executable::from_assembler(&assemble("LDI R0 7\nHLT")).to_bytes();
+    bytes[9] ^= 0xFF;
+
+    assert!(matches!(
+        Executable::parse(&bytes),
+        Err(FormatError::ChecksumMismatch { line: None, .. })
+    ));
+}
+
+#[test]
+fn test_invalid_containers_are_rejected() {
+    let mut bytes = Executable::from_raw(vec![0x00]).to_bytes();
+    bytes[4] = 3;
+    assert_eq!(
+        Executable::parse(&bytes),
+        Err(FormatError::UnsupportedVersion { version: 3 })
+    );
+
+    let too_large = Executable {
+        segments: vec![Segment {
+            bank: None,
+            addr: 0xF0,
+            bytes: vec![0; 32],
+        }],
+        ..Executable::default()
+    };
+    assert!(matches!(
+        Executable::parse(&too_large.to_bytes()),
+        Err(FormatError::InvalidData(_))
+    ));
+}
+
+#[test]
+fn test_last_bank_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let assembler = assemble(
+        "LDI R0 255
+         STORE R0 0xA8
+         CALL 0x60
+         HLT
+         .BANK 255
+         LDI R1 9
+         RET",
+    );
+    let executable = Executable::from_assembler(&assembler);
+    // Main memory and 256 banks.
+    assert_eq!(executable.segments.len(), 257);
+
+    let parsed = Executable::parse(&executable.to_bytes())?;
+    assert_eq!(parsed, executable);
+    assert_eq!(parsed.segments[256].bank, Some(255));
+
+    let mut nano = NanoCore::new();
+    parsed.load(&mut nano)?;
+    nano.run()?;
+    assert_eq!(nano.cpu.registers[1], 9);
+    Ok(())
+}
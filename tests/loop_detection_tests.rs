@@ -0,0 +1,126 @@
+use nanocore::{
+    assembler::Assembler,
+    devices,
+    io::BufferIo,
+    loop_detect::LoopHit,
+    nanocore::{NanoCore, StopReason},
+};
+
+fn load(asm: &str) -> NanoCore {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new()
+        .with_max_cycles(10_000)
+        .with_loop_detection();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano
+}
+
+fn hit(reason: StopReason) -> LoopHit {
+    match reason {
+        StopReason::Loop(hit) => hit,
+        reason => panic!("expected a loop, got {reason:?}"),
+    }
+}
+
+#[test]
+fn test_counter_that_never_changes() {
+    let mut nano = load(
+        "LDI R0 5
+         loop:
+         ADDI R1 0
+         CMP R0 R2
+         JNZ loop
+         HLT",
+    );
+
+    let hit = hit(nano.run_until(|_| false));
+    assert_eq!(
+        hit,
+        LoopHit {
+            start: 0x03,
+            end: 0x08,
+            period: 3
+        }
+    );
+    // The state repeats on the second pass through the backward jump and is
+    // confirmed on the third.
+    assert_eq!(nano.cycle, 10);
+    assert_eq!(hit.to_string(), "0x03-0x08, repeating every 3 instructions");
+}
+
+#[test]
+fn test_terminating_loops_are_not_reported() {
+    let source = std::fs::read_to_string("programs/fib_extended.nca").unwrap();
+    let mut nano = load(&source);
+    assert_eq!(nano.run_until(|_| false), StopReason::Halted);
+
+    // Memory changes on every pass.
+    let mut nano = load(
+        "loop:
+         INC R0
+         STORE R0 0x80
+         CMP R0 R1
+         JNZ loop
+         HLT",
+    );
+    nano.cpu.registers[1] = 200;
+    assert_eq!(nano.run_until(|_| false), StopReason::Halted);
+    assert_eq!(nano.cpu.registers[0], 200);
+}
+
+#[test]
+fn test_device_state_is_part_of_the_machine_state() {
+    // Wait for the timer to expire, which takes several passes.
+    let asm = "LDI R0 10
+               STORE R0 0xA4
+               wait:
+               LDA R1 0xA5
+               CMP R1 R2
+               JZ wait
+               HLT";
+
+    let mut nano = load(asm);
+    devices::map_standard(&mut nano.cpu.bus).unwrap();
+    assert_eq!(nano.run_until(|_| false), StopReason::Halted);
+
+    // Without the timer, 0xA5 is plain RAM that never changes.
+    let mut nano = load(asm);
+    assert_eq!(hit(nano.run_until(|_| false)).start, 0x06);
+}
+
+#[test]
+fn test_input_resets_the_history() {
+    let mut nano = load(
+        "LDI R1 0x71
+         loop:
+         IN R0
+         CMP R0 R1
+         JNZ loop
+         HLT",
+    )
+    .with_io(BufferIo::new("aaaaq"));
+    assert_eq!(nano.run_until(|_| false), StopReason::Halted);
+}
+
+#[test]
+fn test_resuming_and_rewinding() {
+    let mut nano = load(
+        "loop:
+         JMP loop",
+    )
+    .with_journal(16);
+    hit(nano.run_until(|_| false));
+    assert_eq!(nano.cycle, 3);
+
+    // Each further pass repeats the state again.
+    hit(nano.run_until(|_| false));
+    assert_eq!(nano.cycle, 4);
+
+    // Stepping back forgets states that are now in the future.
+    assert!(nano.rewind_to(0));
+    assert_eq!(nano.step(), StopReason::Stepped);
+    assert_eq!(nano.step(), StopReason::Stepped);
+    hit(nano.step());
+}
@@ -0,0 +1,139 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nanocore::{
+    EmulatorError, Op,
+    assembler::Assembler,
+    cpu::CPU,
+    nanocore::{NanoCore, Operands},
+    observer::Observer,
+};
+
+#[derive(Debug, Default)]
+struct Recorder {
+    events: Vec<String>,
+}
+
+impl Observer for Recorder {
+    fn on_fetch(&mut self, pc: u8, opcode: u8) {
+        self.events.push(format!("fetch {pc:#04X} {opcode:#04X}"));
+    }
+
+    fn before_execute(&mut self, _cpu: &CPU, pc: u8, op: Op, _operands: Operands) {
+        self.events.push(format!("before {pc:#04X} {op}"));
+    }
+
+    fn after_execute(&mut self, _cpu: &CPU, pc: u8, op: Op, t_states: u64) {
+        self.events.push(format!("after {pc:#04X} {op} {t_states}"));
+    }
+
+    fn on_read(&mut self, addr: u8, value: u8) {
+        self.events.push(format!("read {addr:#04X} {value}"));
+    }
+
+    fn on_write(&mut self, addr: u8, old: u8, new: u8) {
+        self.events.push(format!("write {addr:#04X} {old} {new}"));
+    }
+
+    fn on_push(&mut self, addr: u8, value: u8) {
+        self.events.push(format!("push {addr:#04X} {value}"));
+    }
+
+    fn on_pop(&mut self, addr: u8, value: u8) {
+        self.events.push(format!("pop {addr:#04X} {value}"));
+    }
+
+    fn on_halt(&mut self, _cpu: &CPU) {
+        self.events.push("halt".to_string());
+    }
+
+    fn on_error(&mut self, _cpu: &CPU, error: &EmulatorError) {
+        self.events.push(format!("error {error}"));
+    }
+}
+
+fn load(asm: &str) -> NanoCore {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano
+}
+
+#[test]
+fn test_event_order() {
+    let recorder = Rc::new(RefCell::new(Recorder::default()));
+    let mut nano = load(
+        "LDI R0 5
+         STORE R0 0x80
+         LDA R1 0x80
+         HLT",
+    );
+    nano.add_observer(recorder.clone());
+    nano.run().unwrap();
+
+    assert_eq!(
+        recorder.borrow().events,
+        [
+            "fetch 0x00 0x02",
+            "before 0x00 LDI",
+            "after 0x00 LDI 2",
+            "fetch 0x03 0x06",
+            "before 0x03 STORE",
+            "write 0x80 0 5",
+            "after 0x03 STORE 3",
+            "fetch 0x06 0x03",
+            "before 0x06 LDA",
+            "read 0x80 5",
+            "after 0x06 LDA 3",
+            "fetch 0x09 0x00",
+            "before 0x09 HLT",
+            "after 0x09 HLT 1",
+            "halt",
+        ]
+    );
+}
+
+#[test]
+fn test_stack_events() {
+    let mut nano = load(
+        "LDI R0 9
+         PUSH R0
+         POP R1
+         HLT",
+    );
+    nano.add_observer(Recorder::default());
+    nano.run().unwrap();
+
+    let events = &nano.observer::<Recorder>().unwrap().events;
+    assert!(events.contains(&"push 0xFF 9".to_string()));
+    assert!(events.contains(&"pop 0xFF 9".to_string()));
+}
+
+#[test]
+fn test_error_event() {
+    let mut nano = load(
+        "LDI R1 0
+         DIV R0 R1
+         HLT",
+    );
+    nano.add_observer(Recorder::default());
+    assert!(nano.run().is_err());
+
+    let events = &nano.observer::<Recorder>().unwrap().events;
+    assert!(events.last().unwrap().starts_with("error"));
+    assert!(!events.iter().any(|e| e.starts_with("after 0x03")));
+}
+
+#[test]
+fn test_observers_notified_in_order() {
+    let first = Rc::new(RefCell::new(Recorder::default()));
+    let second = Rc::new(RefCell::new(Recorder::default()));
+    let mut nano = load("HLT");
+    nano.add_observer(first.clone());
+    nano.add_observer(second.clone());
+    nano.run().unwrap();
+
+    assert_eq!(first.borrow().events, second.borrow().events);
+    assert!(nano.observer::<Recorder>().is_none());
+}
@@ -0,0 +1,91 @@
+use nanocore::{EmulatorError, assembler::Assembler, cpu::CPU, nanocore::NanoCore};
+
+/// Runs `asm` with R0 = `a` and R1 = `b` and returns the core.
+fn run(asm: &str, a: u8, b: u8) -> Result<NanoCore, EmulatorError> {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0)?;
+    nano.cpu.registers[0] = a;
+    nano.cpu.registers[1] = b;
+    nano.run()?;
+    Ok(nano)
+}
+
+const VALUES: [i8; 9] = [i8::MIN, -127, -64, -1, 0, 1, 64, 126, i8::MAX];
+
+#[test]
+fn test_overflow_matches_signed_arithmetic() {
+    for a in VALUES {
+        for b in VALUES {
+            for (op, expected) in [
+                ("ADD", a.checked_add(b).is_none()),
+                ("SUB", a.checked_sub(b).is_none()),
+                ("CMP", a.checked_sub(b).is_none()),
+            ] {
+                let nano = run(&format!("{op} R0 R1\nHLT"), a as u8, b as u8).unwrap();
+                assert_eq!(nano.cpu.get_flag(CPU::FLAG_V), expected, "{a} {op} {b}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_inc_dec_overflow() {
+    let nano = run("INC R0\nDEC R1\nHLT", 0x7F, 0x80).unwrap();
+    assert_eq!(nano.cpu.registers[0], 0x80);
+    assert_eq!(nano.cpu.registers[1], 0x7F);
+    // DEC ran last and overflowed.
+    assert!(nano.cpu.get_flag(CPU::FLAG_V));
+
+    let nano = run("DEC R1\nINC R0\nHLT", 1, 1).unwrap();
+    assert!(!nano.cpu.get_flag(CPU::FLAG_V));
+}
+
+#[test]
+fn test_signed_division() {
+    for a in VALUES {
+        for b in VALUES.into_iter().filter(|&b| b != 0) {
+            let nano = run("MOV R2 R0\nIDIV R0 R1\nIMOD R2 R1\nHLT", a as u8, b as u8).unwrap();
+            assert_eq!(nano.cpu.registers[0] as i8, a.wrapping_div(b), "{a} / {b}");
+            assert_eq!(nano.cpu.registers[2] as i8, a.wrapping_rem(b), "{a} % {b}");
+        }
+    }
+
+    let nano = run("IDIV R0 R1\nHLT", 0x80, 0xFF).unwrap();
+    assert_eq!(nano.cpu.registers[0], 0x80);
+    assert!(nano.cpu.get_flag(CPU::FLAG_V));
+
+    let nano = run("IMOD R0 R1\nHLT", 0x80, 0xFF).unwrap();
+    assert_eq!(nano.cpu.registers[0], 0);
+    assert!(nano.cpu.get_flag(CPU::FLAG_V));
+
+    assert!(matches!(
+        run("IDIV R0 R1\nHLT", 5, 0),
+        Err(EmulatorError::DivisionByZero { .. })
+    ));
+}
+
+#[test]
+fn test_jv_jnv() {
+    let asm = "ADD R0 R1
+               JV overflow
+               LDI R2 1
+               HLT
+               overflow:
+               LDI R2 2
+               JNV done
+               LDI R2 3
+               done:
+               HLT";
+
+    assert_eq!(run(asm, 100, 100).unwrap().cpu.registers[2], 3);
+    assert_eq!(run(asm, 100, 10).unwrap().cpu.registers[2], 1);
+}
+
+#[test]
+fn test_flag_names() {
+    let nano = run("ADD R0 R1\nHLT", 0x80, 0x80).unwrap();
+    assert_eq!(nano.cpu.flag_names(), "-V-CZ");
+}
@@ -0,0 +1,140 @@
+use nanocore::{assembler::Assembler, cpu::CPU, nanocore::NanoCore, profiler::Profiler};
+
+fn profile(asm: &str) -> (NanoCore, Assembler) {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano.add_observer(Profiler::new().with_labels(&assembler.labels));
+    (nano, assembler)
+}
+
+const NESTED: &str = "main:
+    LDI R0 4
+loop:
+    CALL outer
+    DEC R0
+    JNZ loop
+    HLT
+outer:
+    CALL inner
+    RET
+inner:
+    INC R1
+    RET";
+
+#[test]
+fn test_counts_match_the_core() {
+    let (mut nano, _) = profile(NESTED);
+    nano.run().unwrap();
+    let profiler = nano.observer::<Profiler>().unwrap();
+
+    // HLT does not count as a cycle.
+    assert_eq!(profiler.instructions(), nano.cycle + 1);
+    assert_eq!(profiler.total_t_states(), nano.t_states);
+    assert_eq!(profiler.hits[0x05], 4); // DEC R0
+    assert_eq!(profiler.symbol(0x05), "loop+2");
+    assert_eq!(profiler.symbol(0x0A), "outer");
+    assert_eq!(profiler.symbol(0x0B), "outer+1");
+}
+
+#[test]
+fn test_labels_and_subroutines() {
+    let (mut nano, _) = profile(NESTED);
+    nano.run().unwrap();
+    let profiler = nano.observer::<Profiler>().unwrap();
+
+    let labels = profiler.label_stats();
+    let inner = labels.iter().find(|s| s.name == "inner").unwrap();
+    assert_eq!((inner.addr, inner.count), (0x0D, 8));
+
+    let subroutines = profiler.subroutine_stats();
+    assert_eq!(subroutines[0].name, "outer");
+    assert_eq!(subroutines[0].count, 4);
+    let inner = &subroutines[1];
+    assert_eq!((inner.name.as_str(), inner.count), ("inner", 4));
+    // outer includes the time spent in inner.
+    assert!(subroutines[0].t_states > inner.t_states);
+
+    assert_eq!(
+        profiler.collapsed(),
+        "main 38\nmain;outer 32\nmain;outer;inner 20\n"
+    );
+    let total: u64 = profiler.stacks.values().sum();
+    assert_eq!(total, nano.t_states);
+}
+
+#[test]
+fn test_hot_blocks() {
+    let (mut nano, _) = profile(
+        "LDI R0 10
+         loop:
+         INC R1
+         DEC R0
+         JNZ loop
+         HLT",
+    );
+    nano.run().unwrap();
+    let profiler = nano.observer::<Profiler>().unwrap();
+
+    let hot = profiler.hot_blocks(1);
+    let (start, block) = hot[0];
+    // The first pass falls through from LDI, so the loop block is entered 9 times.
+    assert_eq!((start, block.end), (0x03, 0x07));
+    assert_eq!((block.entries, block.instructions), (9, 27));
+    assert_eq!(profiler.blocks[&0x00].instructions, 4);
+    assert!(profiler.report().contains("0x03-0x07 loop"));
+}
+
+#[test]
+fn test_annotated_source() {
+    let asm = "; count
+start:
+    LDI R0 2
+again:
+    DEC R0
+    JNZ again
+    HLT";
+    let (mut nano, assembler) = profile(asm);
+    assert_eq!(
+        assembler
+            .line_map
+            .iter()
+            .map(|(&a, &l)| (a, l))
+            .collect::<Vec<_>>(),
+        [(0x00, 3), (0x03, 5), (0x05, 6), (0x07, 7)]
+    );
+    nano.run().unwrap();
+
+    let annotated = nano
+        .observer::<Profiler>()
+        .unwrap()
+        .annotate(asm, &assembler.line_map);
+    let lines: Vec<_> = annotated.lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], "                   | ; count");
+    assert_eq!(lines[4], "       2         2 |     DEC R0");
+    assert_eq!(lines[6], "       1         1 |     HLT");
+}
+
+#[test]
+fn test_interrupts_are_frames() {
+    let (mut nano, _) = profile(
+        "start:
+         EI
+         spin:
+         JMP spin
+         handler:
+         INC R1
+         HLT",
+    );
+    nano.cpu.bus.write(CPU::IRQ_VECTOR, 0x03);
+    nano.cycle().unwrap();
+    nano.raise_irq();
+    nano.run().unwrap();
+
+    let profiler = nano.observer::<Profiler>().unwrap();
+    assert_eq!(profiler.calls[&0x03], 1);
+    assert!(profiler.collapsed().contains("start;handler "));
+}
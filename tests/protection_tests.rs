@@ -0,0 +1,104 @@
+use nanocore::{
+    EmulatorError, Op,
+    assembler::Assembler,
+    nanocore::NanoCore,
+    protection::{AccessKind, MemoryMap, Permissions, Region},
+};
+
+fn protected(asm: &str) -> NanoCore {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano.memory_map = Some(MemoryMap::standard(0, assembler.program.len()));
+    nano
+}
+
+#[test]
+fn test_write_to_code_is_rejected() {
+    let mut nano = protected(
+        "LDI R0 0xFF
+         STORE R0 0x00
+         HLT",
+    );
+
+    let err = nano.run().unwrap_err();
+    assert_eq!(
+        err,
+        EmulatorError::AccessViolation {
+            addr: 0x00,
+            pc: 0x03,
+            kind: AccessKind::Write,
+        }
+    );
+    assert_eq!(nano.cpu.bus.peek(0x00), u8::from(Op::LDI));
+}
+
+#[test]
+fn test_data_access_to_stack_is_rejected() {
+    let mut nano = protected(
+        "PUSH R0
+         LDA R1 0xFF
+         HLT",
+    );
+
+    let err = nano.run().unwrap_err();
+    assert!(matches!(
+        err,
+        EmulatorError::AccessViolation {
+            addr: 0xFF,
+            kind: AccessKind::Read,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_execute_outside_code_is_rejected() {
+    let mut nano = protected("JMP 0x40");
+
+    let err = nano.run().unwrap_err();
+    assert_eq!(
+        err,
+        EmulatorError::AccessViolation {
+            addr: 0x40,
+            pc: 0x40,
+            kind: AccessKind::Execute,
+        }
+    );
+}
+
+#[test]
+fn test_well_behaved_program_runs() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = protected(
+        "LDI R0 7
+         STORE R0 0x80
+         CALL sub
+         LDA R1 0x80
+         HLT
+         sub:
+         PUSH R0
+         POP R2
+         RET",
+    );
+    nano.cpu.bus.write(0x80, 0);
+
+    nano.run()?;
+    assert_eq!(nano.cpu.registers[1], 7);
+    assert_eq!(nano.cpu.registers[2], 7);
+    Ok(())
+}
+
+#[test]
+fn test_custom_regions() {
+    let mut map = MemoryMap::default();
+    map.regions
+        .push(Region::new("rom", 0x80, 0x8F, Permissions::READ));
+
+    assert!(map.allows(0x80, AccessKind::Read));
+    assert!(!map.allows(0x80, AccessKind::Write));
+    assert!(map.allows(0x90, AccessKind::Write));
+    assert_eq!(map.region(0x85).map(|r| r.name.as_str()), Some("rom"));
+    assert_eq!(map.permissions(0x85).to_string(), "r---");
+}
@@ -0,0 +1,97 @@
+use nanocore::{
+    EmulatorError,
+    assembler::Assembler,
+    nanocore::{NanoCore, StopReason},
+};
+
+fn load(asm: &str) -> NanoCore {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano
+}
+
+#[test]
+fn test_run_for() {
+    let mut nano = load(
+        "INC R0
+         INC R0
+         INC R0
+         HLT",
+    );
+
+    assert_eq!(nano.run_for(2), StopReason::Stepped);
+    assert_eq!(nano.cpu.registers[0], 2);
+    assert_eq!(nano.cycle, 2);
+
+    assert_eq!(nano.run_for(10), StopReason::Halted);
+    assert_eq!(nano.cpu.registers[0], 3);
+}
+
+#[test]
+fn test_breakpoint_stops_and_resumes() {
+    let mut nano = load(
+        "loop:
+         INC R0
+         JMP loop",
+    );
+    nano.breakpoints.push(0x02);
+
+    assert_eq!(
+        nano.run_until(|_| false),
+        StopReason::Breakpoint { pc: 0x02 }
+    );
+    assert_eq!(nano.cpu.registers[0], 1);
+
+    assert_eq!(
+        nano.run_until(|_| false),
+        StopReason::Breakpoint { pc: 0x02 }
+    );
+    assert_eq!(nano.cpu.registers[0], 2);
+}
+
+#[test]
+fn test_cycle_limit_is_configurable() {
+    let mut assembler = Assembler::default();
+    assembler
+        .assemble(
+            "loop:
+             JMP loop",
+        )
+        .unwrap();
+
+    let mut nano = NanoCore::new().with_max_cycles(5000);
+    nano.load_program(&assembler.program, 0).unwrap();
+
+    assert_eq!(nano.run_until(|_| false), StopReason::CycleLimit);
+    assert_eq!(nano.cycle, 5000);
+    assert!(!nano.cpu.is_halted);
+}
+
+#[test]
+fn test_cycle_counter_exceeds_16_bits() {
+    let mut nano = load(
+        "loop:
+         JMP loop",
+    );
+    nano.max_cycles = 70_000;
+
+    assert_eq!(nano.run_for(u64::MAX), StopReason::CycleLimit);
+    assert_eq!(nano.cycle, 70_000);
+}
+
+#[test]
+fn test_error_stop_reason() {
+    let mut nano = load(
+        "LDI R1 0
+         DIV R0 R1
+         HLT",
+    );
+
+    assert!(matches!(
+        nano.run_until(|_| false),
+        StopReason::Error(EmulatorError::DivisionByZero { .. })
+    ));
+}
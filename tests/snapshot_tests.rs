@@ -0,0 +1,103 @@
+use nanocore::{
+    FormatError,
+    assembler::Assembler,
+    devices::{self, Screen, Timer},
+    io::BufferIo,
+    nanocore::NanoCore,
+    snapshot::Snapshot,
+};
+
+fn load(nano: &mut NanoCore, asm: &str) {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+    nano.load_program(&assembler.program, 0).unwrap();
+}
+
+#[test]
+fn test_resume_matches_uninterrupted_run() -> Result<(), Box<dyn std::error::Error>> {
+    let program = "LDI R0 0
+                   LDI R1 1
+                   LDI R2 7
+                   loop:
+                   PRINT R1
+                   ADD R0 R1
+                   PUSH R0
+                   POP R3
+                   DEC R2
+                   JNZ loop
+                   HLT";
+
+    let mut full = NanoCore::new().with_io(BufferIo::default());
+    load(&mut full, program);
+    full.run()?;
+
+    let mut first = NanoCore::new().with_io(BufferIo::default());
+    load(&mut first, program);
+    first.run_for(10);
+    let bytes = first.snapshot().to_bytes();
+
+    let mut resumed = NanoCore::new().with_io(BufferIo::default());
+    resumed.restore(&Snapshot::from_bytes(&bytes)?);
+    resumed.run()?;
+
+    assert_eq!(resumed.snapshot(), full.snapshot());
+    Ok(())
+}
+
+#[test]
+fn test_device_state_is_restored() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    devices::map_standard(&mut nano.cpu.bus)?;
+    nano.cpu.bus.write(Screen::BASE, b'X');
+    nano.cpu.bus.write(Timer::BASE + 1, 9);
+    let snapshot = nano.snapshot();
+
+    let mut other = NanoCore::new();
+    devices::map_standard(&mut other.cpu.bus)?;
+    other.restore(&snapshot);
+
+    let bus = &other.cpu.bus;
+    assert_eq!(bus.device::<Screen>().unwrap().char_at(0, 0), b'X');
+    assert_eq!(bus.device::<Timer>().unwrap().reload, 9);
+    Ok(())
+}
+
+#[test]
+fn test_save_and_load_file() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    nano.cpu.registers[5] = 42;
+    nano.output.push_str("hello");
+
+    let path = std::env::temp_dir().join("nanocore_snapshot_test.ncs");
+    nano.snapshot().save(&path)?;
+    let loaded = Snapshot::load(&path)?;
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(loaded.registers[5], 42);
+    assert_eq!(loaded.output, "hello");
+    Ok(())
+}
+
+#[test]
+fn test_rejects_bad_files() {
+    let bytes = NanoCore::new().snapshot().to_bytes();
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    assert!(matches!(
+        Snapshot::from_bytes(&bad_magic),
+        Err(FormatError::BadMagic { .. })
+    ));
+
+    let mut bad_version = bytes.clone();
+    bad_version[4] = 99;
+    assert_eq!(
+        Snapshot::from_bytes(&bad_version),
+        Err(FormatError::UnsupportedVersion { version: 99 })
+    );
+
+    assert!(matches!(
+        Snapshot::from_bytes(&bytes[..100]),
+        Err(FormatError::Truncated { offset: 27, .. })
+    ));
+}
@@ -0,0 +1,171 @@
+use nanocore::{
+    EmulatorError,
+    assembler::Assembler,
+    cpu::CPU,
+    loader::Executable,
+    nanocore::{NanoCore, StopReason},
+    system::{Mailbox, Schedule, SharedMemory, System, SystemStop},
+    watch::Watchpoint,
+};
+
+fn assemble(asm: &str) -> Vec<u8> {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+    assembler.program
+}
+
+fn load(programs: &[&str], schedule: Schedule) -> System {
+    let mut system = System::new(programs.len()).with_schedule(schedule);
+    for (core, asm) in system.cores.iter_mut().zip(programs) {
+        core.load_program(&assemble(asm), 0).unwrap();
+    }
+    system
+}
+
+const SPINLOCK: &str = include_str!("../programs/spinlock.nca");
+
+#[test]
+fn test_tas() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = NanoCore::new();
+    nano.load_program(&assemble("TAS R0 0x80\nTAS R1 0x80\nHLT"), 0)?;
+    nano.cycle()?;
+    assert_eq!(nano.cpu.registers[0], 0);
+    assert!(nano.cpu.get_flag(CPU::FLAG_Z));
+    nano.cycle()?;
+    assert_eq!(nano.cpu.registers[1], 1);
+    assert!(!nano.cpu.get_flag(CPU::FLAG_Z));
+    assert_eq!(nano.cpu.bus.read(0x80), 1);
+    Ok(())
+}
+
+#[test]
+fn test_spinlock_under_both_schedules() {
+    for schedule in [
+        Schedule::Lockstep,
+        Schedule::RoundRobin { quantum: 1 },
+        Schedule::RoundRobin { quantum: 3 },
+        Schedule::RoundRobin { quantum: 100 },
+    ] {
+        let mut system = load(&[SPINLOCK, SPINLOCK, SPINLOCK], schedule);
+        assert_eq!(system.run(), SystemStop::Halted, "{schedule:?}");
+
+        let shared = system.shared.bytes.borrow();
+        assert_eq!(shared[1], 30, "{schedule:?}");
+        assert_eq!(shared[0], 0, "{schedule:?}");
+    }
+}
+
+#[test]
+fn test_scheduling() {
+    let spin = "loop:\nINC R0\nJMP loop";
+
+    let mut lockstep = load(&[spin, spin, spin], Schedule::Lockstep);
+    assert_eq!(lockstep.run_for(4), SystemStop::Stepped);
+    assert!(lockstep.cores.iter().all(|core| core.cycle == 4));
+
+    let mut round_robin = load(&[spin, spin, spin], Schedule::RoundRobin { quantum: 2 });
+    round_robin.run_for(5);
+    let cycles: Vec<_> = round_robin.cores.iter().map(|core| core.cycle).collect();
+    assert_eq!(cycles, [2, 2, 1]);
+    assert_eq!(round_robin.current, 2);
+
+    // Halted cores are skipped.
+    let mut mixed = load(&["HLT", spin], Schedule::RoundRobin { quantum: 1 });
+    mixed.run_for(4);
+    assert!(mixed.cores[0].cpu.is_halted);
+    assert_eq!(mixed.cores[1].cycle, 3);
+}
+
+#[test]
+fn test_mailbox() {
+    let sender = "LDI R0 1
+                  STORE R0 0x4E
+                  LDI R0 42
+                  STORE R0 0x4D
+                  LDI R0 43
+                  STORE R0 0x4D
+                  HLT";
+    let receiver = "wait:
+                    LDA R0 0x4E
+                    JZ wait
+                    LDA R1 0x4D
+                    LDA R2 0x4D
+                    LDA R3 0x4F
+                    HLT";
+
+    let mut system = load(&[sender, receiver], Schedule::Lockstep);
+    assert_eq!(system.run(), SystemStop::Halted);
+    assert_eq!(system.cores[1].cpu.registers[1..=3], [42, 43, 1]);
+    assert_eq!(system.cores[0].cpu.bus.peek(Mailbox::BASE + 2), 0);
+    assert!(
+        system
+            .post
+            .borrow()
+            .inboxes
+            .iter()
+            .all(|inbox| inbox.is_empty())
+    );
+
+    // Sends past the capacity, or to a core that does not exist, are dropped.
+    let flood = "LDI R0 1
+                 STORE R0 0x4E
+                 LDI R1 20
+                 loop:
+                 STORE R1 0x4D
+                 DEC R1
+                 JNZ loop
+                 LDI R0 9
+                 STORE R0 0x4E
+                 STORE R0 0x4D
+                 HLT";
+    let mut system = load(&[flood, "HLT"], Schedule::Lockstep);
+    system.run();
+    assert_eq!(system.post.borrow().inboxes[1].len(), 16);
+    assert_eq!(system.cores[1].cpu.bus.peek(Mailbox::BASE + 1), 16);
+}
+
+#[test]
+fn test_core_stops_are_reported() {
+    let mut system = load(&[SPINLOCK, SPINLOCK], Schedule::Lockstep);
+    system.cores[1].watchpoints = vec![Watchpoint::Write(SharedMemory::BASE + 1)];
+
+    let SystemStop::Core {
+        core: 1,
+        reason: StopReason::Watchpoint(hit),
+    } = system.run()
+    else {
+        panic!("expected a watchpoint on core 1");
+    };
+    assert_eq!(hit.new, 2);
+
+    system.cores[1].watchpoints.clear();
+    assert_eq!(system.run(), SystemStop::Halted);
+    assert_eq!(system.shared.bytes.borrow()[1], 20);
+}
+
+#[test]
+fn test_system_programs() {
+    // Each core calls into its bank, which writes the core index to shared memory.
+    let mut assembler = Assembler::default();
+    assembler
+        .assemble("CALL 0x60\nHLT\n.BANK 0\nLDA R0 0x4F\nINC R0\nSTORE R0 0x50\nRET")
+        .unwrap();
+    let banked = Executable::from_assembler(&assembler);
+
+    let mut system = System::new(2);
+    system.load(0, &banked).unwrap();
+    system.load(1, &banked).unwrap();
+    assert_eq!(system.run(), SystemStop::Halted);
+    assert_eq!(system.shared.bytes.borrow()[0], 2);
+
+    // Programs must end before the mailbox.
+    let large = Executable::from_raw(vec![0x01; Mailbox::BASE as usize + 1]);
+    assert_eq!(
+        system.load(0, &large),
+        Err(EmulatorError::ProgramTooLarge {
+            size: Mailbox::BASE as usize + 1,
+            start: 0x00,
+            max: Mailbox::BASE as usize,
+        })
+    );
+}
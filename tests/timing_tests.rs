@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+use nanocore::{Op, assembler::Assembler, nanocore::NanoCore, timing::Timing};
+
+fn load(asm: &str) -> NanoCore {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano
+}
+
+#[test]
+fn test_costs_follow_timing_table() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = load(
+        "LDI R0 6
+         LDI R1 7
+         MUL R0 R1
+         STORE R0 0x80
+         HLT",
+    );
+    nano.run()?;
+
+    let timing = Timing::default();
+    let expected = 2 * timing.cost(Op::LDI)
+        + timing.cost(Op::MUL)
+        + timing.cost(Op::STORE)
+        + timing.cost(Op::HLT);
+    assert_eq!(nano.cycle, 4);
+    assert_eq!(nano.t_states, expected);
+    Ok(())
+}
+
+#[test]
+fn test_taken_branch_penalty() -> Result<(), Box<dyn std::error::Error>> {
+    // The first JNZ falls through, the second is taken.
+    let mut not_taken = load(
+        "LDI R0 0
+         JNZ end
+         end:
+         HLT",
+    );
+    not_taken.run()?;
+
+    let mut taken = load(
+        "LDI R0 1
+         JNZ end
+         end:
+         HLT",
+    );
+    taken.run()?;
+
+    assert_eq!(
+        taken.t_states,
+        not_taken.t_states + Timing::default().branch_taken_penalty
+    );
+    Ok(())
+}
+
+#[test]
+fn test_uniform_timing_matches_cycles() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = load(
+        "LDI R0 3
+         loop:
+         DEC R0
+         JNZ loop
+         HLT",
+    );
+    nano.timing = Timing::uniform();
+    nano.run()?;
+
+    // HLT is not counted as a cycle but does take a T-state.
+    assert_eq!(nano.t_states, nano.cycle + 1);
+    Ok(())
+}
+
+#[test]
+fn test_clock_throttling() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = load(
+        "LDI R0 10
+         loop:
+         DEC R0
+         JNZ loop
+         HLT",
+    )
+    .with_clock_hz(1000);
+
+    let start = Instant::now();
+    nano.run()?;
+
+    // 2 + 10 * (1 + 3) + 9 + 1 = 52 T-states at 1 kHz.
+    assert_eq!(nano.t_states, 52);
+    assert!(start.elapsed() >= Duration::from_millis(45));
+    Ok(())
+}
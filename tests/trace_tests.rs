@@ -0,0 +1,151 @@
+use nanocore::{
+    Op,
+    assembler::Assembler,
+    cpu::CPU,
+    nanocore::{NanoCore, Operands},
+    snapshot::Snapshot,
+    trace::{MemRead, MemWrite},
+};
+
+fn load(asm: &str) -> NanoCore {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano
+}
+
+#[test]
+fn test_memory_accesses_are_recorded() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = load(
+        "LDI R0 7
+         STORE R0 0x80
+         LDA R1 0x80
+         CALL sub
+         HLT
+         sub:
+         RET",
+    );
+    nano.run()?;
+
+    let log = &nano.instruction_log;
+    assert_eq!(
+        log[1].writes.as_slice(),
+        [MemWrite {
+            addr: 0x80,
+            old: 0,
+            new: 7
+        }]
+    );
+    assert_eq!(
+        log[2].reads.as_slice(),
+        [MemRead {
+            addr: 0x80,
+            value: 7
+        }]
+    );
+    assert_eq!(log[2].registers_after[1], 7);
+
+    // CALL pushes the return address, RET pops it.
+    assert_eq!(log[3].op, Some(Op::CALL));
+    assert_eq!(log[3].writes.as_slice()[0].new, 0x0B);
+    assert_eq!(log[4].op, Some(Op::RET));
+    assert_eq!(log[4].reads.as_slice()[0].value, 0x0B);
+    assert_eq!(log[4].next_pc, 0x0B);
+
+    let hlt = nano.current_instruction.unwrap();
+    assert_eq!(hlt.op, Some(Op::HLT));
+    assert_eq!(hlt.cycle, 5);
+    Ok(())
+}
+
+#[test]
+fn test_branches() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = load(
+        "LDI R0 1
+         JZ end
+         JNZ end
+         end:
+         HLT",
+    );
+    nano.run()?;
+
+    let skipped = nano.instruction_log[1];
+    assert_eq!(skipped.operands, Operands::Addr(0x07));
+    assert!(!skipped.branch_taken);
+    assert!(skipped.skipped());
+    assert_eq!(skipped.next_pc, 0x05);
+    assert_eq!(skipped.to_string(), "JZ    0x07    | Z(0) (SKIP)");
+
+    let taken = nano.instruction_log[2];
+    assert!(taken.branch_taken);
+    assert!(!taken.skipped());
+    assert_eq!(taken.next_pc, 0x07);
+    Ok(())
+}
+
+#[test]
+fn test_interrupt_entry() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = load(
+        "EI
+         INC R1
+         HLT
+         handler:
+         RETI",
+    );
+    nano.cpu.bus.write(CPU::IRQ_VECTOR, 0x04);
+    nano.cycle()?;
+    nano.raise_irq();
+    nano.cycle()?;
+
+    let entry = nano.current_instruction.unwrap();
+    assert!(entry.is_interrupt());
+    assert_eq!(entry.mnemonic(), "IRQ");
+    assert_eq!((entry.pc, entry.next_pc), (0x01, 0x04));
+    assert_eq!(entry.writes.len(), 2);
+    assert_eq!(entry.to_string(), "IRQ   0x04    | from 0x01");
+    Ok(())
+}
+
+#[test]
+fn test_log_is_bounded_and_journaled() {
+    let mut nano = load(
+        "loop:
+         INC R0
+         JMP loop",
+    )
+    .with_journal(16);
+    nano.run_for(NanoCore::LOG_CAPACITY as u64 + 10);
+
+    assert_eq!(nano.instruction_log.len(), NanoCore::LOG_CAPACITY);
+    let log_before: Vec<_> = nano.instruction_log.iter().copied().collect();
+    let current_before = nano.current_instruction;
+
+    nano.cycle().unwrap();
+    assert!(nano.step_back());
+
+    assert_eq!(nano.current_instruction, current_before);
+    assert!(nano.instruction_log.iter().eq(log_before.iter()));
+}
+
+#[test]
+fn test_snapshot_keeps_trace() -> Result<(), Box<dyn std::error::Error>> {
+    let mut nano = load(
+        "LDI R0 3
+         PUSH R0
+         JZ 0x00
+         HLT",
+    );
+    nano.run()?;
+
+    let snapshot = Snapshot::from_bytes(&nano.snapshot().to_bytes())?;
+    assert_eq!(snapshot.current_instruction, nano.current_instruction);
+    assert!(
+        snapshot
+            .instruction_log
+            .iter()
+            .eq(nano.instruction_log.iter())
+    );
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use nanocore::{
+    assembler::Assembler,
+    cpu::CPU,
+    nanocore::{NanoCore, StopReason},
+    watch::{WatchHit, Watchpoint},
+};
+
+fn load(asm: &str, watchpoints: &[&str]) -> NanoCore {
+    let mut assembler = Assembler::default();
+    assembler.assemble(asm).unwrap();
+
+    let mut nano = NanoCore::new();
+    nano.load_program(&assembler.program, 0).unwrap();
+    nano.watchpoints = watchpoints.iter().map(|w| w.parse().unwrap()).collect();
+    nano
+}
+
+fn hit(reason: StopReason) -> WatchHit {
+    match reason {
+        StopReason::Watchpoint(hit) => hit,
+        reason => panic!("expected a watchpoint, got {reason:?}"),
+    }
+}
+
+#[test]
+fn test_memory_watchpoints() {
+    let asm = "LDI R0 7
+               STORE R0 0x80
+               LDA R1 0x80
+               HLT";
+
+    let mut nano = load(asm, &["read:0x80"]);
+    let read = hit(nano.run_until(|_| false));
+    assert_eq!((read.pc, read.old, read.new), (0x06, 7, 7));
+    assert_eq!(nano.cpu.registers[1], 7);
+
+    let mut nano = load(asm, &["access:0x80"]);
+    let write = hit(nano.run_until(|_| false));
+    assert_eq!((write.pc, write.old, write.new), (0x03, 0, 7));
+    // Resuming continues past the write to the read.
+    assert_eq!(hit(nano.run_until(|_| false)).pc, 0x06);
+    assert_eq!(nano.run_until(|_| false), StopReason::Halted);
+}
+
+#[test]
+fn test_register_watchpoints() {
+    let asm = "loop:
+               INC R3
+               JMP loop";
+
+    let mut nano = load(asm, &["R3=0x05"]);
+    let value = hit(nano.run_until(|_| false));
+    assert_eq!((value.old, value.new), (4, 5));
+    assert_eq!(nano.cpu.registers[3], 5);
+
+    // A value watchpoint fires when the register becomes the value, not while it stays there.
+    let mut nano = load("LDI R3 5\nLDI R3 5\nHLT", &["R3=5"]);
+    assert_eq!(hit(nano.step()).pc, 0x00);
+    assert_eq!(nano.step(), StopReason::Stepped);
+
+    let mut nano = load("LDI R3 0\nMOV R3 R3\nINC R2\nHLT", &["R3"]);
+    assert_eq!(nano.run_until(|_| false), StopReason::Halted);
+}
+
+#[test]
+fn test_flag_watchpoint_and_run() {
+    let mut nano = load(
+        "LDI R0 1
+         DEC R0
+         INC R0
+         HLT",
+        &["flag:Z"],
+    );
+    let set = hit(nano.run_for(10));
+    assert_eq!((set.pc, set.old, set.new), (0x03, 0, 1));
+    let cleared = hit(nano.run_for(10));
+    assert_eq!((cleared.pc, cleared.old, cleared.new), (0x05, 1, 0));
+
+    // `run` stops too.
+    let mut nano = load("INC R0\nINC R0\nHLT", &["R0=2"]);
+    nano.run().unwrap();
+    assert!(!nano.cpu.is_halted);
+    assert_eq!(nano.cpu.pc, 0x04);
+}
+
+#[test]
+fn test_stack_accesses_trigger_watchpoints() {
+    let mut nano = load(
+        "LDI R0 9
+         PUSH R0
+         POP R1
+         HLT",
+        &["write:0xFF"],
+    );
+    let push = hit(nano.run_until(|_| false));
+    assert_eq!((push.pc, push.new), (0x03, 9));
+
+    nano.watchpoints = vec![Watchpoint::Read(CPU::STACK_MAX)];
+    assert_eq!(hit(nano.run_until(|_| false)).pc, 0x05);
+}
+
+#[test]
+fn test_parse_and_display() {
+    for (spec, watchpoint) in [
+        ("read:0x80", Watchpoint::Read(0x80)),
+        ("write:0x0A", Watchpoint::Write(0x0A)),
+        ("access:0xFF", Watchpoint::Access(0xFF)),
+        ("R3", Watchpoint::Register(3)),
+        ("R15=0x10", Watchpoint::RegisterValue(15, 0x10)),
+        ("flag:V", Watchpoint::Flag(CPU::FLAG_V)),
+    ] {
+        assert_eq!(spec.parse::<Watchpoint>(), Ok(watchpoint));
+        assert_eq!(watchpoint.to_string(), spec);
+    }
+
+    assert_eq!("read:128".parse(), Ok(Watchpoint::Read(0x80)));
+    assert_eq!("r2=16".parse(), Ok(Watchpoint::RegisterValue(2, 16)));
+    assert_eq!("flag:z".parse(), Ok(Watchpoint::Flag(CPU::FLAG_Z)));
+    for bad in ["R16", "read:0x100", "flag:Q", "jump:0x10", ""] {
+        assert!(bad.parse::<Watchpoint>().is_err(), "{bad}");
+    }
+}